@@ -7221,10 +7221,18 @@ rec {
           "Stackable GmbH <info@stackable.tech>"
         ];
         dependencies = [
+          {
+            name = "base64";
+            packageId = "base64 0.22.1";
+          }
           {
             name = "indoc";
             packageId = "indoc";
           }
+          {
+            name = "regex";
+            packageId = "regex";
+          }
           {
             name = "semver";
             packageId = "semver";
@@ -0,0 +1,192 @@
+package tech.stackable.kafka.security;
+
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.Hashtable;
+import java.util.List;
+import java.util.Map;
+import javax.naming.AuthenticationException;
+import javax.naming.Context;
+import javax.naming.NamingEnumeration;
+import javax.naming.NamingException;
+import javax.naming.directory.DirContext;
+import javax.naming.directory.InitialDirContext;
+import javax.naming.directory.SearchControls;
+import javax.naming.directory.SearchResult;
+import javax.security.auth.callback.Callback;
+import javax.security.auth.callback.NameCallback;
+import javax.security.auth.callback.UnsupportedCallbackException;
+import javax.security.auth.login.AppConfigurationEntry;
+import org.apache.kafka.common.KafkaException;
+import org.apache.kafka.common.security.auth.AuthenticateCallbackHandler;
+import org.apache.kafka.common.security.plain.PlainAuthenticateCallback;
+import org.apache.kafka.common.security.plain.PlainLoginModule;
+import org.slf4j.Logger;
+import org.slf4j.LoggerFactory;
+
+/**
+ * Verifies SASL/PLAIN logins against an LDAP server.
+ *
+ * <p>The user is searched with the bind credentials below the search base and authenticated by
+ * binding with its DN and the given password. The options are taken from the JAAS configuration
+ * of the listener, which is written by the operator:
+ *
+ * <ul>
+ *   <li>{@code ldap.url}: the URL of the LDAP server, e.g. {@code ldaps://openldap:636}
+ *   <li>{@code ldap.search.base}: the DN the users are searched below
+ *   <li>{@code ldap.search.filter}: an additional filter the users have to match (optional)
+ *   <li>{@code ldap.uid.attribute}: the attribute which contains the user name
+ *   <li>{@code ldap.bind.credentials.dir}: a directory with the files {@code user} and {@code
+ *       password} to search the users with (optional, anonymous otherwise)
+ *   <li>{@code ldap.truststore.location}: a PEM file with the CA of the LDAP server (optional)
+ * </ul>
+ */
+public class LdapPlainServerCallbackHandler implements AuthenticateCallbackHandler {
+  private static final Logger LOG = LoggerFactory.getLogger(LdapPlainServerCallbackHandler.class);
+
+  static final String URL = "ldap.url";
+  static final String SEARCH_BASE = "ldap.search.base";
+  static final String SEARCH_FILTER = "ldap.search.filter";
+  static final String UID_ATTRIBUTE = "ldap.uid.attribute";
+  static final String BIND_CREDENTIALS_DIR = "ldap.bind.credentials.dir";
+  static final String TRUSTSTORE_LOCATION = "ldap.truststore.location";
+
+  private String url;
+  private String searchBase;
+  private String searchFilter;
+  private Path bindCredentialsDir;
+  private boolean customTruststore;
+
+  @Override
+  public void configure(
+      Map<String, ?> configs, String saslMechanism, List<AppConfigurationEntry> jaasConfigEntries) {
+    Map<String, ?> options =
+        jaasConfigEntries.stream()
+            .filter(entry -> PlainLoginModule.class.getName().equals(entry.getLoginModuleName()))
+            .findFirst()
+            .orElseThrow(() -> new KafkaException("No JAAS configuration for PLAIN found"))
+            .getOptions();
+
+    url = requiredOption(options, URL);
+    searchBase = requiredOption(options, SEARCH_BASE);
+    String uidAttribute = requiredOption(options, UID_ATTRIBUTE);
+    String additionalFilter = (String) options.get(SEARCH_FILTER);
+    // The user name is passed as filter argument, so it is escaped by JNDI
+    searchFilter =
+        additionalFilter == null || additionalFilter.isEmpty()
+            ? String.format("(%s={0})", uidAttribute)
+            : String.format("(&(%s={0})%s)", uidAttribute, additionalFilter);
+
+    String bindCredentials = (String) options.get(BIND_CREDENTIALS_DIR);
+    bindCredentialsDir = bindCredentials == null ? null : Path.of(bindCredentials);
+
+    String truststoreLocation = (String) options.get(TRUSTSTORE_LOCATION);
+    if (truststoreLocation != null) {
+      PemSslSocketFactory.configure(Path.of(truststoreLocation));
+      customTruststore = true;
+    }
+  }
+
+  @Override
+  public void handle(Callback[] callbacks) throws IOException, UnsupportedCallbackException {
+    String username = null;
+    for (Callback callback : callbacks) {
+      if (callback instanceof NameCallback) {
+        username = ((NameCallback) callback).getDefaultName();
+      } else if (callback instanceof PlainAuthenticateCallback) {
+        PlainAuthenticateCallback plainCallback = (PlainAuthenticateCallback) callback;
+        plainCallback.authenticated(authenticate(username, plainCallback.password()));
+      } else {
+        throw new UnsupportedCallbackException(callback);
+      }
+    }
+  }
+
+  @Override
+  public void close() {}
+
+  private boolean authenticate(String username, char[] password) throws IOException {
+    // An empty password would result in an unauthenticated bind, which succeeds
+    if (username == null || username.isEmpty() || password == null || password.length == 0) {
+      return false;
+    }
+
+    try {
+      String userDn = searchUser(username);
+      if (userDn == null) {
+        LOG.debug("User [{}] not found below [{}]", username, searchBase);
+        return false;
+      }
+      DirContext userContext = new InitialDirContext(environment(userDn, new String(password)));
+      userContext.close();
+      return true;
+    } catch (AuthenticationException e) {
+      LOG.debug("Bind of user [{}] failed", username, e);
+      return false;
+    } catch (NamingException e) {
+      throw new IOException("Failed to authenticate user [" + username + "] with LDAP", e);
+    }
+  }
+
+  private String searchUser(String username) throws IOException, NamingException {
+    Hashtable<String, String> environment =
+        bindCredentialsDir == null
+            ? environment(null, null)
+            : environment(readBindCredential("user"), readBindCredential("password"));
+
+    SearchControls controls = new SearchControls();
+    controls.setSearchScope(SearchControls.SUBTREE_SCOPE);
+    controls.setReturningAttributes(new String[0]);
+    // a second result is only fetched to detect ambiguous user names
+    controls.setCountLimit(2);
+
+    DirContext context = new InitialDirContext(environment);
+    try {
+      NamingEnumeration<SearchResult> results =
+          context.search(searchBase, searchFilter, new Object[] {username}, controls);
+      if (!results.hasMore()) {
+        return null;
+      }
+      String userDn = results.next().getNameInNamespace();
+      if (results.hasMore()) {
+        LOG.warn("User name [{}] is ambiguous below [{}]", username, searchBase);
+        return null;
+      }
+      return userDn;
+    } finally {
+      context.close();
+    }
+  }
+
+  private Hashtable<String, String> environment(String principal, String credentials) {
+    Hashtable<String, String> environment = new Hashtable<>();
+    environment.put(Context.INITIAL_CONTEXT_FACTORY, "com.sun.jndi.ldap.LdapCtxFactory");
+    environment.put(Context.PROVIDER_URL, url);
+    if (principal != null) {
+      environment.put(Context.SECURITY_AUTHENTICATION, "simple");
+      environment.put(Context.SECURITY_PRINCIPAL, principal);
+      environment.put(Context.SECURITY_CREDENTIALS, credentials);
+    } else {
+      environment.put(Context.SECURITY_AUTHENTICATION, "none");
+    }
+    if (customTruststore) {
+      environment.put("java.naming.ldap.factory.socket", PemSslSocketFactory.class.getName());
+    }
+    return environment;
+  }
+
+  // The bind credentials are read for every login, so that rotated credentials are picked up
+  private String readBindCredential(String file) throws IOException {
+    return Files.readString(bindCredentialsDir.resolve(file), StandardCharsets.UTF_8).trim();
+  }
+
+  private static String requiredOption(Map<String, ?> options, String name) {
+    Object value = options.get(name);
+    if (value == null || value.toString().isEmpty()) {
+      throw new KafkaException("The JAAS option [" + name + "] is required");
+    }
+    return value.toString();
+  }
+}
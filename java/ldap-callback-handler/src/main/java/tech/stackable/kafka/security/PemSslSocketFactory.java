@@ -0,0 +1,80 @@
+package tech.stackable.kafka.security;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.net.InetAddress;
+import java.net.Socket;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.security.GeneralSecurityException;
+import java.security.KeyStore;
+import java.security.cert.Certificate;
+import java.security.cert.CertificateFactory;
+import javax.net.SocketFactory;
+import javax.net.ssl.SSLContext;
+import javax.net.ssl.SSLSocketFactory;
+import javax.net.ssl.TrustManagerFactory;
+import org.apache.kafka.common.KafkaException;
+
+/**
+ * An SSL socket factory which trusts the CA certificates of a PEM file.
+ *
+ * <p>JNDI instantiates the factory by its class name (see {@code java.naming.ldap.factory.socket}),
+ * so the trusted certificates are configured statically.
+ */
+public class PemSslSocketFactory extends SocketFactory {
+  private static volatile SSLSocketFactory delegate;
+
+  static void configure(Path caCertificates) {
+    try (InputStream input = Files.newInputStream(caCertificates)) {
+      KeyStore trustStore = KeyStore.getInstance(KeyStore.getDefaultType());
+      trustStore.load(null, null);
+      int index = 0;
+      for (Certificate certificate :
+          CertificateFactory.getInstance("X.509").generateCertificates(input)) {
+        trustStore.setCertificateEntry("ca-" + index++, certificate);
+      }
+
+      TrustManagerFactory trustManagerFactory =
+          TrustManagerFactory.getInstance(TrustManagerFactory.getDefaultAlgorithm());
+      trustManagerFactory.init(trustStore);
+      SSLContext sslContext = SSLContext.getInstance("TLS");
+      sslContext.init(null, trustManagerFactory.getTrustManagers(), null);
+      delegate = sslContext.getSocketFactory();
+    } catch (IOException | GeneralSecurityException e) {
+      throw new KafkaException("Failed to load the CA certificates from " + caCertificates, e);
+    }
+  }
+
+  /** Called by JNDI to obtain the factory. */
+  public static SocketFactory getDefault() {
+    return new PemSslSocketFactory();
+  }
+
+  @Override
+  public Socket createSocket() throws IOException {
+    return delegate.createSocket();
+  }
+
+  @Override
+  public Socket createSocket(String host, int port) throws IOException {
+    return delegate.createSocket(host, port);
+  }
+
+  @Override
+  public Socket createSocket(String host, int port, InetAddress localHost, int localPort)
+      throws IOException {
+    return delegate.createSocket(host, port, localHost, localPort);
+  }
+
+  @Override
+  public Socket createSocket(InetAddress host, int port) throws IOException {
+    return delegate.createSocket(host, port);
+  }
+
+  @Override
+  public Socket createSocket(InetAddress address, int port, InetAddress localAddress, int localPort)
+      throws IOException {
+    return delegate.createSocket(address, port, localAddress, localPort);
+  }
+}
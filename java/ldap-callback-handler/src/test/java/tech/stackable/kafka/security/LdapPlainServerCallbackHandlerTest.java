@@ -0,0 +1,174 @@
+package tech.stackable.kafka.security;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+
+import com.unboundid.ldap.listener.InMemoryDirectoryServer;
+import com.unboundid.ldap.listener.InMemoryDirectoryServerConfig;
+import com.unboundid.ldap.sdk.OperationType;
+import java.io.IOException;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+import javax.security.auth.callback.Callback;
+import javax.security.auth.callback.NameCallback;
+import javax.security.auth.login.AppConfigurationEntry;
+import javax.security.auth.login.AppConfigurationEntry.LoginModuleControlFlag;
+import org.apache.kafka.common.KafkaException;
+import org.apache.kafka.common.security.plain.PlainAuthenticateCallback;
+import org.apache.kafka.common.security.plain.PlainLoginModule;
+import org.junit.jupiter.api.AfterAll;
+import org.junit.jupiter.api.BeforeAll;
+import org.junit.jupiter.api.Test;
+import org.junit.jupiter.api.io.TempDir;
+
+class LdapPlainServerCallbackHandlerTest {
+  private static final String SEARCH_BASE = "ou=users,dc=example,dc=org";
+  private static final String BIND_DN = "cn=search,dc=example,dc=org";
+  private static final String BIND_PASSWORD = "search-password";
+
+  private static InMemoryDirectoryServer server;
+
+  @TempDir static Path bindCredentialsDir;
+
+  @BeforeAll
+  static void startServer() throws Exception {
+    InMemoryDirectoryServerConfig config = new InMemoryDirectoryServerConfig("dc=example,dc=org");
+    config.addAdditionalBindCredentials(BIND_DN, BIND_PASSWORD);
+    // Like most directories, the users can only be searched after a bind
+    config.setAuthenticationRequiredOperationTypes(OperationType.SEARCH);
+    server = new InMemoryDirectoryServer(config);
+    server.add("dn: dc=example,dc=org", "objectClass: top", "objectClass: domain", "dc: example");
+    server.add("dn: " + SEARCH_BASE, "objectClass: organizationalUnit", "ou: users");
+    addUser("alice", "alice-password", "kafka");
+    addUser("bob", "bob-password", "other");
+    // Two entries with the same uid make the user name ambiguous
+    server.add(
+        "dn: ou=more-users," + SEARCH_BASE, "objectClass: organizationalUnit", "ou: more-users");
+    addUser("carol", "carol-password", "kafka");
+    server.add(
+        "dn: uid=carol,ou=more-users," + SEARCH_BASE,
+        "objectClass: inetOrgPerson",
+        "uid: carol",
+        "cn: carol",
+        "sn: carol",
+        "userPassword: carol-password");
+    server.startListening();
+
+    Files.writeString(bindCredentialsDir.resolve("user"), BIND_DN + "\n", StandardCharsets.UTF_8);
+    Files.writeString(
+        bindCredentialsDir.resolve("password"), BIND_PASSWORD + "\n", StandardCharsets.UTF_8);
+  }
+
+  @AfterAll
+  static void stopServer() {
+    server.shutDown(true);
+  }
+
+  private static void addUser(String uid, String password, String employeeType) throws Exception {
+    server.add(
+        "dn: uid=" + uid + "," + SEARCH_BASE,
+        "objectClass: inetOrgPerson",
+        "uid: " + uid,
+        "cn: " + uid,
+        "sn: " + uid,
+        "employeeType: " + employeeType,
+        "userPassword: " + password);
+  }
+
+  private static Map<String, String> options() {
+    Map<String, String> options = new HashMap<>();
+    options.put(LdapPlainServerCallbackHandler.URL, "ldap://localhost:" + server.getListenPort());
+    options.put(LdapPlainServerCallbackHandler.SEARCH_BASE, SEARCH_BASE);
+    options.put(LdapPlainServerCallbackHandler.UID_ATTRIBUTE, "uid");
+    options.put(
+        LdapPlainServerCallbackHandler.BIND_CREDENTIALS_DIR, bindCredentialsDir.toString());
+    return options;
+  }
+
+  private static LdapPlainServerCallbackHandler handler(Map<String, String> options) {
+    LdapPlainServerCallbackHandler handler = new LdapPlainServerCallbackHandler();
+    handler.configure(
+        Map.of(),
+        "PLAIN",
+        List.of(
+            new AppConfigurationEntry(
+                PlainLoginModule.class.getName(), LoginModuleControlFlag.REQUIRED, options)));
+    return handler;
+  }
+
+  private static boolean authenticate(
+      LdapPlainServerCallbackHandler handler, String username, String password) throws Exception {
+    PlainAuthenticateCallback plainCallback =
+        new PlainAuthenticateCallback(password.toCharArray());
+    handler.handle(new Callback[] {new NameCallback("username", username), plainCallback});
+    return plainCallback.authenticated();
+  }
+
+  @Test
+  void authenticatesUserWithValidPassword() throws Exception {
+    assertEquals(true, authenticate(handler(options()), "alice", "alice-password"));
+  }
+
+  @Test
+  void rejectsInvalidPassword() throws Exception {
+    assertEquals(false, authenticate(handler(options()), "alice", "bob-password"));
+  }
+
+  @Test
+  void rejectsEmptyPassword() throws Exception {
+    assertEquals(false, authenticate(handler(options()), "alice", ""));
+  }
+
+  @Test
+  void rejectsUnknownUser() throws Exception {
+    assertEquals(false, authenticate(handler(options()), "mallory", "alice-password"));
+  }
+
+  @Test
+  void rejectsAmbiguousUser() throws Exception {
+    assertEquals(false, authenticate(handler(options()), "carol", "carol-password"));
+  }
+
+  @Test
+  void escapesFilterCharactersInUserName() throws Exception {
+    assertEquals(false, authenticate(handler(options()), "*", "alice-password"));
+    assertEquals(false, authenticate(handler(options()), "alice)(uid=*", "alice-password"));
+  }
+
+  @Test
+  void appliesAdditionalSearchFilter() throws Exception {
+    Map<String, String> options = options();
+    options.put(LdapPlainServerCallbackHandler.SEARCH_FILTER, "(employeeType=kafka)");
+    LdapPlainServerCallbackHandler handler = handler(options);
+
+    assertEquals(true, authenticate(handler, "alice", "alice-password"));
+    assertEquals(false, authenticate(handler, "bob", "bob-password"));
+  }
+
+  @Test
+  void searchesAnonymouslyWithoutBindCredentials() {
+    Map<String, String> options = options();
+    options.remove(LdapPlainServerCallbackHandler.BIND_CREDENTIALS_DIR);
+
+    // The server refuses anonymous searches, which is an error rather than a failed login
+    assertThrows(
+        IOException.class, () -> authenticate(handler(options), "alice", "alice-password"));
+  }
+
+  @Test
+  void requiresOptions() {
+    for (String option :
+        List.of(
+            LdapPlainServerCallbackHandler.URL,
+            LdapPlainServerCallbackHandler.SEARCH_BASE,
+            LdapPlainServerCallbackHandler.UID_ATTRIBUTE)) {
+      Map<String, String> options = options();
+      options.remove(option);
+      assertThrows(KafkaException.class, () -> handler(options), option);
+    }
+  }
+}
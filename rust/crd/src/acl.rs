@@ -0,0 +1,477 @@
+//! The [`KafkaAcl`] custom resource, which declares ACL bindings in a [`KafkaCluster`](crate::KafkaCluster)
+//! that uses the `acls` authorizer.
+use std::collections::{BTreeMap, BTreeSet};
+
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use stackable_operator::{
+    k8s_openapi::apimachinery::pkg::apis::meta::v1::Time,
+    kube::CustomResource,
+    schemars::{self, JsonSchema},
+};
+use strum::Display;
+
+use crate::admin::{
+    admin_tool, shell_quote, KafkaClusterRef, SyncStatus, ADMIN_JOB_TERMINATION_LOG,
+};
+
+/// The only valid resource name of the `Cluster` resource type.
+const CLUSTER_RESOURCE_NAME: &str = "kafka-cluster";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display(
+        "the principal {principal:?} is invalid: it must have the form '<type>:<name>', e.g. 'User:alice'"
+    ))]
+    InvalidPrincipal { principal: String },
+
+    #[snafu(display("the host {host:?} of the principal {principal:?} is invalid"))]
+    InvalidHost { principal: String, host: String },
+
+    #[snafu(display("the ACL binding of the principal {principal:?} has no operations"))]
+    NoOperations { principal: String },
+
+    #[snafu(display("the name of the {resource_type} resource must not be empty"))]
+    EmptyResourceName { resource_type: AclResourceType },
+
+    #[snafu(display(
+        "the name of the Cluster resource must be {CLUSTER_RESOURCE_NAME:?}, but is {name:?}"
+    ))]
+    InvalidClusterResourceName { name: String },
+}
+
+/// ACL bindings in a Kafka cluster managed by the Stackable operator for Apache Kafka.
+/// The cluster has to use the `acls` authorizer.
+/// Find more information on how to use it in the
+/// [operator documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/acls).
+#[derive(Clone, CustomResource, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[kube(
+    group = "kafka.stackable.tech",
+    version = "v1alpha1",
+    kind = "KafkaAcl",
+    plural = "kafkaacls",
+    status = "KafkaAclStatus",
+    namespaced,
+    crates(
+        kube_core = "stackable_operator::kube::core",
+        k8s_openapi = "stackable_operator::k8s_openapi",
+        schemars = "stackable_operator::schemars"
+    )
+)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaAclSpec {
+    /// The KafkaCluster the ACLs are created in.
+    pub cluster_ref: KafkaClusterRef,
+
+    /// The ACL bindings. For every principal and resource in this list, the ACLs in Kafka are kept
+    /// in sync with the bindings, i.e. ACLs of the principal on the resource that are not listed
+    /// here are removed.
+    #[serde(default)]
+    pub bindings: Vec<AclBinding>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaAclStatus {
+    #[serde(flatten)]
+    pub sync: SyncStatus,
+
+    /// The principals and resources whose ACLs are managed by this object. This includes the ones
+    /// that were removed from the spec, until their ACLs are deleted.
+    #[serde(default)]
+    pub scopes: Vec<AclScope>,
+
+    /// The last drift between the ACLs in Kafka and the spec, which was found (and corrected) by
+    /// the periodic checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<AclDrift>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AclDrift {
+    /// When the drift was detected.
+    pub detected_time: Time,
+
+    /// The ACL entries which were missing in Kafka or not declared in the spec.
+    pub entries: Vec<String>,
+}
+
+/// Allows or denies operations on a resource to a principal.
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AclBinding {
+    /// The principal, e.g. `User:alice`. The name of principals authenticated with a TLS
+    /// certificate is the distinguished name of the certificate, e.g. `User:CN=alice`.
+    pub principal: String,
+
+    /// The host the principal connects from. Defaults to all hosts (`*`).
+    #[serde(default = "default_host")]
+    pub host: String,
+
+    /// The resource the binding applies to.
+    pub resource: AclResource,
+
+    /// The operations which are allowed or denied.
+    pub operations: Vec<AclOperation>,
+
+    /// Whether the operations are allowed or denied. Defaults to `Allow`.
+    #[serde(default)]
+    pub permission_type: AclPermissionType,
+}
+
+fn default_host() -> String {
+    "*".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AclResource {
+    /// The type of the resource.
+    #[serde(rename = "type")]
+    pub resource_type: AclResourceType,
+
+    /// The name of the resource or `*` for all resources of the type.
+    /// The name of the `Cluster` resource is always `kafka-cluster`.
+    pub name: String,
+
+    /// Whether the name is the full name of the resource (`Literal`) or a prefix of it (`Prefixed`).
+    /// Defaults to `Literal`.
+    #[serde(default)]
+    pub pattern_type: AclPatternType,
+}
+
+/// A principal and a resource, whose ACLs are managed together.
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AclScope {
+    pub principal: String,
+    pub resource: AclResource,
+}
+
+#[derive(
+    Clone, Copy, Debug, Deserialize, Display, Eq, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum AclResourceType {
+    Topic,
+    Group,
+    Cluster,
+    TransactionalId,
+    DelegationToken,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum AclPatternType {
+    #[default]
+    Literal,
+    Prefixed,
+}
+
+/// The operations, displayed as they are listed by the Kafka admin tools.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Display, Eq, JsonSchema, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum AclOperation {
+    All,
+    Read,
+    Write,
+    Create,
+    Delete,
+    Alter,
+    Describe,
+    ClusterAction,
+    DescribeConfigs,
+    AlterConfigs,
+    IdempotentWrite,
+    CreateTokens,
+    DescribeTokens,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, Eq, JsonSchema, PartialEq, Serialize,
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum AclPermissionType {
+    #[default]
+    Allow,
+    Deny,
+}
+
+impl AclBinding {
+    pub fn scope(&self) -> AclScope {
+        AclScope {
+            principal: self.principal.clone(),
+            resource: self.resource.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let principal = &self.principal;
+        ensure!(
+            principal
+                .split_once(':')
+                .is_some_and(
+                    |(principal_type, name)| !principal_type.is_empty() && !name.is_empty()
+                )
+                && !principal.contains(char::is_control),
+            InvalidPrincipalSnafu { principal }
+        );
+        ensure!(
+            !self.host.is_empty() && !self.host.contains(|c: char| c.is_control() || c == ','),
+            InvalidHostSnafu {
+                principal,
+                host: &self.host
+            }
+        );
+        ensure!(!self.operations.is_empty(), NoOperationsSnafu { principal });
+        self.resource.validate()
+    }
+
+    /// The ACL entries of the binding, formatted like the Kafka admin tools list them.
+    fn entries(&self) -> impl Iterator<Item = String> + '_ {
+        self.operations.iter().map(|operation| {
+            format!(
+                "(principal={}, host={}, operation={operation}, permissionType={})",
+                self.principal, self.host, self.permission_type
+            )
+        })
+    }
+}
+
+impl AclResource {
+    fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            !self.name.is_empty(),
+            EmptyResourceNameSnafu {
+                resource_type: self.resource_type
+            }
+        );
+        if self.resource_type == AclResourceType::Cluster {
+            ensure!(
+                self.name == CLUSTER_RESOURCE_NAME,
+                InvalidClusterResourceNameSnafu { name: &self.name }
+            );
+        }
+        Ok(())
+    }
+
+    /// The arguments of `kafka-acls.sh` that select the resource.
+    fn args(&self) -> String {
+        let resource = match self.resource_type {
+            AclResourceType::Topic => format!("--topic {}", shell_quote(&self.name)),
+            AclResourceType::Group => format!("--group {}", shell_quote(&self.name)),
+            AclResourceType::Cluster => "--cluster".to_string(),
+            AclResourceType::TransactionalId => {
+                format!("--transactional-id {}", shell_quote(&self.name))
+            }
+            AclResourceType::DelegationToken => {
+                format!("--delegation-token {}", shell_quote(&self.name))
+            }
+        };
+        let pattern_type = match self.pattern_type {
+            AclPatternType::Literal => "literal",
+            AclPatternType::Prefixed => "prefixed",
+        };
+        format!("{resource} --resource-pattern-type {pattern_type}")
+    }
+
+    /// A human readable description, e.g. `Topic prefix orders.`.
+    fn description(&self) -> String {
+        match self.pattern_type {
+            AclPatternType::Literal => format!("{} {}", self.resource_type, self.name),
+            AclPatternType::Prefixed => format!("{} prefix {}", self.resource_type, self.name),
+        }
+    }
+}
+
+impl KafkaAcl {
+    pub fn spec_scopes(&self) -> BTreeSet<AclScope> {
+        self.spec.bindings.iter().map(AclBinding::scope).collect()
+    }
+
+    /// The scopes of the spec and the ones which were managed before, according to the status.
+    pub fn managed_scopes(&self) -> BTreeSet<AclScope> {
+        let mut scopes = self.spec_scopes();
+        scopes.extend(self.status.iter().flat_map(|status| status.scopes.clone()));
+        scopes
+    }
+
+    /// Returns the commands that sync the ACLs in Kafka with the bindings. The ACLs of scopes
+    /// which were removed from the spec are deleted.
+    pub fn apply_commands(&self) -> Result<String, Error> {
+        for binding in &self.spec.bindings {
+            binding.validate()?;
+        }
+        Ok(sync_commands(&self.spec.bindings, &self.managed_scopes()))
+    }
+
+    /// Returns the commands that delete all managed ACLs.
+    pub fn delete_commands(&self) -> String {
+        sync_commands(&[], &self.managed_scopes())
+    }
+}
+
+/// Returns the commands that converge the ACLs of every scope to the given bindings. Every
+/// difference is written to the [termination log](ADMIN_JOB_TERMINATION_LOG) as a drift entry.
+fn sync_commands(bindings: &[AclBinding], scopes: &BTreeSet<AclScope>) -> String {
+    let mut entries = scopes
+        .iter()
+        .map(|scope| (scope.clone(), BTreeSet::new()))
+        .collect::<BTreeMap<_, _>>();
+    for binding in bindings {
+        entries
+            .entry(binding.scope())
+            .or_default()
+            .extend(binding.entries());
+    }
+
+    // The ACL entries are parsed from the (escaped) lines listed by kafka-acls.sh, so that
+    // unexpected entries can be removed, e.g.
+    // (principal=User:alice, host=*, operation=DESCRIBE_CONFIGS, permissionType=ALLOW)
+    let kafka_acls = admin_tool("kafka-acls.sh");
+    let mut commands = vec![formatdoc! {r#"
+        acl_command() {{
+          local action="$1" entry="$2" operation permission
+          operation=$(sed -E 's/^.*, operation=(.*), permissionType=.*$/\1/; s/(^|_)([A-Z])([A-Z]*)/\2\L\3/g' <<< "$entry")
+          permission=$(sed -E 's/^.*, permissionType=(.*)\)$/\L\1/' <<< "$entry")
+          {kafka_acls} "$action" --force "${{RESOURCE[@]}}" --operation "$operation" \
+            "--$permission-principal" "$(sed -E 's/^\(principal=(.*), host=.*$/\1/' <<< "$entry")" \
+            "--$permission-host" "$(sed -E 's/^.*, host=(.*), operation=.*$/\1/' <<< "$entry")"
+        }}
+        sync_acls() {{
+          local principal="$1" listing actual entry
+          shift
+          listing=$({kafka_acls} --list "${{RESOURCE[@]}}" --principal "$principal")
+          actual=$(grep -oP '^\s*\K\(principal=.*\)' <<< "$listing" || true)
+          for entry in "$@"; do
+            if ! grep -qxF -- "$entry" <<< "$actual"; then
+              echo "missing: $DESCRIPTION $entry" | tee -a {ADMIN_JOB_TERMINATION_LOG}
+              acl_command --add "$entry"
+            fi
+          done
+          while IFS= read -r entry; do
+            if [ -n "$entry" ] && ! printf '%s\n' "$@" | grep -qxF -- "$entry"; then
+              echo "unexpected: $DESCRIPTION $entry" | tee -a {ADMIN_JOB_TERMINATION_LOG}
+              acl_command --remove "$entry"
+            fi
+          done <<< "$actual"
+        }}"#
+    }];
+
+    for (scope, entries) in entries {
+        let entries = entries
+            .iter()
+            .map(|entry| format!(" \\\n  {}", shell_quote(entry)))
+            .collect::<String>();
+        commands.push(formatdoc! {"
+            RESOURCE=({resource})
+            DESCRIPTION={description}
+            sync_acls {principal}{entries}",
+            resource = scope.resource.args(),
+            description = shell_quote(&scope.resource.description()),
+            principal = shell_quote(&scope.principal),
+        });
+    }
+
+    commands.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    fn acl(bindings: &str, scopes: &str) -> KafkaAcl {
+        let input = format!(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaAcl
+        metadata:
+          name: orders
+          namespace: default
+        spec:
+          clusterRef:
+            name: simple-kafka
+          bindings: {bindings}
+        status:
+          syncState: Synced
+          scopes: {scopes}
+        "#
+        );
+        serde_yaml::from_str(&input).expect("illegal test input")
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let acl = acl(
+            r#"
+            - principal: User:alice
+              resource:
+                type: Topic
+                name: orders.
+                patternType: Prefixed
+              operations: [Read, DescribeConfigs]
+            - principal: User:alice
+              host: 10.0.0.1
+              resource:
+                type: Topic
+                name: orders.
+                patternType: Prefixed
+              operations: [Write]
+              permissionType: Deny"#,
+            r#"
+            - principal: User:bob
+              resource:
+                type: Cluster
+                name: kafka-cluster"#,
+        );
+        let commands = acl.apply_commands().unwrap();
+
+        assert!(commands.contains(indoc::indoc! {r#"
+            RESOURCE=(--topic 'orders.' --resource-pattern-type prefixed)
+            DESCRIPTION='Topic prefix orders.'
+            sync_acls 'User:alice' \
+              '(principal=User:alice, host=*, operation=DESCRIBE_CONFIGS, permissionType=ALLOW)' \
+              '(principal=User:alice, host=*, operation=READ, permissionType=ALLOW)' \
+              '(principal=User:alice, host=10.0.0.1, operation=WRITE, permissionType=DENY)'"#
+        }));
+        // The bindings of bob were removed from the spec
+        assert!(commands.contains(indoc::indoc! {"
+            RESOURCE=(--cluster --resource-pattern-type literal)
+            DESCRIPTION='Cluster kafka-cluster'
+            sync_acls 'User:bob'"
+        }));
+        assert_eq!(acl.managed_scopes().len(), 2);
+    }
+
+    #[rstest]
+    #[case("alice", "Topic", "orders", "[Read]")]
+    #[case("User:alice", "Topic", "", "[Read]")]
+    #[case("User:alice", "Cluster", "orders", "[Create]")]
+    #[case("User:alice", "Topic", "orders", "[]")]
+    fn test_invalid_bindings(
+        #[case] principal: &str,
+        #[case] resource_type: &str,
+        #[case] name: &str,
+        #[case] operations: &str,
+    ) {
+        let acl = acl(
+            &format!(
+                r#"
+            - principal: "{principal}"
+              resource:
+                type: {resource_type}
+                name: "{name}"
+              operations: {operations}"#
+            ),
+            "[]",
+        );
+
+        assert!(acl.apply_commands().is_err());
+    }
+}
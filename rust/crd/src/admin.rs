@@ -0,0 +1,215 @@
+//! Types shared by the resources the operator manages inside of a [`KafkaCluster`](crate::KafkaCluster),
+//! such as topics, users and ACLs. These are applied by running the Kafka admin tools against the cluster.
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_operator::{
+    k8s_openapi::{apimachinery::pkg::apis::meta::v1::Time, chrono::Utc},
+    kube::ResourceExt,
+    schemars::{self, JsonSchema},
+    utils::cluster_info::KubernetesClusterInfo,
+};
+use strum::Display;
+
+use crate::{
+    security::KafkaTlsSecurity, KafkaCluster, KafkaRole, STACKABLE_KERBEROS_DIR,
+    STACKABLE_KERBEROS_KRB5_PATH,
+};
+
+/// The client configuration of the Kafka admin tools, which is written by [`admin_tool_commands`].
+pub const ADMIN_CLIENT_PROPERTIES: &str = "/tmp/client.properties";
+/// The admin tool commands can report their outcome to the operator in the termination message of
+/// the container, which is read from this file.
+pub const ADMIN_JOB_TERMINATION_LOG: &str = "/dev/termination-log";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("failed to list the broker Pods"))]
+    ListBrokerPods { source: crate::Error },
+
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+}
+
+/// A reference to the [`KafkaCluster`](crate::KafkaCluster) a resource is managed in.
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaClusterRef {
+    /// The name of the KafkaCluster, which must be in the same namespace.
+    pub name: String,
+}
+
+/// Whether a resource is deleted in Kafka when the Kubernetes object is deleted.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, Eq, JsonSchema, PartialEq, Serialize,
+)]
+pub enum DeletionPolicy {
+    /// Keep the resource in Kafka.
+    #[default]
+    Retain,
+    /// Delete the resource in Kafka. The deletion of the Kubernetes object is blocked by a
+    /// finalizer until this succeeded.
+    Delete,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, Eq, JsonSchema, PartialEq, Serialize,
+)]
+pub enum SyncState {
+    /// The resource was not applied to Kafka yet.
+    #[default]
+    Pending,
+    /// The resource in Kafka matches the spec.
+    Synced,
+    /// The resource could not be applied, see the message for details.
+    Failed,
+    /// The resource is being deleted in Kafka.
+    Deleting,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// Whether the spec is applied to the Kafka cluster.
+    pub sync_state: SyncState,
+
+    /// The generation of the object that the sync state refers to.
+    pub observed_generation: Option<i64>,
+
+    /// Details about the last sync, e.g. why it failed.
+    pub message: Option<String>,
+
+    /// The last time the sync state changed.
+    pub last_transition_time: Option<Time>,
+}
+
+impl SyncStatus {
+    /// Builds the next status. The transition time is only updated if the sync state changed
+    /// compared to the `previous` status.
+    pub fn new(
+        sync_state: SyncState,
+        observed_generation: Option<i64>,
+        message: Option<String>,
+        previous: Option<&SyncStatus>,
+    ) -> Self {
+        let last_transition_time = match previous {
+            Some(previous) if previous.sync_state == sync_state => {
+                previous.last_transition_time.clone()
+            }
+            _ => Some(Time(Utc::now())),
+        };
+
+        Self {
+            sync_state,
+            observed_generation,
+            message,
+            last_transition_time,
+        }
+    }
+}
+
+/// Returns a script that writes the client configuration of the Kafka admin tools and exports the
+/// INTERNAL listeners of all brokers as `BOOTSTRAP_SERVERS`, followed by the given commands.
+///
+/// The brokers are addressed by their Pod FQDN, as the internal TLS certificates are issued for it.
+pub fn admin_tool_commands(
+    kafka: &KafkaCluster,
+    kafka_security: &KafkaTlsSecurity,
+    cluster_info: &KubernetesClusterInfo,
+    commands: &str,
+) -> Result<String, Error> {
+    let bootstrap_servers = kafka
+        .pods()
+        .context(ListBrokerPodsSnafu)?
+        .map(|pod| {
+            format!(
+                "{}:{}",
+                pod.fqdn(cluster_info),
+                kafka_security.internal_port()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let client_properties = kafka_security
+        .admin_client_config_settings()
+        .into_iter()
+        .map(|(key, value)| {
+            format!(
+                "echo {} >> {ADMIN_CLIENT_PROPERTIES}",
+                shell_quote(&format!("{key}={value}"))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let kerberos_jaas_config = if kafka_security.has_kerberos_inter_broker_enabled() {
+        let service_name = KafkaRole::Broker.kerberos_service_name();
+        let principal_host = format!(
+            "{service}.{namespace}.svc.{cluster_domain}",
+            service = admin_kerberos_service(kafka),
+            namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?,
+            cluster_domain = cluster_info.cluster_domain,
+        );
+        formatdoc! {"
+            export KERBEROS_REALM=$(grep -oP 'default_realm = \\K.*' {STACKABLE_KERBEROS_KRB5_PATH})
+            echo \"sasl.jaas.config=com.sun.security.auth.module.Krb5LoginModule required useKeyTab=true storeKey=true keyTab=\\\"{STACKABLE_KERBEROS_DIR}/keytab\\\" principal=\\\"{service_name}/{principal_host}@$KERBEROS_REALM\\\";\" >> {ADMIN_CLIENT_PROPERTIES}
+            "}
+    } else {
+        String::new()
+    };
+
+    Ok(formatdoc! {"
+        rm -f {ADMIN_CLIENT_PROPERTIES}
+        {client_properties}
+        {kerberos_jaas_config}export BOOTSTRAP_SERVERS={bootstrap_servers}
+        {commands}",
+    })
+}
+
+/// The name of the service scope of the keytab of the admin Jobs, which authenticate with Kerberos
+/// if the brokers do so among each other. The Service itself does not exist.
+pub fn admin_kerberos_service(kafka: &KafkaCluster) -> String {
+    format!("{}-admin", kafka.name_any())
+}
+
+/// Invokes the given Kafka admin tool, e.g. `kafka-topics.sh`, with the connection settings
+/// provided by [`admin_tool_commands`].
+pub fn admin_tool(tool: &str) -> String {
+    format!(
+        "bin/{tool} --bootstrap-server \"$BOOTSTRAP_SERVERS\" --command-config {ADMIN_CLIENT_PROPERTIES}"
+    )
+}
+
+/// Quotes the value for bash, as names and settings of the managed resources are passed to the
+/// admin tools as arguments.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("retention.ms=1000"), "'retention.ms=1000'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn test_sync_status_transition_time() {
+        let pending = SyncStatus::new(SyncState::Pending, Some(1), None, None);
+        assert!(pending.last_transition_time.is_some());
+
+        let still_pending = SyncStatus::new(SyncState::Pending, Some(2), None, Some(&pending));
+        assert_eq!(
+            still_pending.last_transition_time,
+            pending.last_transition_time
+        );
+        assert_eq!(still_pending.observed_generation, Some(2));
+
+        let synced = SyncStatus::new(SyncState::Synced, Some(2), None, Some(&still_pending));
+        assert_eq!(synced.sync_state, SyncState::Synced);
+        assert!(synced.last_transition_time.is_some());
+    }
+}
@@ -1,15 +1,40 @@
+use std::collections::BTreeSet;
+
 use crate::ObjectRef;
 
 use serde::{Deserialize, Serialize};
-use snafu::{ResultExt, Snafu};
+use snafu::{ensure, ResultExt, Snafu};
 use stackable_operator::commons::authentication::AuthenticationClassProvider;
 use stackable_operator::{
     client::Client,
     commons::authentication::AuthenticationClass,
+    kube::ResourceExt,
     schemars::{self, JsonSchema},
 };
 
-pub const SUPPORTED_AUTHENTICATION_CLASS_PROVIDERS: [&str; 2] = ["TLS", "Kerberos"];
+pub const SUPPORTED_AUTHENTICATION_CLASS_PROVIDERS: [&str; 5] =
+    ["TLS", "Kerberos", "Static", "OIDC", "LDAP"];
+/// The JWKS endpoint of Keycloak, relative to the issuer (i.e. the realm).
+pub const DEFAULT_JWKS_ENDPOINT_PATH: &str = "protocol/openid-connect/certs";
+/// The token endpoint of Keycloak, relative to the issuer (i.e. the realm).
+pub const DEFAULT_TOKEN_ENDPOINT_PATH: &str = "protocol/openid-connect/token";
+/// The name of a dedicated listener ends up in the port name `kafka-<name>`, which may not exceed
+/// 15 characters.
+const MAX_LISTENER_NAME_LENGTH: usize = 9;
+/// Listener names which would collide with built-in names:
+/// - `auth` results in the Kafka listener name `CLIENT_AUTH`
+/// - `client`, `internal`, `controller` and `bootstrap` are (part of) built-in Kafka listener names
+/// - `nodeport` results in the name of the backwards compatible discovery ConfigMap
+/// - `tls` results in the port name `kafka-tls` of the default client listener
+const RESERVED_LISTENER_NAMES: [&str; 7] = [
+    "auth",
+    "client",
+    "internal",
+    "controller",
+    "bootstrap",
+    "nodeport",
+    "tls",
+];
 
 #[derive(Snafu, Debug)]
 pub enum Error {
@@ -19,8 +44,29 @@ pub enum Error {
         authentication_class: ObjectRef<AuthenticationClass>,
     },
 
-    #[snafu(display("only one authentication class at a time is currently supported. Possible Authentication class providers are {SUPPORTED_AUTHENTICATION_CLASS_PROVIDERS:?}"))]
-    MultipleAuthenticationClassesProvided,
+    #[snafu(display("only one authentication class per provider is supported, but multiple authentication classes use the [{provider}] provider"))]
+    MultipleAuthenticationClassesOfProvider { provider: String },
+
+    #[snafu(display("authentication class [{authentication_class}] requires a dedicated listener, only the first authentication class is served by the default client listener"))]
+    ListenerRequired {
+        authentication_class: ObjectRef<AuthenticationClass>,
+    },
+
+    #[snafu(display("the first authentication class [{authentication_class}] is served by the default client listener and cannot have a dedicated listener"))]
+    UnexpectedListener {
+        authentication_class: ObjectRef<AuthenticationClass>,
+    },
+
+    #[snafu(display("invalid listener name [{name}], it must start with a lowercase letter and consist of at most {MAX_LISTENER_NAME_LENGTH} lowercase ASCII alphanumerics"))]
+    InvalidListenerName { name: String },
+
+    #[snafu(display(
+        "the listener name [{name}] is reserved, reserved names are {RESERVED_LISTENER_NAMES:?}"
+    ))]
+    ReservedListenerName { name: String },
+
+    #[snafu(display("the listener name [{name}] is used by multiple authentication classes"))]
+    DuplicateListenerName { name: String },
 
     #[snafu(display(
         "failed to use authentication provider [{provider}] for authentication class [{authentication_class}] - supported providers: {SUPPORTED_AUTHENTICATION_CLASS_PROVIDERS:?}",
@@ -29,6 +75,28 @@ pub enum Error {
         authentication_class: ObjectRef<AuthenticationClass>,
         provider: String,
     },
+
+    #[snafu(display("the OIDC provider of authentication class [{authentication_class}] must verify the TLS certificate of the identity provider"))]
+    OidcTlsVerificationRequired {
+        authentication_class: ObjectRef<AuthenticationClass>,
+    },
+
+    #[snafu(display("the LDAP provider of authentication class [{authentication_class}] must verify the TLS certificate of the LDAP server"))]
+    LdapTlsVerificationRequired {
+        authentication_class: ObjectRef<AuthenticationClass>,
+    },
+
+    #[snafu(display("invalid pattern [{pattern}] of a principal mapping rule"))]
+    InvalidPrincipalMappingRule {
+        source: regex::Error,
+        pattern: String,
+    },
+
+    #[snafu(display("invalid Kerberos principal to local rule: {reason}"))]
+    InvalidKerberosPrincipalToLocalRule { reason: String },
+
+    #[snafu(display("invalid entry [{entry}] of the trusted Kerberos realm [{realm}]"))]
+    InvalidKerberosTrustedRealm { realm: String, entry: String },
 }
 
 #[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
@@ -46,25 +114,457 @@ pub struct KafkaAuthentication {
     ///
     /// ## Kerberos provider
     ///
-    /// This affects client connections and also requires TLS for encryption.
+    /// This affects client connections and also requires TLS for encryption, unless
+    /// `kerberos.saslPlaintext` is set.
     /// This setting is used to reference an `AuthenticationClass` and in turn, a `SecretClass` that is
     /// used to create keytabs.
+    ///
+    /// ## Static provider
+    ///
+    /// This affects client connections and also requires TLS for encryption.
+    /// Clients authenticate with SCRAM-SHA-256 or SCRAM-SHA-512. The users and their passwords are
+    /// taken from the referenced Secret and bootstrapped into the SCRAM credential store of Kafka.
+    ///
+    /// ## OIDC provider
+    ///
+    /// This affects client connections and also requires TLS for encryption.
+    /// Clients authenticate with OAUTHBEARER, using access tokens of the identity provider, which
+    /// are validated against its JWKS endpoint. The user name is taken from the principal claim.
+    ///
+    /// ## LDAP provider
+    ///
+    /// This affects client connections and also requires TLS for encryption.
+    /// Clients authenticate with SASL/PLAIN. The brokers search the user with the bind credentials
+    /// of the AuthenticationClass and verify the password by binding as the user.
     pub authentication_class: String,
+
+    /// Kafka specific options for an AuthenticationClass with the TLS provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<KafkaTlsAuthenticationOptions>,
+
+    /// Kafka specific options for an AuthenticationClass with the OIDC provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oidc: Option<KafkaOidcOptions>,
+
+    /// Kafka specific options for an AuthenticationClass with the Kerberos provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kerberos: Option<KafkaKerberosOptions>,
+
+    /// The dedicated listener which serves this AuthenticationClass.
+    /// The first AuthenticationClass is served by the default client listener and must not set
+    /// this, all further AuthenticationClasses require it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listener: Option<KafkaAuthenticationListener>,
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaAuthenticationListener {
+    /// The name of the listener, which must start with a lowercase letter and consist of at most
+    /// 9 lowercase ASCII alphanumerics. The Kafka listener is called `CLIENT_<NAME>`, its port
+    /// `kafka-<name>` and its discovery ConfigMap `<cluster name>-<name>`.
+    pub name: String,
+
+    /// The ListenerClass used for connecting to brokers on this listener.
+    /// Defaults to the `brokerListenerClass` of the role group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broker_listener_class: Option<String>,
+
+    /// The ListenerClass used for bootstrapping clients of this listener.
+    /// Defaults to the `bootstrapListenerClass` of the role group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_listener_class: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaTlsAuthenticationOptions {
+    /// Whether clients must present a certificate (`Required`) or may also connect anonymously
+    /// with TLS (`Requested`), e.g. while migrating clients to mutual TLS. Defaults to `Required`.
+    #[serde(default)]
+    pub client_auth: KafkaTlsClientAuth,
+
+    /// Rules which map the distinguished name of a client certificate to the principal name, e.g.
+    /// `alice` instead of `CN=alice,OU=users,O=example`. The rules are applied in order, the first
+    /// matching one wins. If no rule matches, the full distinguished name is used.
+    #[serde(default)]
+    pub principal_mapping_rules: Vec<KafkaPrincipalMappingRule>,
+}
+
+impl KafkaTlsAuthenticationOptions {
+    /// Returns the rules in the format of `ssl.principal.mapping.rules`, if there are any.
+    pub fn ssl_principal_mapping_rules(&self) -> Option<String> {
+        if self.principal_mapping_rules.is_empty() {
+            return None;
+        }
+
+        let rules = self
+            .principal_mapping_rules
+            .iter()
+            .map(KafkaPrincipalMappingRule::to_kafka_rule)
+            // Keep the full distinguished name if no rule matches
+            .chain(std::iter::once("DEFAULT".to_string()))
+            .collect::<Vec<_>>();
+        Some(rules.join(","))
+    }
+}
+
+#[derive(
+    Clone, Copy, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize, strum::Display,
+)]
+#[serde(rename_all = "PascalCase")]
+#[strum(serialize_all = "lowercase")]
+pub enum KafkaTlsClientAuth {
+    /// Clients must present a certificate.
+    #[default]
+    Required,
+    /// Clients are asked for a certificate, but may connect without one as `User:ANONYMOUS`.
+    Requested,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaPrincipalMappingRule {
+    /// A regular expression, which has to match the complete distinguished name, e.g.
+    /// `^CN=(.*?),OU=users,.*$`. Look-arounds and backreferences are not supported.
+    pub pattern: String,
+
+    /// The principal name, which can refer to the groups of the pattern, e.g. `$1`.
+    pub replacement: String,
+
+    /// Converts the principal name to lowercase or uppercase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case: Option<KafkaPrincipalNameCase>,
+}
+
+impl KafkaPrincipalMappingRule {
+    /// Renders the rule as `RULE:pattern/replacement/[LU]`, slashes are escaped as they separate
+    /// the parts of the rule.
+    fn to_kafka_rule(&self) -> String {
+        let case = match self.case {
+            Some(KafkaPrincipalNameCase::Lowercase) => "L",
+            Some(KafkaPrincipalNameCase::Uppercase) => "U",
+            None => "",
+        };
+        format!(
+            "RULE:{pattern}/{replacement}/{case}",
+            pattern = self.pattern.replace('/', "\\/"),
+            replacement = self.replacement.replace('/', "\\/"),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum KafkaPrincipalNameCase {
+    Lowercase,
+    Uppercase,
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaOidcOptions {
+    /// The audience (`aud` claim) the access tokens must be issued for.
+    /// The audience is not validated if it is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_audience: Option<String>,
+
+    /// The path of the JWKS endpoint, relative to the issuer of the AuthenticationClass.
+    /// Defaults to the endpoint of Keycloak, `protocol/openid-connect/certs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwks_endpoint_path: Option<String>,
+
+    /// The name of a Secret with the keys `clientId` and `clientSecret` of a client of the
+    /// identity provider. The readiness probe of the brokers obtains access tokens with the client
+    /// credentials grant and logs in with OAUTHBEARER. Without it, the probe only checks that the
+    /// client port accepts connections, as it cannot obtain an access token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prober_credentials_secret: Option<String>,
+
+    /// The path of the token endpoint the readiness probe obtains access tokens from, relative to
+    /// the issuer of the AuthenticationClass.
+    /// Defaults to the endpoint of Keycloak, `protocol/openid-connect/token`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_endpoint_path: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaKerberosOptions {
+    /// Accept Kerberos (GSSAPI) logins on unencrypted connections (`SASL_PLAINTEXT`), so that no
+    /// server `SecretClass` is required. Only enable this if the connections are encrypted
+    /// otherwise, e.g. by a service mesh. Defaults to `false`.
+    #[serde(default)]
+    pub sasl_plaintext: bool,
+
+    /// Let the brokers authenticate to each other with their Kerberos principals on the internal
+    /// listener (`SASL_SSL` with GSSAPI), in addition to the certificates of the internal
+    /// `SecretClass`. Defaults to `false`.
+    #[serde(default)]
+    pub inter_broker: bool,
+
+    /// Rules which map Kerberos principals to the principal names used by Kafka, e.g. `alice`
+    /// instead of `alice@CORP.EXAMPLE.COM`. The rules are applied in order, the first matching one
+    /// wins. If no rule matches, only principals of the default realm are shortened.
+    #[serde(default)]
+    pub principal_to_local_rules: Vec<KafkaKerberosPrincipalToLocalRule>,
+
+    /// Further realms which are trusted by the realm of the Kerberos `SecretClass`. They are added
+    /// to the Kerberos configuration (`krb5.conf`) of the brokers.
+    #[serde(default)]
+    pub trusted_realms: Vec<KafkaKerberosTrustedRealm>,
+}
+
+impl KafkaKerberosOptions {
+    /// Returns the rules in the format of `sasl.kerberos.principal.to.local.rules`, if there are
+    /// any.
+    pub fn sasl_kerberos_principal_to_local_rules(&self) -> Option<String> {
+        if self.principal_to_local_rules.is_empty() {
+            return None;
+        }
+
+        let rules = self
+            .principal_to_local_rules
+            .iter()
+            .map(KafkaKerberosPrincipalToLocalRule::to_kafka_rule)
+            // Shorten the principals of the default realm if no rule matches
+            .chain(std::iter::once("DEFAULT".to_string()))
+            .collect::<Vec<_>>();
+        Some(rules.join(","))
+    }
+
+    /// Returns the `[realms]` and `[domain_realm]` sections of the trusted realms, which are
+    /// appended to the `krb5.conf` of the Kerberos `SecretClass`.
+    pub fn krb5_trusted_realms_config(&self) -> Option<String> {
+        if self.trusted_realms.is_empty() {
+            return None;
+        }
+
+        let mut realms = vec!["[realms]".to_string()];
+        let mut domain_realms = vec!["[domain_realm]".to_string()];
+        for trusted_realm in &self.trusted_realms {
+            let realm = &trusted_realm.realm;
+            realms.push(format!("  {realm} = {{"));
+            for kdc in &trusted_realm.kdcs {
+                realms.push(format!("    kdc = {kdc}"));
+            }
+            if let Some(admin_server) = &trusted_realm.admin_server {
+                realms.push(format!("    admin_server = {admin_server}"));
+            }
+            realms.push("  }".to_string());
+
+            for domain in &trusted_realm.domains {
+                domain_realms.push(format!("  .{domain} = {realm}"));
+                domain_realms.push(format!("  {domain} = {realm}"));
+            }
+        }
+
+        Some(format!(
+            "{realms}\n\n{domain_realms}\n",
+            realms = realms.join("\n"),
+            domain_realms = domain_realms.join("\n")
+        ))
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaKerberosPrincipalToLocalRule {
+    /// The number of components the principal must have, e.g. `1` for `alice@CORP.EXAMPLE.COM` or
+    /// `2` for `kafka/broker.example.com@CORP.EXAMPLE.COM`.
+    pub components: u8,
+
+    /// The format of the name the rule works on. `$0` refers to the realm and `$1`, `$2`, ... to
+    /// the components of the principal, e.g. `$1@$0`.
+    pub format: String,
+
+    /// A regular expression, which has to match the formatted name for the rule to apply, e.g.
+    /// `.*@CORP\.EXAMPLE\.COM`. Must not contain `)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// A substitution which is applied to the formatted name, e.g. to remove the realm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub substitution: Option<KafkaKerberosPrincipalSubstitution>,
+
+    /// Converts the principal name to lowercase or uppercase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case: Option<KafkaPrincipalNameCase>,
+}
+
+impl KafkaKerberosPrincipalToLocalRule {
+    /// Renders the rule as `RULE:[n:format](filter)s/pattern/replacement/g/[LU]`.
+    fn to_kafka_rule(&self) -> String {
+        let mut rule = format!(
+            "RULE:[{components}:{format}]",
+            components = self.components,
+            format = self.format
+        );
+        if let Some(filter) = &self.filter {
+            rule.push_str(&format!("({filter})"));
+        }
+        if let Some(substitution) = &self.substitution {
+            rule.push_str(&format!(
+                "s/{pattern}/{replacement}/",
+                pattern = substitution.pattern,
+                replacement = substitution.replacement
+            ));
+            if substitution.global {
+                rule.push('g');
+            }
+        }
+        match self.case {
+            Some(KafkaPrincipalNameCase::Lowercase) => rule.push_str("/L"),
+            Some(KafkaPrincipalNameCase::Uppercase) => rule.push_str("/U"),
+            None => {}
+        }
+        rule
+    }
+
+    /// Kafka cannot escape the delimiters of the rule parts, so they must not be part of them.
+    fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            self.components > 0,
+            InvalidKerberosPrincipalToLocalRuleSnafu {
+                reason: "components must be at least 1"
+            }
+        );
+        ensure!(
+            !self.format.contains(']'),
+            InvalidKerberosPrincipalToLocalRuleSnafu {
+                reason: format!("format [{}] must not contain ']'", self.format)
+            }
+        );
+        if let Some(filter) = &self.filter {
+            ensure!(
+                !filter.contains(')'),
+                InvalidKerberosPrincipalToLocalRuleSnafu {
+                    reason: format!("filter [{filter}] must not contain ')'")
+                }
+            );
+            regex::Regex::new(filter).context(InvalidPrincipalMappingRuleSnafu {
+                pattern: filter.clone(),
+            })?;
+        }
+        if let Some(substitution) = &self.substitution {
+            for value in [&substitution.pattern, &substitution.replacement] {
+                ensure!(
+                    !value.contains('/'),
+                    InvalidKerberosPrincipalToLocalRuleSnafu {
+                        reason: format!("substitution [{value}] must not contain '/'")
+                    }
+                );
+            }
+            regex::Regex::new(&substitution.pattern).context(InvalidPrincipalMappingRuleSnafu {
+                pattern: substitution.pattern.clone(),
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaKerberosPrincipalSubstitution {
+    /// A regular expression, e.g. `@.*`. Must not contain `/`.
+    pub pattern: String,
+
+    /// The replacement of the matches, which can refer to the groups of the pattern, e.g. `$1`.
+    /// Must not contain `/`.
+    pub replacement: String,
+
+    /// Replace all matches instead of only the first one. Defaults to `false`.
+    #[serde(default)]
+    pub global: bool,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaKerberosTrustedRealm {
+    /// The name of the realm, e.g. `EU.CORP.EXAMPLE.COM`.
+    pub realm: String,
+
+    /// The KDCs of the realm, e.g. `dc1.eu.corp.example.com` or `dc1.eu.corp.example.com:88`.
+    #[serde(default)]
+    pub kdcs: Vec<String>,
+
+    /// The admin server of the realm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_server: Option<String>,
+
+    /// DNS domains whose hosts belong to the realm, e.g. `eu.corp.example.com`.
+    #[serde(default)]
+    pub domains: Vec<String>,
+}
+
+impl KafkaKerberosTrustedRealm {
+    /// The entries are written to `krb5.conf`, so they are restricted to the characters of realm
+    /// names and host names.
+    fn validate(&self) -> Result<(), Error> {
+        let entries = std::iter::once(&self.realm)
+            .chain(&self.kdcs)
+            .chain(&self.admin_server)
+            .chain(&self.domains);
+        for entry in entries {
+            ensure!(
+                !entry.is_empty()
+                    && entry
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':')),
+                InvalidKerberosTrustedRealmSnafu {
+                    realm: self.realm.clone(),
+                    entry: entry.clone(),
+                }
+            );
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
 /// Helper struct that contains resolved AuthenticationClasses to reduce network API calls.
 pub struct ResolvedAuthenticationClasses {
     resolved_authentication_classes: Vec<AuthenticationClass>,
+    /// The dedicated listeners, in the order of the resolved AuthenticationClasses
+    listeners: Vec<Option<KafkaAuthenticationListener>>,
+    tls_options: KafkaTlsAuthenticationOptions,
+    oidc_options: KafkaOidcOptions,
+    kerberos_options: KafkaKerberosOptions,
 }
 
 impl ResolvedAuthenticationClasses {
     pub fn new(resolved_authentication_classes: Vec<AuthenticationClass>) -> Self {
         Self {
             resolved_authentication_classes,
+            listeners: vec![],
+            tls_options: KafkaTlsAuthenticationOptions::default(),
+            oidc_options: KafkaOidcOptions::default(),
+            kerberos_options: KafkaKerberosOptions::default(),
         }
     }
 
+    pub fn with_listeners(mut self, listeners: Vec<Option<KafkaAuthenticationListener>>) -> Self {
+        self.listeners = listeners;
+        self
+    }
+
+    pub fn with_tls_options(mut self, tls_options: KafkaTlsAuthenticationOptions) -> Self {
+        self.tls_options = tls_options;
+        self
+    }
+
+    pub fn with_oidc_options(mut self, oidc_options: KafkaOidcOptions) -> Self {
+        self.oidc_options = oidc_options;
+        self
+    }
+
+    pub fn with_kerberos_options(mut self, kerberos_options: KafkaKerberosOptions) -> Self {
+        self.kerberos_options = kerberos_options;
+        self
+    }
+
     /// Resolve provided AuthenticationClasses via API calls and validate the contents.
     /// Currently errors out if:
     /// - AuthenticationClass could not be resolved
@@ -87,7 +587,30 @@ impl ResolvedAuthenticationClasses {
             );
         }
 
-        ResolvedAuthenticationClasses::new(resolved_authentication_classes).validate()
+        let tls_options = auth_classes
+            .iter()
+            .find_map(|auth_class| auth_class.tls.clone())
+            .unwrap_or_default();
+        let oidc_options = auth_classes
+            .iter()
+            .find_map(|auth_class| auth_class.oidc.clone())
+            .unwrap_or_default();
+        let kerberos_options = auth_classes
+            .iter()
+            .find_map(|auth_class| auth_class.kerberos.clone())
+            .unwrap_or_default();
+
+        ResolvedAuthenticationClasses::new(resolved_authentication_classes)
+            .with_listeners(
+                auth_classes
+                    .iter()
+                    .map(|auth_class| auth_class.listener.clone())
+                    .collect(),
+            )
+            .with_tls_options(tls_options)
+            .with_oidc_options(oidc_options)
+            .with_kerberos_options(kerberos_options)
+            .validate()
     }
 
     /// Return the (first) TLS `AuthenticationClass` if available
@@ -104,30 +627,172 @@ impl ResolvedAuthenticationClasses {
             .find(|auth| matches!(auth.spec.provider, AuthenticationClassProvider::Kerberos(_)))
     }
 
+    /// Return the (first) Static `AuthenticationClass` if available
+    pub fn get_static_authentication_class(&self) -> Option<&AuthenticationClass> {
+        self.resolved_authentication_classes
+            .iter()
+            .find(|auth| matches!(auth.spec.provider, AuthenticationClassProvider::Static(_)))
+    }
+
+    /// Return the (first) OIDC `AuthenticationClass` if available
+    pub fn get_oidc_authentication_class(&self) -> Option<&AuthenticationClass> {
+        self.resolved_authentication_classes
+            .iter()
+            .find(|auth| matches!(auth.spec.provider, AuthenticationClassProvider::Oidc(_)))
+    }
+
+    /// Return the (first) LDAP `AuthenticationClass` if available
+    pub fn get_ldap_authentication_class(&self) -> Option<&AuthenticationClass> {
+        self.resolved_authentication_classes
+            .iter()
+            .find(|auth| matches!(auth.spec.provider, AuthenticationClassProvider::Ldap(_)))
+    }
+
+    /// Return the `AuthenticationClass` with the given name if available
+    pub fn get_authentication_class(&self, name: &str) -> Option<&AuthenticationClass> {
+        self.resolved_authentication_classes
+            .iter()
+            .find(|auth| auth.name_any() == name)
+    }
+
+    /// Return all `AuthenticationClass`es in the order they were provided, together with their
+    /// dedicated listener. The first one is served by the default client listener.
+    pub fn authentication_classes_with_listeners(
+        &self,
+    ) -> impl Iterator<Item = (&AuthenticationClass, Option<&KafkaAuthenticationListener>)> {
+        self.resolved_authentication_classes
+            .iter()
+            .enumerate()
+            .map(|(index, auth_class)| {
+                (
+                    auth_class,
+                    self.listeners.get(index).and_then(Option::as_ref),
+                )
+            })
+    }
+
+    /// The Kafka specific options of the TLS `AuthenticationClass`
+    pub fn tls_options(&self) -> &KafkaTlsAuthenticationOptions {
+        &self.tls_options
+    }
+
+    /// The Kafka specific options of the OIDC `AuthenticationClass`
+    pub fn oidc_options(&self) -> &KafkaOidcOptions {
+        &self.oidc_options
+    }
+
+    /// The Kafka specific options of the Kerberos `AuthenticationClass`
+    pub fn kerberos_options(&self) -> &KafkaKerberosOptions {
+        &self.kerberos_options
+    }
+
     /// Validates the resolved AuthenticationClasses.
     /// Currently errors out if:
-    /// - More than one AuthenticationClass was provided
+    /// - More than one AuthenticationClass with the same provider was provided
+    /// - The first AuthenticationClass has a dedicated listener or a further one has none
+    /// - The name of a dedicated listener is invalid or not unique
     /// - AuthenticationClass provider was not supported
+    /// - The OIDC provider does not verify the TLS certificate of the identity provider
+    /// - The LDAP provider does not verify the TLS certificate of the LDAP server
     pub fn validate(&self) -> Result<Self, Error> {
-        if self.resolved_authentication_classes.len() > 1 {
-            return Err(Error::MultipleAuthenticationClassesProvided);
-        }
+        let mut providers = BTreeSet::new();
+        let mut listener_names = BTreeSet::new();
 
-        for auth_class in &self.resolved_authentication_classes {
-            match &auth_class.spec.provider {
-                // explicitly list each branch so new elements do not get overlooked
-                AuthenticationClassProvider::Tls(_) | AuthenticationClassProvider::Kerberos(_) => {}
-                AuthenticationClassProvider::Static(_)
-                | AuthenticationClassProvider::Ldap(_)
-                | AuthenticationClassProvider::Oidc(_) => {
-                    return Err(Error::AuthenticationProviderNotSupported {
+        for (index, (auth_class, listener)) in
+            self.authentication_classes_with_listeners().enumerate()
+        {
+            let provider = auth_class.spec.provider.to_string();
+            ensure!(
+                providers.insert(provider.clone()),
+                MultipleAuthenticationClassesOfProviderSnafu { provider }
+            );
+
+            match (index, listener) {
+                (0, Some(_)) => {
+                    return Err(Error::UnexpectedListener {
                         authentication_class: ObjectRef::from_obj(auth_class),
-                        provider: auth_class.spec.provider.to_string(),
                     })
                 }
+                (0, None) => {}
+                (_, None) => {
+                    return Err(Error::ListenerRequired {
+                        authentication_class: ObjectRef::from_obj(auth_class),
+                    })
+                }
+                (_, Some(listener)) => {
+                    validate_listener_name(&listener.name)?;
+                    ensure!(
+                        listener_names.insert(listener.name.clone()),
+                        DuplicateListenerNameSnafu {
+                            name: listener.name.clone()
+                        }
+                    );
+                }
+            }
+
+            match &auth_class.spec.provider {
+                // explicitly list each branch so new elements do not get overlooked
+                AuthenticationClassProvider::Tls(_)
+                | AuthenticationClassProvider::Kerberos(_)
+                | AuthenticationClassProvider::Static(_) => {}
+                AuthenticationClassProvider::Oidc(oidc) => {
+                    // Kafka can only trust the CA of the identity provider, it cannot skip the
+                    // verification of its certificate
+                    if oidc.tls.uses_tls() && !oidc.tls.uses_tls_verification() {
+                        return Err(Error::OidcTlsVerificationRequired {
+                            authentication_class: ObjectRef::from_obj(auth_class),
+                        });
+                    }
+                }
+                AuthenticationClassProvider::Ldap(ldap) => {
+                    // The callback handler can only trust the CA of the LDAP server, it cannot
+                    // skip the verification of its certificate
+                    if ldap.tls.uses_tls() && !ldap.tls.uses_tls_verification() {
+                        return Err(Error::LdapTlsVerificationRequired {
+                            authentication_class: ObjectRef::from_obj(auth_class),
+                        });
+                    }
+                }
             }
         }
 
+        // Kafka only fails on invalid rules when the broker starts, so they are checked upfront.
+        // Java supports more regex features than the regex crate, but e.g. look-arounds are not
+        // needed to match a distinguished name.
+        for rule in &self.tls_options.principal_mapping_rules {
+            regex::Regex::new(&rule.pattern).context(InvalidPrincipalMappingRuleSnafu {
+                pattern: rule.pattern.clone(),
+            })?;
+        }
+        for rule in &self.kerberos_options.principal_to_local_rules {
+            rule.validate()?;
+        }
+        for trusted_realm in &self.kerberos_options.trusted_realms {
+            trusted_realm.validate()?;
+        }
+
         Ok(self.clone())
     }
 }
+
+/// Validates the name of a dedicated or user-defined listener.
+pub fn validate_listener_name(name: &str) -> Result<(), Error> {
+    let valid = name.len() <= MAX_LISTENER_NAME_LENGTH
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    ensure!(
+        valid,
+        InvalidListenerNameSnafu {
+            name: name.to_string()
+        }
+    );
+    ensure!(
+        !RESERVED_LISTENER_NAMES.contains(&name),
+        ReservedListenerNameSnafu {
+            name: name.to_string()
+        }
+    );
+    Ok(())
+}
@@ -1,12 +1,134 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use stackable_operator::{
-    commons::opa::OpaConfig,
+    commons::{opa::OpaConfig, tls_verification::TlsClientDetails},
     schemars::{self, JsonSchema},
+    time::Duration,
 };
 
+use crate::acl::AclBinding;
+
+/// The authorizer of ZooKeeper based brokers, which stores the ACLs in ZooKeeper.
+pub const ACL_AUTHORIZER_CLASS: &str = "kafka.security.authorizer.AclAuthorizer";
+/// The authorizer of KRaft based brokers and controllers, which stores the ACLs in the metadata log.
+pub const STANDARD_AUTHORIZER_CLASS: &str =
+    "org.apache.kafka.metadata.authorizer.StandardAuthorizer";
+/// The brokers, controllers and admin Jobs authenticate with a certificate of the internal
+/// SecretClass. With OPA or ACLs, all of them are mapped to this super user, which is the
+/// principal the operator administers the cluster with.
+pub const INTERNAL_USER_NAME: &str = "stackable-internal";
+// settings of the OPA authorizer
+const OPA_AUTHORIZER_CACHE_INITIAL_CAPACITY: &str = "opa.authorizer.cache.initial.capacity";
+const OPA_AUTHORIZER_CACHE_MAXIMUM_SIZE: &str = "opa.authorizer.cache.maximum.size";
+const OPA_AUTHORIZER_CACHE_EXPIRE_AFTER_SECONDS: &str = "opa.authorizer.cache.expire.after.seconds";
+
 #[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KafkaAuthorization {
+    /// Authorize requests with the policies of an OPA cluster.
+    pub opa: Option<KafkaOpaConfig>,
+
+    /// Authorize requests with ACLs, using the authorizer built into Kafka.
+    /// Cannot be combined with `opa` and requires internal TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acls: Option<KafkaAclAuthorization>,
+
+    /// Principals which are allowed all operations, regardless of the ACLs or OPA policies, e.g.
+    /// `User:alice`. The principal of the operator, `User:stackable-internal`, is always a super
+    /// user.
+    #[serde(default)]
+    pub super_users: Vec<String>,
+}
+
+impl KafkaAuthorization {
+    /// Whether requests are authorized by OPA or ACLs.
+    pub fn is_enabled(&self) -> bool {
+        self.opa.is_some() || self.acls.is_some()
+    }
+
+    /// Returns the value of `super.users`, which always contains the principal of the operator.
+    pub fn super_users_config(&self) -> String {
+        std::iter::once(format!("User:{INTERNAL_USER_NAME}"))
+            .chain(self.super_users.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaOpaConfig {
     // no doc - docs in the OpaConfig struct.
-    pub opa: Option<OpaConfig>,
+    #[serde(flatten)]
+    pub config: OpaConfig,
+
+    /// The TLS settings of the connection to OPA, if the discovery ConfigMap contains an `https`
+    /// URL. The CA of OPA is mounted from a SecretClass, skipping the verification of the
+    /// certificate is not supported by the authorizer.
+    #[serde(flatten)]
+    pub tls: TlsClientDetails,
+
+    /// The cache of the decisions of OPA in the brokers.
+    #[serde(default)]
+    pub cache: KafkaOpaCache,
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaOpaCache {
+    /// The number of decisions the cache is initially sized for. Defaults to 5000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_capacity: Option<u32>,
+
+    /// The maximum number of cached decisions. Defaults to 50000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum_size: Option<u32>,
+
+    /// How long a decision is cached, e.g. `5m`. Changes of the policies or of the data in OPA
+    /// only take effect after this period. Defaults to `1h`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_after: Option<Duration>,
+}
+
+impl KafkaOpaCache {
+    /// Returns the cache settings of the OPA authorizer which are set, the authorizer applies its
+    /// defaults to the others.
+    pub fn config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if let Some(initial_capacity) = self.initial_capacity {
+            config.insert(
+                OPA_AUTHORIZER_CACHE_INITIAL_CAPACITY.to_string(),
+                initial_capacity.to_string(),
+            );
+        }
+        if let Some(maximum_size) = self.maximum_size {
+            config.insert(
+                OPA_AUTHORIZER_CACHE_MAXIMUM_SIZE.to_string(),
+                maximum_size.to_string(),
+            );
+        }
+        if let Some(expire_after) = self.expire_after {
+            config.insert(
+                OPA_AUTHORIZER_CACHE_EXPIRE_AFTER_SECONDS.to_string(),
+                expire_after.as_secs().to_string(),
+            );
+        }
+
+        config
+    }
+}
+
+#[derive(Clone, Deserialize, Debug, Default, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaAclAuthorization {
+    /// Allow all requests on resources without any ACL. Defaults to `false`.
+    #[serde(default)]
+    pub allow_everyone_if_no_acl_found: bool,
+
+    /// ACL bindings which are applied to the cluster. They are managed in a KafkaAcl named
+    /// `<cluster name>-cluster-acls`, further bindings can be declared in additional KafkaAcls.
+    #[serde(default)]
+    pub bindings: Vec<AclBinding>,
 }
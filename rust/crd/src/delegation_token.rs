@@ -0,0 +1,323 @@
+//! The [`KafkaDelegationToken`] custom resource, which issues a delegation token of a
+//! [`KafkaCluster`](crate::KafkaCluster) and writes it to a Secret.
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use stackable_operator::{
+    k8s_openapi::apimachinery::pkg::apis::meta::v1::Time,
+    kube::{CustomResource, ResourceExt},
+    schemars::{self, JsonSchema},
+    time::Duration,
+};
+
+use crate::{
+    admin::{admin_tool, shell_quote, KafkaClusterRef, SyncStatus, ADMIN_JOB_TERMINATION_LOG},
+    authorization::INTERNAL_USER_NAME,
+    KafkaCluster,
+};
+
+/// The key of the Secret with the secret key the brokers sign the tokens with, which is also the
+/// name of the mounted file.
+pub const DELEGATION_TOKEN_SECRET_KEY: &str = "secret.key";
+// keys of the generated Secret
+pub const TOKEN_ID_SECRET_KEY: &str = "tokenId";
+pub const HMAC_SECRET_KEY: &str = "hmac";
+/// Tokens expire after one day unless they are renewed, see `delegation.token.expiry.time.ms`.
+const MAX_LIFETIME: Duration = Duration::from_days_unchecked(1);
+const DEFAULT_LIFETIME: Duration = Duration::from_hours_unchecked(12);
+// keys of the termination message of the admin Job
+const TOKEN_ID_OUTPUT_KEY: &str = "tokenId";
+const HMAC_OUTPUT_KEY: &str = "hmac";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display(
+        "the principal {principal:?} is invalid: it must have the form '<type>:<name>', e.g. 'User:alice'"
+    ))]
+    InvalidPrincipal { principal: String },
+
+    #[snafu(display("the lifetime {lifetime} must be between 1m and {MAX_LIFETIME}"))]
+    InvalidLifetime { lifetime: Duration },
+
+    #[snafu(display(
+        "the owner {owner:?} is not allowed: it must be listed in clusterConfig.delegationTokenOwners of the KafkaCluster"
+    ))]
+    OwnerNotAllowed { owner: String },
+
+    #[snafu(display("the owner {owner:?} is not allowed: tokens are not issued for super users"))]
+    SuperUserOwner { owner: String },
+}
+
+/// A delegation token of a Kafka cluster managed by the Stackable operator for Apache Kafka.
+/// The operator issues the token for the owner and writes it to a Secret, before it expires a new
+/// token is issued. Find more information on how to use it in the
+/// [operator documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/delegation-tokens).
+#[derive(Clone, CustomResource, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[kube(
+    group = "kafka.stackable.tech",
+    version = "v1alpha1",
+    kind = "KafkaDelegationToken",
+    plural = "kafkadelegationtokens",
+    status = "KafkaDelegationTokenStatus",
+    namespaced,
+    crates(
+        kube_core = "stackable_operator::kube::core",
+        k8s_openapi = "stackable_operator::k8s_openapi",
+        schemars = "stackable_operator::schemars"
+    )
+)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaDelegationTokenSpec {
+    /// The KafkaCluster the token is issued by.
+    pub cluster_ref: KafkaClusterRef,
+
+    /// The principal which owns the token, e.g. `User:spark`. Clients which authenticate with the
+    /// token act as this principal.
+    pub owner: String,
+
+    /// Principals which may renew the token, e.g. `User:spark-driver`.
+    #[serde(default)]
+    pub renewers: Vec<String>,
+
+    /// How long the token is valid, at most `1d`. A new token is issued after half of the
+    /// lifetime. Defaults to `12h`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifetime: Option<Duration>,
+
+    /// The name of the Secret the token is written to. Defaults to the name of the
+    /// KafkaDelegationToken object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaDelegationTokenStatus {
+    #[serde(flatten)]
+    pub sync: SyncStatus,
+
+    /// The ID of the token in the Secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+
+    /// When the token in the Secret was issued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_time: Option<Time>,
+
+    /// The number of tokens issued so far.
+    #[serde(default)]
+    pub issued_tokens: u32,
+}
+
+/// A delegation token as reported by the admin Job.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegationToken {
+    pub token_id: String,
+    pub hmac: String,
+}
+
+impl KafkaDelegationToken {
+    pub fn secret_name(&self) -> String {
+        self.spec
+            .secret_name
+            .clone()
+            .unwrap_or_else(|| self.name_any())
+    }
+
+    pub fn lifetime(&self) -> Result<Duration, Error> {
+        let lifetime = self.spec.lifetime.unwrap_or(DEFAULT_LIFETIME);
+        ensure!(
+            lifetime >= Duration::from_minutes_unchecked(1) && lifetime <= MAX_LIFETIME,
+            InvalidLifetimeSnafu { lifetime }
+        );
+        Ok(lifetime)
+    }
+
+    /// The admin Job issues the token with the privileges of the operator, so the cluster has to
+    /// allow the owner explicitly. Super users are refused, as a token would grant unrestricted
+    /// access to everyone who can read the Secret.
+    pub fn validate_owner(&self, kafka: &KafkaCluster) -> Result<(), Error> {
+        let owner = &self.spec.owner;
+        let authorization = &kafka.spec.cluster_config.authorization;
+        ensure!(
+            *owner != format!("User:{INTERNAL_USER_NAME}")
+                && !authorization.super_users.contains(owner),
+            SuperUserOwnerSnafu { owner }
+        );
+        ensure!(
+            kafka
+                .spec
+                .cluster_config
+                .delegation_token_owners
+                .contains(owner),
+            OwnerNotAllowedSnafu { owner }
+        );
+        Ok(())
+    }
+
+    /// Returns the commands that issue a token and write it to the termination message of the
+    /// admin Job, from where it is read by the operator.
+    pub fn create_commands(&self) -> Result<String, Error> {
+        let mut principals = vec![format!(
+            "--owner-principal {}",
+            shell_quote(&validate_principal(&self.spec.owner)?)
+        )];
+        for renewer in &self.spec.renewers {
+            principals.push(format!(
+                "--renewer-principal {}",
+                shell_quote(&validate_principal(renewer)?)
+            ));
+        }
+
+        // The output lists the token below a header starting with TOKENID, e.g.
+        // TOKENID  HMAC  OWNER  ...
+        // <id>     <hmac> User:spark ...
+        Ok(formatdoc! {"
+            # The token must not end up in the log of the Job
+            set +x
+            {tokens} --create --max-life-time-period {lifetime_ms} {principals} > /tmp/delegation-token
+            awk '/^TOKENID/ {{ getline; print \"{TOKEN_ID_OUTPUT_KEY}=\" $1; print \"{HMAC_OUTPUT_KEY}=\" $2; exit }}' /tmp/delegation-token > {ADMIN_JOB_TERMINATION_LOG}
+            grep -q '^{HMAC_OUTPUT_KEY}=.' {ADMIN_JOB_TERMINATION_LOG}",
+            tokens = admin_tool("kafka-delegation-tokens.sh"),
+            lifetime_ms = self.lifetime()?.as_millis(),
+            principals = principals.join(" "),
+        })
+    }
+}
+
+impl DelegationToken {
+    /// Parses the termination message written by [`KafkaDelegationToken::create_commands`].
+    pub fn from_output(lines: &[String]) -> Option<Self> {
+        let value = |key: &str| {
+            lines.iter().find_map(|line| {
+                line.strip_prefix(key)
+                    .and_then(|line| line.strip_prefix('='))
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+            })
+        };
+
+        Some(Self {
+            token_id: value(TOKEN_ID_OUTPUT_KEY)?,
+            hmac: value(HMAC_OUTPUT_KEY)?,
+        })
+    }
+
+    /// The JAAS configuration (`sasl.jaas.config`) Kafka clients use to log in with the token.
+    pub fn jaas_config(&self) -> String {
+        format!(
+            "org.apache.kafka.common.security.scram.ScramLoginModule required username=\"{}\" password=\"{}\" tokenauth=\"true\";",
+            self.token_id, self.hmac
+        )
+    }
+}
+
+fn validate_principal(principal: &str) -> Result<String, Error> {
+    ensure!(
+        principal
+            .split_once(':')
+            .is_some_and(|(principal_type, name)| !principal_type.is_empty() && !name.is_empty())
+            && !principal.contains(char::is_control),
+        InvalidPrincipalSnafu { principal }
+    );
+    Ok(principal.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    fn delegation_token(spec: &str) -> KafkaDelegationToken {
+        let input = format!(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaDelegationToken
+        metadata:
+          name: spark
+          namespace: default
+        spec:
+          clusterRef:
+            name: simple-kafka
+          {spec}
+        "#
+        );
+        serde_yaml::from_str(&input).expect("illegal test input")
+    }
+
+    #[test]
+    fn test_create_commands() {
+        let token = delegation_token(
+            "owner: User:spark\n          renewers:\n            - User:spark-driver",
+        );
+
+        assert_eq!(token.secret_name(), "spark");
+        let commands = token.create_commands().unwrap();
+        assert!(commands.starts_with("# The token must not end up in the log of the Job\nset +x\n"));
+        assert!(commands.contains(
+            "--create --max-life-time-period 43200000 --owner-principal 'User:spark' --renewer-principal 'User:spark-driver' > /tmp/delegation-token"
+        ));
+    }
+
+    #[rstest]
+    #[case("owner: spark", false)]
+    #[case("owner: User:spark\n          lifetime: 2d", false)]
+    #[case("owner: User:spark\n          lifetime: 1h", true)]
+    fn test_validate(#[case] spec: &str, #[case] valid: bool) {
+        assert_eq!(delegation_token(spec).create_commands().is_ok(), valid);
+    }
+
+    #[rstest]
+    #[case("User:spark", true)]
+    #[case("User:flink", false)]
+    #[case("User:alice", false)]
+    #[case("User:stackable-internal", false)]
+    fn test_validate_owner(#[case] owner: &str, #[case] valid: bool) {
+        let kafka: KafkaCluster = serde_yaml::from_str(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            delegationTokenOwners:
+              - User:spark
+              - User:alice
+              - User:stackable-internal
+            authorization:
+              superUsers:
+                - User:alice
+            zookeeperConfigMapName: xyz
+        "#,
+        )
+        .expect("illegal test input");
+
+        assert_eq!(
+            delegation_token(&format!("owner: {owner}"))
+                .validate_owner(&kafka)
+                .is_ok(),
+            valid
+        );
+    }
+
+    #[test]
+    fn test_from_output() {
+        let lines = vec![
+            "tokenId=vo3pTcaZRRuy6TlQbQ0uGA".to_string(),
+            "hmac=dGhpcyBpcyBub3QgYSByZWFsIGhtYWM=".to_string(),
+        ];
+        let token = DelegationToken::from_output(&lines).unwrap();
+        assert_eq!(token.token_id, "vo3pTcaZRRuy6TlQbQ0uGA");
+        assert_eq!(
+            token.jaas_config(),
+            "org.apache.kafka.common.security.scram.ScramLoginModule required username=\"vo3pTcaZRRuy6TlQbQ0uGA\" password=\"dGhpcyBpcyBub3QgYSByZWFsIGhtYWM=\" tokenauth=\"true\";"
+        );
+
+        assert_eq!(DelegationToken::from_output(&["hmac=".to_string()]), None);
+    }
+}
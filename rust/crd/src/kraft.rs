@@ -0,0 +1,724 @@
+//! Helpers to run Apache Kafka in KRaft mode, i.e. with a quorum of controllers managing the
+//! cluster metadata instead of ZooKeeper.
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_operator::{
+    k8s_openapi::{apimachinery::pkg::apis::meta::v1::Time, chrono::Utc},
+    kube::ResourceExt,
+    role_utils::RoleGroupRef,
+    schemars::{self, JsonSchema},
+    status::condition::ClusterConditionStatus,
+    utils::cluster_info::KubernetesClusterInfo,
+};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::{listener::KafkaListenerName, security::KafkaTlsSecurity, KafkaCluster, KafkaRole};
+
+// server.properties
+const PROCESS_ROLES: &str = "process.roles";
+const CONTROLLER_QUORUM_VOTERS: &str = "controller.quorum.voters";
+const CONTROLLER_LISTENER_NAMES: &str = "controller.listener.names";
+const ZOOKEEPER_METADATA_MIGRATION_ENABLE: &str = "zookeeper.metadata.migration.enable";
+
+/// The phases of the migration from ZooKeeper to KRaft, in the order they are rolled out.
+/// See the [Kafka documentation](https://kafka.apache.org/documentation/#kraft_zk_migration) for details.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    Eq,
+    JsonSchema,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+pub enum KraftMigrationPhase {
+    /// The KRaft controllers are deployed in migration mode, the brokers still use ZooKeeper.
+    Controllers,
+    /// The brokers are restarted with the migration enabled and register with the controllers,
+    /// which then copy the metadata from ZooKeeper.
+    Brokers,
+    /// The brokers are restarted in KRaft mode. The controllers still write the metadata to
+    /// ZooKeeper, so the brokers can be rolled back to ZooKeeper.
+    Kraft,
+    /// The controllers are restarted without the migration. ZooKeeper is not used anymore.
+    Finalize,
+}
+
+impl KraftMigrationPhase {
+    /// The phase following this one, if any.
+    pub fn next(&self) -> Option<Self> {
+        Self::iter().find(|phase| phase > self)
+    }
+
+    /// The migration states the controllers report once this phase is completed.
+    pub fn completed_states(&self) -> &'static [ZkMigrationState] {
+        match self {
+            // The controllers wait for the brokers to register before copying the metadata
+            Self::Controllers => &[ZkMigrationState::PreMigration, ZkMigrationState::Migration],
+            // The metadata is copied and the controllers write it to ZooKeeper as well
+            Self::Brokers | Self::Kraft => &[ZkMigrationState::Migration],
+            Self::Finalize => &[ZkMigrationState::PostMigration],
+        }
+    }
+}
+
+/// The state of the migration as reported by the `ZkMigrationState` metric of the active KRaft
+/// controller, see [KIP-866](https://cwiki.apache.org/confluence/display/KAFKA/KIP-866+ZooKeeper+to+KRaft+Migration).
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum ZkMigrationState {
+    None,
+    Migration,
+    PreMigration,
+    PostMigration,
+    Zk,
+}
+
+impl ZkMigrationState {
+    /// The name of the metric as exported by the JMX exporter.
+    pub const METRIC_NAME: &'static str = "kafka_controller_kafkacontroller_zkmigrationstate";
+
+    /// Reads the state from the Prometheus metrics of a controller. Returns `None` if the metric
+    /// is missing, which is the case for controllers that are not active.
+    pub fn from_metrics(metrics: &str) -> Option<Self> {
+        metrics
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .find_map(|line| {
+                let (name, value) = line.rsplit_once(' ')?;
+                let name = name.split('{').next()?.trim();
+                if !name.eq_ignore_ascii_case(Self::METRIC_NAME) {
+                    return None;
+                }
+                Self::from_metric_value(value.trim().parse().ok()?)
+            })
+    }
+
+    fn from_metric_value(value: f64) -> Option<Self> {
+        // The metric is a gauge, so the state is exported as a float
+        match value as i64 {
+            0 => Some(Self::None),
+            1 => Some(Self::Migration),
+            2 => Some(Self::PreMigration),
+            3 => Some(Self::PostMigration),
+            4 => Some(Self::Zk),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KraftMigrationStatus {
+    /// The phase of the migration that is currently rolled out.
+    pub phase: KraftMigrationPhase,
+
+    /// One condition per phase that was started, which is `True` once the phase is rolled out.
+    #[serde(default)]
+    pub conditions: Vec<KraftMigrationCondition>,
+}
+
+impl KraftMigrationStatus {
+    /// Whether the current phase is still rolled out, i.e. the operator has to check again.
+    pub fn is_pending(&self) -> bool {
+        !self.conditions.iter().any(|condition| {
+            condition.type_ == self.phase && condition.status == ClusterConditionStatus::True
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KraftMigrationCondition {
+    #[serde(rename = "type")]
+    pub type_: KraftMigrationPhase,
+    pub status: ClusterConditionStatus,
+    pub last_transition_time: Option<Time>,
+    pub message: Option<String>,
+}
+
+/// How a role stores and accesses the cluster metadata.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MetadataMode {
+    /// The metadata is stored in ZooKeeper.
+    ZooKeeper,
+    /// A ZooKeeper based broker, which additionally registers with the KRaft controllers during
+    /// the migration.
+    ZooKeeperMigration,
+    /// A KRaft controller, which copies the metadata from and to ZooKeeper during the migration.
+    KraftMigration,
+    /// The metadata is managed by the KRaft controllers.
+    Kraft,
+}
+
+impl MetadataMode {
+    /// Whether the process runs in KRaft mode and therefore needs a `node.id`.
+    pub fn is_kraft(&self) -> bool {
+        matches!(self, Self::KraftMigration | Self::Kraft)
+    }
+
+    /// Whether the process connects to the KRaft controllers.
+    pub fn uses_controllers(&self) -> bool {
+        !matches!(self, Self::ZooKeeper)
+    }
+
+    /// Whether the process connects to ZooKeeper.
+    pub fn uses_zookeeper(&self) -> bool {
+        !matches!(self, Self::Kraft)
+    }
+
+    pub fn is_migrating(&self) -> bool {
+        matches!(self, Self::ZooKeeperMigration | Self::KraftMigration)
+    }
+}
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("object has no uid associated, which is required for the KRaft cluster id"))]
+    NoUid,
+
+    #[snafu(display("the object uid [{uid}] is not a valid UUID"))]
+    InvalidUid { uid: String },
+
+    #[snafu(display("failed to list the KRaft controller pods"))]
+    ListControllerPods { source: crate::Error },
+}
+
+/// Whether the given id is a valid KRaft cluster id, i.e. a UUID in the URL-safe base64 encoding
+/// without padding.
+pub fn is_valid_cluster_id(cluster_id: &str) -> bool {
+    cluster_id.len() == 22
+        && URL_SAFE_NO_PAD
+            .decode(cluster_id)
+            .is_ok_and(|bytes| bytes.len() == 16)
+}
+
+/// The id of the KRaft cluster, which is required to format the metadata log directories.
+///
+/// Kafka expects a UUID in the URL-safe base64 encoding without padding. Unless configured
+/// explicitly (which is required when migrating from ZooKeeper), we derive it from the uid of
+/// the [`KafkaCluster`] object, so it stays stable for the lifetime of the stacklet.
+pub fn cluster_id(kafka: &KafkaCluster) -> Result<String, Error> {
+    if let Some(cluster_id) = &kafka.spec.cluster_config.kraft_cluster_id {
+        return Ok(cluster_id.clone());
+    }
+
+    let uid = kafka.uid().context(NoUidSnafu)?;
+    uuid_to_kafka_id(&uid).context(InvalidUidSnafu { uid })
+}
+
+/// Returns the offset that is added to the Pod ordinal to build the `node.id` of a Kafka process.
+///
+/// In KRaft mode every broker and controller needs a `node.id`, which must be unique across all
+/// roles and rolegroups. The offset is derived from the role and rolegroup name (instead of e.g.
+/// the rolegroup position) so that adding, removing or scaling rolegroups does not change the ids
+/// of existing nodes.
+pub fn node_id_offset(rolegroup_ref: &RoleGroupRef<KafkaCluster>) -> u32 {
+    // FNV-1a, as the hashers from std are not guaranteed to be stable across Rust releases
+    let hash = format!("{}/{}", rolegroup_ref.role, rolegroup_ref.role_group)
+        .bytes()
+        .fold(0x811c9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+        });
+    // Leaves room for 1000 replicas per rolegroup while staying within the (signed) int range
+    (hash % 2_000_000) * 1000
+}
+
+/// Returns the `controller.quorum.voters` in the form `{node_id}@{host}:{port},...`.
+pub fn quorum_voters(
+    kafka: &KafkaCluster,
+    kafka_security: &KafkaTlsSecurity,
+    cluster_info: &KubernetesClusterInfo,
+) -> Result<String, Error> {
+    let port = kafka_security.controller_port();
+    Ok(kafka
+        .controller_pods()
+        .context(ListControllerPodsSnafu)?
+        .map(|pod| {
+            format!(
+                "{node_id}@{fqdn}:{port}",
+                node_id = pod.node_id,
+                fqdn = pod.fqdn(cluster_info)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Returns the KRaft related settings for the `server.properties` file of the given rolegroup.
+///
+/// The `node.id` depends on the Pod ordinal and is therefore set in the container command.
+pub fn config_settings(
+    kafka: &KafkaCluster,
+    role: &KafkaRole,
+    rolegroup_name: &str,
+    kafka_security: &KafkaTlsSecurity,
+    cluster_info: &KubernetesClusterInfo,
+) -> Result<BTreeMap<String, String>, Error> {
+    let metadata_mode = kafka.metadata_mode(role);
+    let mut config = BTreeMap::new();
+
+    if metadata_mode.uses_controllers() {
+        config.insert(
+            CONTROLLER_QUORUM_VOTERS.to_string(),
+            quorum_voters(kafka, kafka_security, cluster_info)?,
+        );
+        config.insert(
+            CONTROLLER_LISTENER_NAMES.to_string(),
+            KafkaListenerName::Controller.to_string(),
+        );
+    }
+    if metadata_mode.is_kraft() {
+        let process_roles = if *role == KafkaRole::Broker
+            && kafka.is_combined_controller_rolegroup(rolegroup_name)
+        {
+            format!("{},{}", KafkaRole::Broker, KafkaRole::Controller)
+        } else {
+            role.to_string()
+        };
+        config.insert(PROCESS_ROLES.to_string(), process_roles);
+    }
+    if metadata_mode.is_migrating() {
+        config.insert(
+            ZOOKEEPER_METADATA_MIGRATION_ENABLE.to_string(),
+            "true".to_string(),
+        );
+    }
+
+    Ok(config)
+}
+
+/// Computes the migration status after the current phase was applied.
+///
+/// The operator only moves on to the next phase once all StatefulSets are rolled out and the
+/// controllers report the migration state the phase leads to (see
+/// [`KraftMigrationPhase::completed_states`]), and never beyond the phase requested in the spec.
+/// Returns `None` if no migration is requested.
+pub fn next_migration_status(
+    kafka: &KafkaCluster,
+    rolled_out: bool,
+    controller_states: &[ZkMigrationState],
+) -> Option<KraftMigrationStatus> {
+    let target_phase = kafka.spec.cluster_config.kraft_migration?;
+    let phase = kafka.kraft_migration_phase()?;
+    let mut conditions = kafka
+        .status
+        .as_ref()
+        .and_then(|status| status.kraft_migration.as_ref())
+        .map(|migration| migration.conditions.clone())
+        .unwrap_or_default();
+
+    let completed_states = phase.completed_states();
+    let migrated = controller_states
+        .iter()
+        .any(|state| completed_states.contains(state));
+    let completed = rolled_out && migrated;
+
+    let phase_status = if completed {
+        ClusterConditionStatus::True
+    } else {
+        ClusterConditionStatus::False
+    };
+    let message = if completed {
+        format!("Phase {phase} is completed")
+    } else if rolled_out {
+        format!(
+            "Phase {phase} is rolled out, waiting for the controllers to report the migration state {expected}",
+            expected = completed_states
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" or ")
+        )
+    } else {
+        format!("Rolling out phase {phase}")
+    };
+    update_condition(&mut conditions, phase, phase_status, message);
+
+    let next_phase = match phase.next() {
+        Some(next_phase) if completed && next_phase <= target_phase => {
+            update_condition(
+                &mut conditions,
+                next_phase,
+                ClusterConditionStatus::False,
+                format!("Rolling out phase {next_phase}"),
+            );
+            next_phase
+        }
+        _ => phase,
+    };
+
+    Some(KraftMigrationStatus {
+        phase: next_phase,
+        conditions,
+    })
+}
+
+fn update_condition(
+    conditions: &mut Vec<KraftMigrationCondition>,
+    phase: KraftMigrationPhase,
+    status: ClusterConditionStatus,
+    message: String,
+) {
+    match conditions
+        .iter_mut()
+        .find(|condition| condition.type_ == phase)
+    {
+        Some(condition) => {
+            if condition.status != status {
+                condition.last_transition_time = Some(Time(Utc::now()));
+            }
+            condition.status = status;
+            condition.message = Some(message);
+        }
+        None => conditions.push(KraftMigrationCondition {
+            type_: phase,
+            status,
+            last_transition_time: Some(Time(Utc::now())),
+            message: Some(message),
+        }),
+    }
+}
+
+fn uuid_to_kafka_id(uuid: &str) -> Option<String> {
+    let hex = uuid.replace('-', "");
+    if hex.len() != 32 {
+        return None;
+    }
+    let bytes = (0..16)
+        .map(|i| u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use indoc::indoc;
+    use rstest::rstest;
+    use stackable_operator::commons::networking::DomainName;
+
+    use crate::authentication::ResolvedAuthenticationClasses;
+
+    #[rstest]
+    #[case("00000000-0000-0000-0000-000000000000", Some("AAAAAAAAAAAAAAAAAAAAAA"))]
+    #[case("f3b8a9d2-4c1e-4f5a-9b7d-2e6c8a1f0b34", Some("87ip0kweT1qbfS5sih8LNA"))]
+    #[case("f3b8a9d2-4c1e-4f5a-9b7d", None)]
+    #[case("x3b8a9d2-4c1e-4f5a-9b7d-2e6c8a1f0b34", None)]
+    fn test_uuid_to_kafka_id(#[case] uuid: &str, #[case] expected: Option<&str>) {
+        assert_eq!(uuid_to_kafka_id(uuid).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("87ip0kweT1qbfS5sih8LNA", true)]
+    #[case("AAAAAAAAAAAAAAAAAAAAAA", true)]
+    // Too short or too long
+    #[case("87ip0kweT1qbfS5sih8LN", false)]
+    #[case("87ip0kweT1qbfS5sih8LNAA", false)]
+    // Padded
+    #[case("87ip0kweT1qbfS5sih8LNA==", false)]
+    // Standard instead of URL-safe alphabet
+    #[case("87ip0kweT1qbfS5sih8L+A", false)]
+    // Trailing bits beyond the 16 bytes
+    #[case("87ip0kweT1qbfS5sih8LNB", false)]
+    #[case("my-cluster-id", false)]
+    fn test_is_valid_cluster_id(#[case] cluster_id: &str, #[case] expected: bool) {
+        assert_eq!(is_valid_cluster_id(cluster_id), expected);
+    }
+
+    #[rstest]
+    #[case(
+        indoc! {"
+            # HELP kafka_controller_kafkacontroller_zkmigrationstate Attribute exposed for management
+            # TYPE kafka_controller_kafkacontroller_zkmigrationstate untyped
+            kafka_controller_kafkacontroller_zkmigrationstate 2.0
+        "},
+        Some(ZkMigrationState::PreMigration)
+    )]
+    #[case(
+        "kafka_controller_kafkacontroller_zkmigrationstate{instance=\"controller-0\"} 1.0\n",
+        Some(ZkMigrationState::Migration)
+    )]
+    #[case("kafka_controller_kafkacontroller_activecontrollercount 1.0\n", None)]
+    #[case("kafka_controller_kafkacontroller_zkmigrationstate 7.0\n", None)]
+    fn test_zk_migration_state_from_metrics(
+        #[case] metrics: &str,
+        #[case] expected: Option<ZkMigrationState>,
+    ) {
+        assert_eq!(ZkMigrationState::from_metrics(metrics), expected);
+    }
+
+    #[test]
+    fn test_kraft_migration_phase_order() {
+        assert_eq!(
+            KraftMigrationPhase::Controllers.next(),
+            Some(KraftMigrationPhase::Brokers)
+        );
+        assert_eq!(
+            KraftMigrationPhase::Kraft.next(),
+            Some(KraftMigrationPhase::Finalize)
+        );
+        assert_eq!(KraftMigrationPhase::Finalize.next(), None);
+    }
+
+    #[rstest]
+    #[case(
+        None,
+        KraftMigrationPhase::Controllers,
+        true,
+        &[ZkMigrationState::PreMigration],
+        KraftMigrationPhase::Brokers
+    )]
+    #[case(
+        None,
+        KraftMigrationPhase::Controllers,
+        false,
+        &[ZkMigrationState::PreMigration],
+        KraftMigrationPhase::Controllers
+    )]
+    // The controllers are rolled out, but did not start the migration yet
+    #[case(
+        None,
+        KraftMigrationPhase::Controllers,
+        true,
+        &[],
+        KraftMigrationPhase::Controllers
+    )]
+    #[case(
+        None,
+        KraftMigrationPhase::Controllers,
+        true,
+        &[ZkMigrationState::None],
+        KraftMigrationPhase::Controllers
+    )]
+    #[case(
+        Some(KraftMigrationPhase::Brokers),
+        KraftMigrationPhase::Brokers,
+        true,
+        &[ZkMigrationState::Migration],
+        KraftMigrationPhase::Brokers
+    )]
+    // The metadata is not copied from ZooKeeper yet
+    #[case(
+        Some(KraftMigrationPhase::Brokers),
+        KraftMigrationPhase::Finalize,
+        true,
+        &[ZkMigrationState::PreMigration],
+        KraftMigrationPhase::Brokers
+    )]
+    #[case(
+        Some(KraftMigrationPhase::Brokers),
+        KraftMigrationPhase::Finalize,
+        true,
+        &[ZkMigrationState::Migration],
+        KraftMigrationPhase::Kraft
+    )]
+    #[case(
+        Some(KraftMigrationPhase::Kraft),
+        KraftMigrationPhase::Finalize,
+        true,
+        &[ZkMigrationState::Migration],
+        KraftMigrationPhase::Finalize
+    )]
+    fn test_next_migration_status(
+        #[case] current_phase: Option<KraftMigrationPhase>,
+        #[case] target_phase: KraftMigrationPhase,
+        #[case] rolled_out: bool,
+        #[case] controller_states: &[ZkMigrationState],
+        #[case] expected_phase: KraftMigrationPhase,
+    ) {
+        let input = format!(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+            kraftClusterId: 87ip0kweT1qbfS5sih8LNA
+            kraftMigration: {target_phase}
+          brokers:
+            roleGroups:
+              default:
+                replicas: 3
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#
+        );
+        let mut kafka: KafkaCluster = serde_yaml::from_str(&input).expect("illegal test input");
+        kafka.status = current_phase.map(|phase| crate::KafkaClusterStatus {
+            conditions: vec![],
+            kraft_migration: Some(KraftMigrationStatus {
+                phase,
+                conditions: vec![],
+            }),
+            ..crate::KafkaClusterStatus::default()
+        });
+
+        let status = next_migration_status(&kafka, rolled_out, controller_states).unwrap();
+        assert_eq!(status.phase, expected_phase);
+        assert_eq!(
+            status.conditions.last().map(|condition| condition.type_),
+            Some(expected_phase)
+        );
+    }
+
+    #[test]
+    fn test_migration_metadata_mode() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+            kraftClusterId: 87ip0kweT1qbfS5sih8LNA
+            kraftMigration: Finalize
+          brokers:
+            roleGroups:
+              default:
+                replicas: 3
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+
+        // No status yet, so the migration starts with the first phase
+        assert_eq!(
+            kafka.kraft_migration_phase(),
+            Some(KraftMigrationPhase::Controllers)
+        );
+        assert_eq!(
+            kafka.metadata_mode(&KafkaRole::Broker),
+            MetadataMode::ZooKeeper
+        );
+        assert_eq!(
+            kafka.metadata_mode(&KafkaRole::Controller),
+            MetadataMode::KraftMigration
+        );
+        assert_eq!(cluster_id(&kafka).unwrap(), "87ip0kweT1qbfS5sih8LNA");
+    }
+
+    #[test]
+    fn test_combined_controller_config_settings() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig: {}
+          brokers:
+            roleGroups:
+              combined:
+                replicas: 1
+                config:
+                  combinedController: true
+              default:
+                replicas: 2
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "internalTls".to_string(),
+            None,
+        );
+        let cluster_info = KubernetesClusterInfo {
+            cluster_domain: DomainName::try_from("cluster.local").unwrap(),
+        };
+
+        assert!(kafka.validate_metadata_quorum().is_ok());
+
+        let config = config_settings(
+            &kafka,
+            &KafkaRole::Broker,
+            "combined",
+            &kafka_security,
+            &cluster_info,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get(PROCESS_ROLES).map(String::as_str),
+            Some("broker,controller")
+        );
+        let combined_offset = node_id_offset(&KafkaRole::Broker.rolegroup_ref(&kafka, "combined"));
+        assert_eq!(
+            config.get(CONTROLLER_QUORUM_VOTERS),
+            Some(&format!(
+                "{combined_offset}@simple-kafka-broker-combined-0.simple-kafka-broker-combined.default.svc.cluster.local:{port}",
+                port = KafkaTlsSecurity::SECURE_CONTROLLER_PORT,
+            ))
+        );
+
+        let config = config_settings(
+            &kafka,
+            &KafkaRole::Broker,
+            "default",
+            &kafka_security,
+            &cluster_info,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get(PROCESS_ROLES).map(String::as_str),
+            Some("broker")
+        );
+    }
+
+    #[test]
+    fn test_node_id_offset() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          brokers:
+            roleGroups:
+              default:
+                replicas: 3
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+
+        let broker_offset = node_id_offset(&KafkaRole::Broker.rolegroup_ref(&kafka, "default"));
+        let controller_offset =
+            node_id_offset(&KafkaRole::Controller.rolegroup_ref(&kafka, "default"));
+
+        assert_ne!(broker_offset, controller_offset);
+        assert_eq!(broker_offset % 1000, 0);
+        assert!(i32::try_from(broker_offset + 999).is_ok());
+        assert_eq!(
+            broker_offset,
+            node_id_offset(&KafkaRole::Broker.rolegroup_ref(&kafka, "default"))
+        );
+    }
+}
@@ -1,17 +1,31 @@
+pub mod acl;
+pub mod admin;
 pub mod affinity;
 pub mod authentication;
 pub mod authorization;
+pub mod delegation_token;
+pub mod kraft;
 pub mod listener;
+pub mod rack_awareness;
 pub mod security;
 pub mod tls;
+pub mod topic;
+pub mod user;
+pub mod zookeeper;
 
-use crate::authorization::KafkaAuthorization;
-use crate::tls::KafkaTls;
+use crate::authorization::{
+    KafkaAuthorization, ACL_AUTHORIZER_CLASS, INTERNAL_USER_NAME, STANDARD_AUTHORIZER_CLASS,
+};
+use crate::tls::{KafkaTls, KafkaTlsStoreFormat};
+use crate::zookeeper::KafkaZookeeperConfig;
 
 use affinity::get_affinity;
 use authentication::KafkaAuthentication;
+use kraft::{KraftMigrationPhase, KraftMigrationStatus, MetadataMode};
+use listener::KafkaAdditionalListener;
+use rack_awareness::KafkaRackAwareness;
 use serde::{Deserialize, Serialize};
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use stackable_operator::{
     commons::{
         affinity::StackableAffinity,
@@ -38,7 +52,10 @@ use stackable_operator::{
     time::Duration,
     utils::cluster_info::KubernetesClusterInfo,
 };
-use std::{collections::BTreeMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
 use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
 pub const DOCKER_IMAGE_BASE_NAME: &str = "kafka";
@@ -50,6 +67,8 @@ pub const METRICS_PORT: u16 = 9606;
 // config files
 pub const SERVER_PROPERTIES_FILE: &str = "server.properties";
 pub const JVM_SECURITY_PROPERTIES_FILE: &str = "security.properties";
+pub const KRB5_TRUSTED_REALMS_FILE: &str = "krb5-trusted-realms.conf";
+pub const ZOOKEEPER_JAAS_CONFIG_FILE: &str = "zookeeper-jaas.conf";
 // env vars
 pub const KAFKA_HEAP_OPTS: &str = "KAFKA_HEAP_OPTS";
 // server_properties
@@ -66,6 +85,17 @@ pub const STACKABLE_LOG_DIR: &str = "/stackable/log";
 // kerberos
 pub const STACKABLE_KERBEROS_DIR: &str = "/stackable/kerberos";
 pub const STACKABLE_KERBEROS_KRB5_PATH: &str = "/stackable/kerberos/krb5.conf";
+/// The `krb5.conf` of the `SecretClass` extended by the trusted realms
+pub const STACKABLE_TRUSTED_REALMS_KRB5_PATH: &str = "/tmp/krb5.conf";
+// ldap
+pub const STACKABLE_LDAP_BIND_CREDENTIALS_DIR: &str = "/stackable/ldap-bind-credentials";
+// delegation tokens
+pub const STACKABLE_DELEGATION_TOKEN_DIR: &str = "/stackable/delegation-token";
+// TLS stores
+pub const STACKABLE_TLS_STORE_PASSWORD_DIR: &str = "/stackable/tls-store-password";
+// zookeeper
+pub const STACKABLE_ZOOKEEPER_KERBEROS_DIR: &str = "/stackable/zookeeper-kerberos";
+pub const STACKABLE_ZOOKEEPER_KERBEROS_KRB5_PATH: &str = "/stackable/zookeeper-kerberos/krb5.conf";
 
 const DEFAULT_BROKER_GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_minutes_unchecked(30);
 
@@ -101,6 +131,50 @@ pub enum Error {
 
     #[snafu(display("fragment validation failure"))]
     FragmentValidationFailure { source: ValidationError },
+
+    #[snafu(display("either a ZooKeeper connection (zookeeperConfigMapName) or KRaft controllers must be configured"))]
+    NoMetadataQuorum,
+
+    #[snafu(display("a ZooKeeper connection (zookeeperConfigMapName) and KRaft controllers cannot be configured at the same time unless migrating (kraftMigration)"))]
+    MultipleMetadataQuorums,
+
+    #[snafu(display("a KRaft migration requires a ZooKeeper connection (zookeeperConfigMapName) and KRaft controllers"))]
+    IncompleteKraftMigration,
+
+    #[snafu(display(
+        "a KRaft migration requires the id of the existing cluster (kraftClusterId)"
+    ))]
+    MissingKraftClusterId,
+
+    #[snafu(display(
+        "the KRaft cluster id (kraftClusterId) {cluster_id:?} is not a UUID in the URL-safe base64 encoding without padding, i.e. 22 characters"
+    ))]
+    InvalidKraftClusterId { cluster_id: String },
+
+    #[snafu(display(
+        "brokers in combined mode (combinedController) cannot be migrated from ZooKeeper"
+    ))]
+    CombinedControllerMigration,
+
+    #[snafu(display(
+        "OPA (opa) and ACLs (acls) cannot be used for authorization at the same time"
+    ))]
+    MultipleAuthorizers,
+
+    #[snafu(display(
+        "authorization with ACLs (acls) requires internal TLS (tls.internalSecretClass), which secures the login of the brokers"
+    ))]
+    AclsRequireInternalTls,
+
+    #[snafu(display(
+        "the OPA authorizer can only trust the CA of OPA, it cannot skip the verification of its certificate"
+    ))]
+    OpaTlsVerificationRequired,
+
+    #[snafu(display(
+        "the super user [{principal}] must have the form <type>:<name>, e.g. User:alice, and must not contain ';'"
+    ))]
+    InvalidSuperUser { principal: String },
 }
 
 /// A Kafka cluster stacklet. This resource is managed by the Stackable operator for Apache Kafka.
@@ -129,6 +203,10 @@ pub struct KafkaClusterSpec {
     // no doc - docs in Role struct.
     pub brokers: Option<Role<KafkaConfigFragment>>,
 
+    // no doc - docs in Role struct.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controllers: Option<Role<KafkaConfigFragment>>,
+
     /// Kafka settings that affect all roles and role groups.
     /// The settings in the `clusterConfig` are cluster wide settings that do not need to be configurable at role or role group level.
     pub cluster_config: KafkaClusterConfig,
@@ -142,6 +220,8 @@ pub struct KafkaClusterSpec {
 #[serde(rename_all = "camelCase")]
 pub struct KafkaClusterConfig {
     /// Authentication class settings for Kafka like mTLS authentication.
+    /// Multiple AuthenticationClasses with different providers can be used at the same time,
+    /// each of them is served by its own listener.
     #[serde(default)]
     pub authentication: Vec<KafkaAuthentication>,
 
@@ -149,6 +229,19 @@ pub struct KafkaClusterConfig {
     #[serde(default)]
     pub authorization: KafkaAuthorization,
 
+    /// Principals which `KafkaDelegationToken` objects may issue delegation tokens for, e.g.
+    /// `User:spark`. Everyone who can create a KafkaDelegationToken in the namespace of the
+    /// cluster can act as these principals. Tokens are never issued for super users.
+    #[serde(default)]
+    pub delegation_token_owners: Vec<String>,
+
+    /// User-defined listeners in addition to the listeners of the AuthenticationClasses, e.g. a
+    /// replication listener on a dedicated ListenerClass or a listener for sidecars, which is only
+    /// bound to the loopback interface. Every exposed listener gets its own listener volumes,
+    /// container port and discovery ConfigMap.
+    #[serde(default)]
+    pub listeners: Vec<KafkaAdditionalListener>,
+
     /// TLS encryption settings for Kafka (server, internal).
     #[serde(
         default = "tls::default_kafka_tls",
@@ -163,11 +256,42 @@ pub struct KafkaClusterConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vector_aggregator_config_map_name: Option<String>,
 
-    /// Kafka requires a ZooKeeper cluster connection to run.
-    /// Provide the name of the ZooKeeper [discovery ConfigMap](DOCS_BASE_URL_PLACEHOLDER/concepts/service_discovery)
+    /// Kafka stores its metadata either in ZooKeeper or in a quorum of KRaft controllers.
+    /// To use ZooKeeper, provide the name of the ZooKeeper [discovery ConfigMap](DOCS_BASE_URL_PLACEHOLDER/concepts/service_discovery)
     /// here. When using the [Stackable operator for Apache ZooKeeper](DOCS_BASE_URL_PLACEHOLDER/zookeeper/)
     /// to deploy a ZooKeeper cluster, this will simply be the name of your ZookeeperCluster resource.
-    pub zookeeper_config_map_name: String,
+    /// Leave this empty and configure the `controllers` role instead to run Kafka in KRaft mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zookeeper_config_map_name: Option<String>,
+
+    /// Security settings of the connection to ZooKeeper, which only apply if
+    /// `zookeeperConfigMapName` is set.
+    #[serde(default)]
+    pub zookeeper: KafkaZookeeperConfig,
+
+    /// Migrate the metadata of a ZooKeeper based cluster to the KRaft controllers.
+    /// The operator rolls out one phase after another, up to and including the given phase.
+    /// Both `zookeeperConfigMapName` and the `controllers` role must be configured during the migration.
+    /// Once the `Finalize` phase is rolled out, `zookeeperConfigMapName` and this field can be removed.
+    /// Read the [KRaft migration documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/kraft#_migrating_from_zookeeper)
+    /// before starting a migration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kraft_migration: Option<KraftMigrationPhase>,
+
+    /// The id of the KRaft cluster, which is derived from the uid of the KafkaCluster if not set.
+    /// It is a UUID in the URL-safe base64 encoding without padding, i.e. 22 characters.
+    /// When migrating from ZooKeeper, this must be set to the id of the existing cluster,
+    /// which is stored as `cluster.id` in the `meta.properties` file of the brokers.
+    /// The controllers refuse to start if it differs from the id stored in ZooKeeper.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kraft_cluster_id: Option<String>,
+
+    /// Spread the replicas of the partitions across racks, e.g. availability zones. The rack of a
+    /// broker is the value of a label of the Kubernetes node it runs on.
+    /// Read the [rack awareness documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/rack-awareness)
+    /// before enabling it on an existing cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rack_awareness: Option<KafkaRackAwareness>,
 }
 
 impl KafkaCluster {
@@ -182,16 +306,183 @@ impl KafkaCluster {
         &self,
         group_name: impl Into<String>,
     ) -> RoleGroupRef<KafkaCluster> {
-        RoleGroupRef {
-            cluster: ObjectRef::from_obj(self),
-            role: KafkaRole::Broker.to_string(),
-            role_group: group_name.into(),
+        KafkaRole::Broker.rolegroup_ref(self, group_name)
+    }
+
+    /// Whether the brokers of the given rolegroup also run a KRaft controller (combined mode).
+    pub fn is_combined_controller_rolegroup(&self, rolegroup_name: &str) -> bool {
+        let Some(brokers) = &self.spec.brokers else {
+            return false;
+        };
+        brokers
+            .role_groups
+            .get(rolegroup_name)
+            .and_then(|rolegroup| rolegroup.config.config.combined_controller)
+            .or(brokers.config.config.combined_controller)
+            .unwrap_or_default()
+    }
+
+    /// Whether any broker rolegroup runs in combined mode.
+    pub fn has_combined_controllers(&self) -> bool {
+        self.spec.brokers.iter().any(|brokers| {
+            brokers
+                .role_groups
+                .keys()
+                .any(|rolegroup_name| self.is_combined_controller_rolegroup(rolegroup_name))
+        })
+    }
+
+    /// Ensures that exactly one way of managing the cluster metadata (ZooKeeper or KRaft) is configured,
+    /// unless a migration from ZooKeeper to KRaft is requested, which requires both.
+    /// The KRaft controllers are either the `controllers` role or brokers in combined mode.
+    /// An explicitly configured KRaft cluster id must be valid, as Kafka refuses to start otherwise.
+    pub fn validate_metadata_quorum(&self) -> Result<(), Error> {
+        let cluster_config = &self.spec.cluster_config;
+        if let Some(cluster_id) = &cluster_config.kraft_cluster_id {
+            ensure!(
+                kraft::is_valid_cluster_id(cluster_id),
+                InvalidKraftClusterIdSnafu { cluster_id }
+            );
+        }
+        let has_controllers = self.spec.controllers.is_some() || self.has_combined_controllers();
+        match (
+            cluster_config.zookeeper_config_map_name.is_some(),
+            has_controllers,
+            cluster_config.kraft_migration.is_some(),
+        ) {
+            (true, false, false) | (false, true, false) => Ok(()),
+            (true, true, true) => {
+                ensure!(
+                    !self.has_combined_controllers(),
+                    CombinedControllerMigrationSnafu
+                );
+                ensure!(
+                    cluster_config.kraft_cluster_id.is_some(),
+                    MissingKraftClusterIdSnafu
+                );
+                Ok(())
+            }
+            (false, false, false) => NoMetadataQuorumSnafu.fail(),
+            (true, true, false) => MultipleMetadataQuorumsSnafu.fail(),
+            (_, _, true) => IncompleteKraftMigrationSnafu.fail(),
+        }
+    }
+
+    /// Ensures that at most one authorizer is configured. ACLs require internal TLS, as the
+    /// brokers and controllers are only granted access based on their internal certificates.
+    /// The OPA authorizer must verify the certificate of OPA if the connection uses TLS.
+    pub fn validate_authorization(&self) -> Result<(), Error> {
+        let authorization = &self.spec.cluster_config.authorization;
+        // `super.users` is a list separated by `;`
+        for principal in &authorization.super_users {
+            ensure!(
+                !principal.contains(';')
+                    && principal
+                        .split_once(':')
+                        .is_some_and(|(r#type, name)| !r#type.is_empty() && !name.is_empty()),
+                InvalidSuperUserSnafu { principal }
+            );
+        }
+        if let Some(opa) = &authorization.opa {
+            ensure!(
+                !opa.tls.uses_tls() || opa.tls.uses_tls_verification(),
+                OpaTlsVerificationRequiredSnafu
+            );
+        }
+        if authorization.acls.is_some() {
+            ensure!(authorization.opa.is_none(), MultipleAuthorizersSnafu);
+            let internal_secret_class = self
+                .spec
+                .cluster_config
+                .tls
+                .as_ref()
+                .map(|tls| tls.internal_secret_class.clone())
+                .unwrap_or_else(tls::internal_tls_default);
+            ensure!(
+                !internal_secret_class.is_empty(),
+                AclsRequireInternalTlsSnafu
+            );
+        }
+        Ok(())
+    }
+
+    /// The name of the KafkaAcl which holds the ACL bindings declared in the cluster spec.
+    pub fn cluster_acl_name(&self) -> String {
+        format!("{}-cluster-acls", self.name_any())
+    }
+
+    /// The name of the generated Secret with the key the delegation tokens are signed with.
+    pub fn delegation_token_secret_name(&self) -> String {
+        format!("{}-delegation-token", self.name_any())
+    }
+
+    /// The name of the generated Secret with the password of the SCRAM user the readiness probe
+    /// of the brokers logs in with.
+    pub fn scram_prober_secret_name(&self) -> String {
+        format!("{}-scram-prober", self.name_any())
+    }
+
+    /// The name of the generated Secret with the password of the internal super user, which the
+    /// brokers, controllers and admin Jobs log in with if an authorizer is configured.
+    pub fn internal_credentials_secret_name(&self) -> String {
+        format!("{}-internal-credentials", self.name_any())
+    }
+
+    /// The name of the generated Secret with the password of the PKCS12 keystores and
+    /// truststores.
+    pub fn tls_store_password_secret_name(&self) -> String {
+        format!("{}-tls-store-password", self.name_any())
+    }
+
+    pub fn tls_store_format(&self) -> KafkaTlsStoreFormat {
+        self.spec
+            .cluster_config
+            .tls
+            .as_ref()
+            .map(|tls| tls.store_format)
+            .unwrap_or_default()
+    }
+
+    /// The phase of the ZooKeeper to KRaft migration that is currently rolled out.
+    ///
+    /// Returns `None` if no migration is requested. The migration starts with the first phase and
+    /// is advanced by the operator, which tracks the current phase in the status.
+    pub fn kraft_migration_phase(&self) -> Option<KraftMigrationPhase> {
+        self.spec.cluster_config.kraft_migration?;
+        Some(
+            self.status
+                .as_ref()
+                .and_then(|status| status.kraft_migration.as_ref())
+                .map(|migration| migration.phase)
+                .unwrap_or(KraftMigrationPhase::Controllers),
+        )
+    }
+
+    /// How the given role stores and accesses the cluster metadata.
+    pub fn metadata_mode(&self, role: &KafkaRole) -> MetadataMode {
+        let Some(phase) = self.kraft_migration_phase() else {
+            return if self.spec.cluster_config.zookeeper_config_map_name.is_some() {
+                MetadataMode::ZooKeeper
+            } else {
+                MetadataMode::Kraft
+            };
+        };
+
+        match (role, phase) {
+            (KafkaRole::Broker, KraftMigrationPhase::Controllers) => MetadataMode::ZooKeeper,
+            (KafkaRole::Broker, KraftMigrationPhase::Brokers) => MetadataMode::ZooKeeperMigration,
+            (KafkaRole::Broker, KraftMigrationPhase::Kraft | KraftMigrationPhase::Finalize) => {
+                MetadataMode::Kraft
+            }
+            (KafkaRole::Controller, KraftMigrationPhase::Finalize) => MetadataMode::Kraft,
+            (KafkaRole::Controller, _) => MetadataMode::KraftMigration,
         }
     }
 
     pub fn role(&self, role_variant: &KafkaRole) -> Result<&Role<KafkaConfigFragment>, Error> {
         match role_variant {
             KafkaRole::Broker => self.spec.brokers.as_ref(),
+            KafkaRole::Controller => self.spec.controllers.as_ref(),
         }
         .with_context(|| CannotRetrieveKafkaRoleSnafu {
             role: role_variant.to_string(),
@@ -219,30 +510,62 @@ impl KafkaCluster {
     pub fn role_config(&self, role: &KafkaRole) -> Option<&GenericRoleConfig> {
         match role {
             KafkaRole::Broker => self.spec.brokers.as_ref().map(|b| &b.role_config),
+            KafkaRole::Controller => self.spec.controllers.as_ref().map(|c| &c.role_config),
         }
     }
 
-    /// List all pods expected to form the cluster
+    /// List all broker pods expected to form the cluster
     ///
     /// We try to predict the pods here rather than looking at the current cluster state in order to
     /// avoid instance churn.
     pub fn pods(&self) -> Result<impl Iterator<Item = KafkaPodRef> + '_, Error> {
-        let ns = self.metadata.namespace.clone().context(NoNamespaceSnafu)?;
-        Ok(self
+        self.role_pods(&KafkaRole::Broker)
+    }
+
+    /// List all pods running a KRaft controller, i.e. the controllers as well as the brokers in
+    /// combined mode
+    pub fn controller_pods(&self) -> Result<impl Iterator<Item = KafkaPodRef> + '_, Error> {
+        let combined_rolegroup_services = self
             .spec
             .brokers
             .iter()
+            .flat_map(|brokers| brokers.role_groups.keys())
+            .filter(|rolegroup_name| self.is_combined_controller_rolegroup(rolegroup_name))
+            .map(|rolegroup_name| self.broker_rolegroup_ref(rolegroup_name).object_name())
+            .collect::<BTreeSet<_>>();
+
+        Ok(self.role_pods(&KafkaRole::Controller)?.chain(
+            self.role_pods(&KafkaRole::Broker)?.filter(move |pod| {
+                combined_rolegroup_services.contains(&pod.role_group_service_name)
+            }),
+        ))
+    }
+
+    /// List all pods of the given role expected to form the cluster
+    pub fn role_pods(
+        &self,
+        role: &KafkaRole,
+    ) -> Result<impl Iterator<Item = KafkaPodRef> + '_, Error> {
+        let ns = self.metadata.namespace.clone().context(NoNamespaceSnafu)?;
+        let role_groups = self
+            .role(role)
+            .ok()
+            .into_iter()
             .flat_map(|role| &role.role_groups)
             // Order rolegroups consistently, to avoid spurious downstream rewrites
-            .collect::<BTreeMap<_, _>>()
+            .collect::<BTreeMap<_, _>>();
+        let role = role.clone();
+        Ok(role_groups
             .into_iter()
             .flat_map(move |(rolegroup_name, rolegroup)| {
-                let rolegroup_ref = self.broker_rolegroup_ref(rolegroup_name);
+                let rolegroup_ref = role.rolegroup_ref(self, rolegroup_name);
+                let node_id_offset = kraft::node_id_offset(&rolegroup_ref);
                 let ns = ns.clone();
                 (0..rolegroup.replicas.unwrap_or(0)).map(move |i| KafkaPodRef {
                     namespace: ns.clone(),
                     role_group_service_name: rolegroup_ref.object_name(),
                     pod_name: format!("{}-{}", rolegroup_ref.object_name(), i),
+                    node_id: node_id_offset + u32::from(i),
                 })
             }))
     }
@@ -285,6 +608,8 @@ pub struct KafkaPodRef {
     pub namespace: String,
     pub role_group_service_name: String,
     pub pod_name: String,
+    /// The `node.id` of the pod when running in KRaft mode.
+    pub node_id: u32,
 }
 
 impl KafkaPodRef {
@@ -315,6 +640,8 @@ impl KafkaPodRef {
 pub enum KafkaRole {
     #[strum(serialize = "broker")]
     Broker,
+    #[strum(serialize = "controller")]
+    Controller,
 }
 
 impl KafkaRole {
@@ -340,7 +667,7 @@ impl KafkaRole {
     }
 
     /// A Kerberos principal has three parts, with the form username/fully.qualified.domain.name@YOUR-REALM.COM.
-    /// We only have one role and will use "kafka" everywhere (which e.g. differs from the current hdfs implementation,
+    /// Only brokers expose Kerberos secured listeners and will use "kafka" everywhere (which e.g. differs from the current hdfs implementation,
     /// but is similar to HBase).
     pub fn kerberos_service_name(&self) -> &'static str {
         "kafka"
@@ -435,6 +762,13 @@ pub struct KafkaConfig {
     /// Please note that this can be shortened by the `maxCertificateLifetime` setting on the SecretClass issuing the TLS certificate.
     #[fragment_attrs(serde(default))]
     pub requested_secret_lifetime: Option<Duration>,
+
+    /// Run a KRaft controller in the same process as the broker (combined mode), instead of deploying
+    /// the `controllers` role. This saves resources, but is only recommended for small development or
+    /// edge clusters, as brokers and controllers cannot be scaled and restarted independently.
+    /// Only has an effect on brokers.
+    #[fragment_attrs(serde(default))]
+    pub combined_controller: bool,
 }
 
 impl KafkaConfig {
@@ -442,6 +776,12 @@ impl KafkaConfig {
     const DEFAULT_BROKER_SECRET_LIFETIME: Duration = Duration::from_days_unchecked(1);
 
     pub fn default_config(cluster_name: &str, role: &KafkaRole) -> KafkaConfigFragment {
+        // Controllers only store the (comparatively small) metadata log
+        let log_dirs_capacity = match role {
+            KafkaRole::Broker => "2Gi",
+            KafkaRole::Controller => "1Gi",
+        };
+
         KafkaConfigFragment {
             logging: product_logging::spec::default_logging(),
             resources: ResourcesFragment {
@@ -455,7 +795,7 @@ impl KafkaConfig {
                 },
                 storage: StorageFragment {
                     log_dirs: PvcConfigFragment {
-                        capacity: Some(Quantity("2Gi".to_owned())),
+                        capacity: Some(Quantity(log_dirs_capacity.to_owned())),
                         storage_class: None,
                         selectors: None,
                     },
@@ -466,6 +806,7 @@ impl KafkaConfig {
             bootstrap_listener_class: Some("cluster-internal".to_string()),
             broker_listener_class: Some("cluster-internal".to_string()),
             requested_secret_lifetime: Some(Self::DEFAULT_BROKER_SECRET_LIFETIME),
+            combined_controller: Some(false),
         }
     }
 }
@@ -494,7 +835,7 @@ impl Configuration for KafkaConfigFragment {
     fn compute_files(
         &self,
         resource: &Self::Configurable,
-        _role_name: &str,
+        role_name: &str,
         file: &str,
     ) -> Result<BTreeMap<String, Option<String>>, stackable_operator::product_config_utils::Error>
     {
@@ -502,7 +843,7 @@ impl Configuration for KafkaConfigFragment {
 
         if file == SERVER_PROPERTIES_FILE {
             // OPA
-            if resource.spec.cluster_config.authorization.opa.is_some() {
+            if let Some(opa) = &resource.spec.cluster_config.authorization.opa {
                 config.insert(
                     "authorizer.class.name".to_string(),
                     Some("org.openpolicyagent.kafka.OpaAuthorizer".to_string()),
@@ -511,6 +852,73 @@ impl Configuration for KafkaConfigFragment {
                     "opa.authorizer.metrics.enabled".to_string(),
                     Some("true".to_string()),
                 );
+                config.extend(
+                    opa.cache
+                        .config_settings()
+                        .into_iter()
+                        .map(|(k, v)| (k, Some(v))),
+                );
+            }
+
+            // ACLs
+            if let Some(acls) = &resource.spec.cluster_config.authorization.acls {
+                // KRaft controllers and brokers store the ACLs in the metadata log, ZooKeeper based
+                // brokers (also during a migration) in ZooKeeper.
+                let kraft = KafkaRole::from_str(role_name)
+                    .is_ok_and(|role| resource.metadata_mode(&role).is_kraft());
+                config.insert(
+                    "authorizer.class.name".to_string(),
+                    Some(
+                        if kraft {
+                            STANDARD_AUTHORIZER_CLASS
+                        } else {
+                            ACL_AUTHORIZER_CLASS
+                        }
+                        .to_string(),
+                    ),
+                );
+                config.insert(
+                    "allow.everyone.if.no.acl.found".to_string(),
+                    Some(acls.allow_everyone_if_no_acl_found.to_string()),
+                );
+            }
+
+            // Both authorizers allow super users all operations, so that the operator is never
+            // locked out by an OPA policy or a missing ACL.
+            let authorization = &resource.spec.cluster_config.authorization;
+            if authorization.is_enabled() {
+                // The brokers, controllers and admin Jobs log in to the internal listeners as the
+                // internal super user with a generated password, see
+                // `KafkaTlsSecurity::has_internal_credentials_enabled`. The certificates of the
+                // internal SecretClass are not mapped, as the secret-operator issues all of them
+                // with the same distinguished name, so they cannot tell the operator apart from
+                // any other Pod.
+                // With Kerberos between the brokers, the principal is taken from the Kerberos
+                // login instead. Only the service principals of the brokers and admin Jobs are
+                // mapped to the internal super user, all others fall through to the default rule.
+                if resource
+                    .spec
+                    .cluster_config
+                    .authentication
+                    .iter()
+                    .any(|auth| {
+                        auth.kerberos
+                            .as_ref()
+                            .is_some_and(|kerberos| kerberos.inter_broker)
+                    })
+                {
+                    config.insert(
+                        "listener.name.internal.sasl.kerberos.principal.to.local.rules".to_string(),
+                        Some(format!(
+                            "RULE:[2:$1]({service_name})s/.*/{INTERNAL_USER_NAME}/,DEFAULT",
+                            service_name = KafkaRole::Broker.kerberos_service_name(),
+                        )),
+                    );
+                }
+                config.insert(
+                    "super.users".to_string(),
+                    Some(authorization.super_users_config()),
+                );
             }
         }
 
@@ -523,6 +931,20 @@ impl Configuration for KafkaConfigFragment {
 pub struct KafkaClusterStatus {
     #[serde(default)]
     pub conditions: Vec<ClusterCondition>,
+
+    /// The progress of the migration from ZooKeeper to KRaft, if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kraft_migration: Option<KraftMigrationStatus>,
+
+    /// The name of the user credentials Secret of a Static AuthenticationClass, which is watched
+    /// for changes of the users.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scram_credentials_secret: Option<String>,
+
+    /// The `resourceVersion` of the user credentials Secret of a Static AuthenticationClass,
+    /// whose users were last bootstrapped as SCRAM credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scram_credentials_revision: Option<String>,
 }
 
 impl HasStatusCondition for KafkaCluster {
@@ -707,4 +1129,321 @@ mod tests {
             tls::internal_tls_default()
         );
     }
+
+    #[test]
+    fn test_validate_metadata_quorum() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig: {}
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert_eq!(kafka.metadata_mode(&KafkaRole::Broker), MetadataMode::Kraft);
+        assert!(kafka.validate_metadata_quorum().is_ok());
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig: {}
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_metadata_quorum(),
+            Err(Error::NoMetadataQuorum)
+        ));
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_metadata_quorum(),
+            Err(Error::MultipleMetadataQuorums)
+        ));
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+            kraftClusterId: my-cluster
+            kraftMigration: Controllers
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_metadata_quorum(),
+            Err(Error::InvalidKraftClusterId { .. })
+        ));
+    }
+
+    #[test]
+    fn test_acl_authorization() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              acls: {}
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(kafka.validate_authorization().is_ok());
+
+        let config = KafkaConfig::default_config("simple-kafka", &KafkaRole::Broker)
+            .compute_files(&kafka, "broker", SERVER_PROPERTIES_FILE)
+            .unwrap();
+        assert_eq!(
+            config.get("authorizer.class.name"),
+            Some(&Some(ACL_AUTHORIZER_CLASS.to_string()))
+        );
+        assert_eq!(
+            config.get("allow.everyone.if.no.acl.found"),
+            Some(&Some("false".to_string()))
+        );
+        assert_eq!(
+            config.get("super.users"),
+            Some(&Some("User:stackable-internal".to_string()))
+        );
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              acls: {}
+            tls:
+              internalSecretClass: ""
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_authorization(),
+            Err(Error::AclsRequireInternalTls)
+        ));
+
+        let config = KafkaConfig::default_config("simple-kafka", &KafkaRole::Controller)
+            .compute_files(&kafka, "controller", SERVER_PROPERTIES_FILE)
+            .unwrap();
+        assert_eq!(
+            config.get("authorizer.class.name"),
+            Some(&Some(STANDARD_AUTHORIZER_CLASS.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_super_users() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              opa:
+                configMapName: simple-opa
+              superUsers:
+                - User:alice
+                - User:CN=bob
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(kafka.validate_authorization().is_ok());
+
+        let config = KafkaConfig::default_config("simple-kafka", &KafkaRole::Broker)
+            .compute_files(&kafka, "broker", SERVER_PROPERTIES_FILE)
+            .unwrap();
+        assert_eq!(
+            config.get("super.users"),
+            Some(&Some(
+                "User:stackable-internal;User:alice;User:CN=bob".to_string()
+            ))
+        );
+        // The certificates of the internal listeners are not mapped to the internal super user
+        assert!(!config.contains_key("listener.name.internal.ssl.principal.mapping.rules"));
+        assert!(!config.contains_key("listener.name.controller.ssl.principal.mapping.rules"));
+        assert!(
+            !config.contains_key("listener.name.internal.sasl.kerberos.principal.to.local.rules")
+        );
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authentication:
+              - authenticationClass: kerberos
+                kerberos:
+                  interBroker: true
+            authorization:
+              acls: {}
+            tls:
+              internalSecretClass: kafka-internal
+              serverSecretClass: tls
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        let config = KafkaConfig::default_config("simple-kafka", &KafkaRole::Broker)
+            .compute_files(&kafka, "broker", SERVER_PROPERTIES_FILE)
+            .unwrap();
+        // Only the service principals of the brokers are super users
+        assert_eq!(
+            config.get("listener.name.internal.sasl.kerberos.principal.to.local.rules"),
+            Some(&Some(
+                "RULE:[2:$1](kafka)s/.*/stackable-internal/,DEFAULT".to_string()
+            ))
+        );
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              acls: {}
+              superUsers:
+                - alice
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_authorization(),
+            Err(Error::InvalidSuperUser { .. })
+        ));
+    }
+
+    #[test]
+    fn test_opa_authorization() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              opa:
+                configMapName: simple-opa
+                package: kafka/authz
+                tls:
+                  verification:
+                    server:
+                      caCert:
+                        secretClass: opa-tls
+                cache:
+                  maximumSize: 1000
+                  expireAfter: 5m
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(kafka.validate_authorization().is_ok());
+        let opa = kafka
+            .spec
+            .cluster_config
+            .authorization
+            .opa
+            .as_ref()
+            .unwrap();
+        assert_eq!(opa.config.config_map_name, "simple-opa");
+        assert_eq!(
+            opa.tls.tls_ca_cert_secret_class(),
+            Some("opa-tls".to_string())
+        );
+
+        let config = KafkaConfig::default_config("simple-kafka", &KafkaRole::Broker)
+            .compute_files(&kafka, "broker", SERVER_PROPERTIES_FILE)
+            .unwrap();
+        assert_eq!(
+            config.get("opa.authorizer.cache.maximum.size"),
+            Some(&Some("1000".to_string()))
+        );
+        assert_eq!(
+            config.get("opa.authorizer.cache.expire.after.seconds"),
+            Some(&Some("300".to_string()))
+        );
+        assert_eq!(config.get("opa.authorizer.cache.initial.capacity"), None);
+
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authorization:
+              opa:
+                configMapName: simple-opa
+                tls:
+                  verification:
+                    none: {}
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert!(matches!(
+            kafka.validate_authorization(),
+            Err(Error::OpaTlsVerificationRequired)
+        ));
+    }
 }
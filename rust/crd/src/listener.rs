@@ -1,15 +1,25 @@
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
+use serde::{Deserialize, Serialize};
 use snafu::{OptionExt, Snafu};
+use stackable_operator::commons::authentication::AuthenticationClassProvider;
 use stackable_operator::kube::ResourceExt;
+use stackable_operator::role_utils::RoleGroupRef;
+use stackable_operator::schemars::{self, JsonSchema};
 use stackable_operator::utils::cluster_info::KubernetesClusterInfo;
 use strum::{EnumDiscriminants, EnumString};
 
+use crate::authentication::KafkaAuthenticationListener;
 use crate::security::KafkaTlsSecurity;
-use crate::{KafkaCluster, STACKABLE_LISTENER_BROKER_DIR};
+use crate::{
+    KafkaCluster, KafkaRole, LISTENER_BOOTSTRAP_VOLUME_NAME, LISTENER_BROKER_VOLUME_NAME,
+    STACKABLE_LISTENER_BOOTSTRAP_DIR, STACKABLE_LISTENER_BROKER_DIR,
+};
 
 const LISTENER_LOCAL_ADDRESS: &str = "0.0.0.0";
+const LISTENER_LOOPBACK_ADDRESS: &str = "127.0.0.1";
+const LISTENER_LOOPBACK_HOST: &str = "localhost";
 
 #[derive(Snafu, Debug, EnumDiscriminants)]
 pub enum KafkaListenerError {
@@ -17,22 +27,51 @@ pub enum KafkaListenerError {
     ObjectHasNoNamespace,
 }
 
-#[derive(strum::Display, Debug, EnumString)]
+#[derive(
+    strum::Display,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumString,
+    Eq,
+    JsonSchema,
+    PartialEq,
+    Serialize,
+)]
 pub enum KafkaListenerProtocol {
-    /// Unencrypted and unauthenticated HTTP connections
+    /// Unencrypted and unauthenticated connections
+    #[serde(rename = "PLAINTEXT")]
     #[strum(serialize = "PLAINTEXT")]
     Plaintext,
 
-    /// Encrypted and server-authenticated HTTPS connections
+    /// Encrypted and server-authenticated connections
+    #[serde(rename = "SSL")]
     #[strum(serialize = "SSL")]
     Ssl,
 
-    /// Kerberos authentication
+    /// SASL authentication on encrypted connections
+    #[serde(rename = "SASL_SSL")]
     #[strum(serialize = "SASL_SSL")]
     SaslSsl,
+
+    /// Kerberos authentication without encryption, only used if explicitly requested
+    #[serde(rename = "SASL_PLAINTEXT")]
+    #[strum(serialize = "SASL_PLAINTEXT")]
+    SaslPlaintext,
 }
 
-#[derive(strum::Display, Debug, EnumString, Ord, Eq, PartialEq, PartialOrd)]
+impl KafkaListenerProtocol {
+    /// Whether connections with this protocol are encrypted with TLS.
+    pub fn uses_tls(&self) -> bool {
+        match self {
+            Self::Ssl | Self::SaslSsl => true,
+            Self::Plaintext | Self::SaslPlaintext => false,
+        }
+    }
+}
+
+#[derive(strum::Display, Clone, Debug, EnumString, Ord, Eq, PartialEq, PartialOrd)]
 pub enum KafkaListenerName {
     #[strum(serialize = "CLIENT")]
     Client,
@@ -42,6 +81,186 @@ pub enum KafkaListenerName {
     Internal,
     #[strum(serialize = "BOOTSTRAP")]
     Bootstrap,
+    #[strum(serialize = "CONTROLLER")]
+    Controller,
+    /// Any other listener, e.g. the dedicated listener of an additional AuthenticationClass
+    #[strum(default)]
+    Custom(String),
+}
+
+impl KafkaListenerName {
+    /// Returns the listener specific variant of a broker setting, e.g.
+    /// `listener.name.client.ssl.keystore.location` for `ssl.keystore.location`.
+    pub fn config_key(&self, key: &str) -> String {
+        format!("listener.name.{}.{key}", self.to_string().to_lowercase())
+    }
+}
+
+/// A user-defined listener in addition to the listeners of the AuthenticationClasses, e.g. for
+/// replication with MirrorMaker on a dedicated ListenerClass or for sidecars of the brokers.
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaAdditionalListener {
+    /// The name of the listener, which must start with a lowercase letter and consist of at most
+    /// 9 lowercase ASCII alphanumerics. It must differ from the names of the dedicated listeners
+    /// of the AuthenticationClasses. The Kafka listener is called `CLIENT_<NAME>`, its port
+    /// `kafka-<name>` and its discovery ConfigMap `<cluster name>-<name>`.
+    pub name: String,
+
+    /// The port the brokers listen on, which must not be used by any other listener.
+    pub port: u16,
+
+    /// The security protocol of the listener. Without an AuthenticationClass, this is `PLAINTEXT`
+    /// or `SSL`. A TLS AuthenticationClass requires `SSL`, a Static, OIDC or LDAP
+    /// AuthenticationClass requires `SASL_SSL`.
+    pub protocol: KafkaListenerProtocol,
+
+    /// The AuthenticationClass clients authenticate with on this listener. It must be one of the
+    /// AuthenticationClasses in `clusterConfig.authentication`, whose Kafka specific options also
+    /// apply to this listener. Kerberos is not supported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentication_class: Option<String>,
+
+    /// The ListenerClass used for connecting to brokers on this listener.
+    /// Defaults to the `brokerListenerClass` of the role group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broker_listener_class: Option<String>,
+
+    /// The ListenerClass used for bootstrapping clients of this listener.
+    /// Defaults to the `bootstrapListenerClass` of the role group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_listener_class: Option<String>,
+
+    /// Bind the listener to the loopback interface, so that only containers in the Pod of the
+    /// broker, e.g. sidecars, can connect to it on `localhost`. Such a listener has no listener
+    /// volumes and no discovery ConfigMap, and it must use `PLAINTEXT` without an
+    /// AuthenticationClass. Defaults to `false`.
+    #[serde(default)]
+    pub loopback: bool,
+}
+
+impl KafkaAdditionalListener {
+    /// The listener name and ListenerClasses, which are handled like the ones of a dedicated
+    /// listener of an AuthenticationClass.
+    pub fn authentication_listener(&self) -> KafkaAuthenticationListener {
+        KafkaAuthenticationListener {
+            name: self.name.clone(),
+            broker_listener_class: self.broker_listener_class.clone(),
+            bootstrap_listener_class: self.bootstrap_listener_class.clone(),
+        }
+    }
+}
+
+/// How clients authenticate on a client listener, derived from the provider of its
+/// AuthenticationClass.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KafkaClientAuthentication {
+    Tls,
+    Kerberos,
+    Scram,
+    Oidc,
+    Ldap,
+}
+
+impl KafkaClientAuthentication {
+    pub fn from_provider(provider: &AuthenticationClassProvider) -> Self {
+        match provider {
+            AuthenticationClassProvider::Tls(_) => Self::Tls,
+            AuthenticationClassProvider::Kerberos(_) => Self::Kerberos,
+            AuthenticationClassProvider::Static(_) => Self::Scram,
+            AuthenticationClassProvider::Oidc(_) => Self::Oidc,
+            AuthenticationClassProvider::Ldap(_) => Self::Ldap,
+        }
+    }
+
+    /// All methods besides TLS client authentication use SASL, which in turn requires TLS.
+    /// Kerberos can explicitly opt out of TLS, see
+    /// [`KafkaTlsSecurity::has_kerberos_sasl_plaintext_enabled`].
+    pub fn protocol(&self) -> KafkaListenerProtocol {
+        match self {
+            Self::Tls => KafkaListenerProtocol::Ssl,
+            Self::Kerberos | Self::Scram | Self::Oidc | Self::Ldap => {
+                KafkaListenerProtocol::SaslSsl
+            }
+        }
+    }
+}
+
+/// A listener for client connections. The first AuthenticationClass (or none at all) is served by
+/// the default client listener, every further AuthenticationClass by a dedicated listener with its
+/// own port, listener volumes and discovery ConfigMap. User-defined listeners are handled like
+/// dedicated listeners.
+#[derive(Clone, Debug)]
+pub struct KafkaClientListener {
+    pub name: KafkaListenerName,
+    pub protocol: KafkaListenerProtocol,
+    pub authentication: Option<KafkaClientAuthentication>,
+    pub port_name: String,
+    pub port: u16,
+    /// The dedicated listener of an additional AuthenticationClass or a user-defined listener,
+    /// `None` for the default client listener
+    pub dedicated: Option<KafkaAuthenticationListener>,
+    /// Whether the listener is bound to the loopback interface. It is not exposed, so it has
+    /// neither listener volumes nor a container port nor a discovery ConfigMap.
+    pub loopback: bool,
+}
+
+impl KafkaClientListener {
+    pub fn broker_listener_volume_name(&self) -> String {
+        self.with_dedicated_suffix(LISTENER_BROKER_VOLUME_NAME)
+    }
+
+    pub fn bootstrap_listener_volume_name(&self) -> String {
+        self.with_dedicated_suffix(LISTENER_BOOTSTRAP_VOLUME_NAME)
+    }
+
+    pub fn broker_listener_dir(&self) -> String {
+        self.with_dedicated_suffix(STACKABLE_LISTENER_BROKER_DIR)
+    }
+
+    pub fn bootstrap_listener_dir(&self) -> String {
+        self.with_dedicated_suffix(STACKABLE_LISTENER_BOOTSTRAP_DIR)
+    }
+
+    /// The name of the Listener which provides the bootstrap address of a broker role group.
+    pub fn bootstrap_listener_name(
+        &self,
+        kafka: &KafkaCluster,
+        rolegroup: &RoleGroupRef<KafkaCluster>,
+    ) -> String {
+        self.with_dedicated_suffix(&kafka.bootstrap_service_name(rolegroup))
+    }
+
+    /// The name of the discovery ConfigMap with the bootstrap servers of this listener.
+    pub fn discovery_config_map_name(&self, cluster_name: &str) -> String {
+        self.with_dedicated_suffix(cluster_name)
+    }
+
+    /// The port of the BOOTSTRAP listener, if this listener serves Kerberos.
+    pub fn bootstrap_port(&self) -> u16 {
+        if self.protocol.uses_tls() {
+            KafkaTlsSecurity::SECURE_BOOTSTRAP_PORT
+        } else {
+            KafkaTlsSecurity::BOOTSTRAP_PORT
+        }
+    }
+
+    /// The name of the port clients bootstrap with. With Kerberos, the bootstrap address has its
+    /// own principal and is therefore served by the BOOTSTRAP listener.
+    pub fn bootstrap_port_name(&self) -> &str {
+        if self.authentication == Some(KafkaClientAuthentication::Kerberos) {
+            KafkaTlsSecurity::BOOTSTRAP_PORT_NAME
+        } else {
+            &self.port_name
+        }
+    }
+
+    fn with_dedicated_suffix(&self, base: &str) -> String {
+        match &self.dedicated {
+            Some(dedicated) => format!("{base}-{}", dedicated.name),
+            None => base.to_string(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -72,6 +291,14 @@ impl KafkaListenerConfig {
             .join(",")
     }
 
+    /// Returns the advertised host of the given listener, if it is configured.
+    pub fn advertised_host(&self, name: &KafkaListenerName) -> Option<&str> {
+        self.advertised_listeners
+            .iter()
+            .find(|listener| &listener.name == name)
+            .map(|listener| listener.host.as_str())
+    }
+
     /// Returns the `listener.security.protocol.map` for the Kafka `server.properties` config.
     pub fn listener_security_protocol_map(&self) -> String {
         self.listener_security_protocol_map
@@ -95,10 +322,13 @@ impl Display for KafkaListener {
     }
 }
 
+/// Returns the listener config of the brokers. Brokers running in combined mode (`combined_controller`)
+/// additionally expose the CONTROLLER listener.
 pub fn get_kafka_listener_config(
     kafka: &KafkaCluster,
     kafka_security: &KafkaTlsSecurity,
     object_name: &str,
+    combined_controller: bool,
     cluster_info: &KubernetesClusterInfo,
 ) -> Result<KafkaListenerConfig, KafkaListenerError> {
     let pod_fqdn = pod_fqdn(kafka, object_name, cluster_info)?;
@@ -106,75 +336,34 @@ pub fn get_kafka_listener_config(
     let mut advertised_listeners = vec![];
     let mut listener_security_protocol_map = BTreeMap::new();
 
-    // CLIENT
-    if kafka_security.tls_client_authentication_class().is_some() {
-        // 1) If client authentication required, we expose only CLIENT_AUTH connection with SSL
-        listeners.push(KafkaListener {
-            name: KafkaListenerName::ClientAuth,
-            host: LISTENER_LOCAL_ADDRESS.to_string(),
-            port: kafka_security.client_port().to_string(),
-        });
-        advertised_listeners.push(KafkaListener {
-            name: KafkaListenerName::ClientAuth,
-            host: node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
-            port: node_port_cmd(
-                STACKABLE_LISTENER_BROKER_DIR,
-                kafka_security.client_port_name(),
-            ),
-        });
-        listener_security_protocol_map
-            .insert(KafkaListenerName::ClientAuth, KafkaListenerProtocol::Ssl);
-    } else if kafka_security.has_kerberos_enabled() {
-        // 2) Kerberos and TLS authentication classes are mutually exclusive
-        listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: LISTENER_LOCAL_ADDRESS.to_string(),
-            port: KafkaTlsSecurity::SECURE_CLIENT_PORT.to_string(),
-        });
-        advertised_listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
-            port: node_port_cmd(
-                STACKABLE_LISTENER_BROKER_DIR,
-                kafka_security.client_port_name(),
-            ),
-        });
-        listener_security_protocol_map
-            .insert(KafkaListenerName::Client, KafkaListenerProtocol::SaslSsl);
-    } else if kafka_security.tls_server_secret_class().is_some() {
-        // 3) If no client authentication but tls is required we expose CLIENT with SSL
-        listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: LISTENER_LOCAL_ADDRESS.to_string(),
-            port: kafka_security.client_port().to_string(),
-        });
-        advertised_listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
-            port: node_port_cmd(
-                STACKABLE_LISTENER_BROKER_DIR,
-                kafka_security.client_port_name(),
-            ),
-        });
-        listener_security_protocol_map
-            .insert(KafkaListenerName::Client, KafkaListenerProtocol::Ssl);
-    } else {
-        // 4) If no client auth or tls is required we expose CLIENT with PLAINTEXT
-        listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: LISTENER_LOCAL_ADDRESS.to_string(),
-            port: KafkaTlsSecurity::CLIENT_PORT.to_string(),
-        });
-        advertised_listeners.push(KafkaListener {
-            name: KafkaListenerName::Client,
-            host: node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
-            port: node_port_cmd(
-                STACKABLE_LISTENER_BROKER_DIR,
-                kafka_security.client_port_name(),
-            ),
-        });
-        listener_security_protocol_map
-            .insert(KafkaListenerName::Client, KafkaListenerProtocol::Plaintext);
+    // CLIENT: the default client listener and the dedicated listeners of additional
+    // AuthenticationClasses and the user-defined listeners
+    for client_listener in kafka_security.client_listeners() {
+        if client_listener.loopback {
+            listeners.push(KafkaListener {
+                name: client_listener.name.clone(),
+                host: LISTENER_LOOPBACK_ADDRESS.to_string(),
+                port: client_listener.port.to_string(),
+            });
+            advertised_listeners.push(KafkaListener {
+                name: client_listener.name.clone(),
+                host: LISTENER_LOOPBACK_HOST.to_string(),
+                port: client_listener.port.to_string(),
+            });
+        } else {
+            let broker_listener_dir = client_listener.broker_listener_dir();
+            listeners.push(KafkaListener {
+                name: client_listener.name.clone(),
+                host: LISTENER_LOCAL_ADDRESS.to_string(),
+                port: client_listener.port.to_string(),
+            });
+            advertised_listeners.push(KafkaListener {
+                name: client_listener.name.clone(),
+                host: node_address_cmd(&broker_listener_dir),
+                port: node_port_cmd(&broker_listener_dir, &client_listener.port_name),
+            });
+        }
+        listener_security_protocol_map.insert(client_listener.name, client_listener.protocol);
     }
 
     // INTERNAL
@@ -191,8 +380,16 @@ pub fn get_kafka_listener_config(
             host: pod_fqdn.to_string(),
             port: KafkaTlsSecurity::SECURE_INTERNAL_PORT.to_string(),
         });
-        listener_security_protocol_map
-            .insert(KafkaListenerName::Internal, KafkaListenerProtocol::Ssl);
+        listener_security_protocol_map.insert(
+            KafkaListenerName::Internal,
+            if kafka_security.has_kerberos_inter_broker_enabled()
+                || kafka_security.has_internal_credentials_enabled()
+            {
+                KafkaListenerProtocol::SaslSsl
+            } else {
+                KafkaListenerProtocol::Ssl
+            },
+        );
     } else {
         // 7) If no internal tls is required we expose INTERNAL as PLAINTEXT
         listeners.push(KafkaListener {
@@ -212,22 +409,37 @@ pub fn get_kafka_listener_config(
     }
 
     // BOOTSTRAP
-    if kafka_security.has_kerberos_enabled() {
+    if let Some(kerberos_listener) = kafka_security.kerberos_client_listener() {
+        let broker_listener_dir = kerberos_listener.broker_listener_dir();
         listeners.push(KafkaListener {
             name: KafkaListenerName::Bootstrap,
             host: LISTENER_LOCAL_ADDRESS.to_string(),
-            port: kafka_security.bootstrap_port().to_string(),
+            port: kerberos_listener.bootstrap_port().to_string(),
         });
         advertised_listeners.push(KafkaListener {
             name: KafkaListenerName::Bootstrap,
-            host: node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
-            port: node_port_cmd(
-                STACKABLE_LISTENER_BROKER_DIR,
-                kafka_security.client_port_name(),
-            ),
+            host: node_address_cmd(&broker_listener_dir),
+            port: node_port_cmd(&broker_listener_dir, &kerberos_listener.port_name),
         });
         listener_security_protocol_map
-            .insert(KafkaListenerName::Bootstrap, KafkaListenerProtocol::SaslSsl);
+            .insert(KafkaListenerName::Bootstrap, kerberos_listener.protocol);
+    }
+
+    // CONTROLLER
+    if combined_controller {
+        // Like on dedicated controllers, the controller listener is not advertised
+        listeners.push(KafkaListener {
+            name: KafkaListenerName::Controller,
+            host: LISTENER_LOCAL_ADDRESS.to_string(),
+            port: kafka_security.controller_port().to_string(),
+        });
+    }
+    if kafka.metadata_mode(&KafkaRole::Broker).uses_controllers() {
+        // Brokers need to know how to connect to the controllers
+        listener_security_protocol_map.insert(
+            KafkaListenerName::Controller,
+            controller_listener_protocol(kafka_security),
+        );
     }
 
     Ok(KafkaListenerConfig {
@@ -237,6 +449,49 @@ pub fn get_kafka_listener_config(
     })
 }
 
+/// Returns the listener config of the KRaft controllers, which only expose the CONTROLLER listener.
+/// Controllers must not advertise any listeners.
+pub fn get_kafka_controller_listener_config(
+    kafka: &KafkaCluster,
+    kafka_security: &KafkaTlsSecurity,
+) -> KafkaListenerConfig {
+    let mut listener_security_protocol_map = BTreeMap::from([(
+        KafkaListenerName::Controller,
+        controller_listener_protocol(kafka_security),
+    )]);
+
+    // During a migration from ZooKeeper the controllers connect to the INTERNAL listener of the brokers.
+    // It uses the same protocol, as both are secured with the internal TLS settings.
+    if kafka.metadata_mode(&KafkaRole::Controller).is_migrating() {
+        listener_security_protocol_map.insert(
+            KafkaListenerName::Internal,
+            controller_listener_protocol(kafka_security),
+        );
+    }
+
+    KafkaListenerConfig {
+        listeners: vec![KafkaListener {
+            name: KafkaListenerName::Controller,
+            host: LISTENER_LOCAL_ADDRESS.to_string(),
+            port: kafka_security.controller_port().to_string(),
+        }],
+        advertised_listeners: vec![],
+        listener_security_protocol_map,
+    }
+}
+
+/// The controller quorum is secured with the internal TLS settings and, with an authorizer, the
+/// credentials of the internal super user.
+fn controller_listener_protocol(kafka_security: &KafkaTlsSecurity) -> KafkaListenerProtocol {
+    if kafka_security.has_internal_credentials_enabled() {
+        KafkaListenerProtocol::SaslSsl
+    } else if kafka_security.tls_internal_secret_class().is_some() {
+        KafkaListenerProtocol::Ssl
+    } else {
+        KafkaListenerProtocol::Plaintext
+    }
+}
+
 pub fn node_address_cmd(directory: &str) -> String {
     format!("$(cat {directory}/default-address/address)")
 }
@@ -261,13 +516,13 @@ pub fn pod_fqdn(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::authentication::ResolvedAuthenticationClasses;
+    use crate::authentication::{KafkaKerberosOptions, ResolvedAuthenticationClasses};
 
     use stackable_operator::{
         builder::meta::ObjectMetaBuilder,
         commons::{
             authentication::{
-                kerberos,
+                kerberos, static_,
                 tls::{self},
                 AuthenticationClass, AuthenticationClassProvider, AuthenticationClassSpec,
             },
@@ -318,7 +573,8 @@ mod tests {
         );
 
         let config =
-            get_kafka_listener_config(&kafka, &kafka_security, object_name, &cluster_info).unwrap();
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
 
         assert_eq!(
             config.listeners(),
@@ -366,7 +622,8 @@ mod tests {
             Some("tls".to_string()),
         );
         let config =
-            get_kafka_listener_config(&kafka, &kafka_security, object_name, &cluster_info).unwrap();
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
 
         assert_eq!(
             config.listeners(),
@@ -415,7 +672,8 @@ mod tests {
         );
 
         let config =
-            get_kafka_listener_config(&kafka, &kafka_security, object_name, &cluster_info).unwrap();
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
 
         assert_eq!(
             config.listeners(),
@@ -458,6 +716,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_kafka_kraft_listeners_config() {
+        let object_name = "simple-kafka-broker-default";
+        let cluster_info = default_cluster_info();
+
+        let kafka_cluster = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            tls:
+              internalSecretClass: internalTls
+              serverSecretClass: tls
+          brokers:
+            roleGroups:
+              default:
+                replicas: 3
+          controllers:
+            roleGroups:
+              default:
+                replicas: 3
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(kafka_cluster).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "internalTls".to_string(),
+            Some("tls".to_string()),
+        );
+
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol},{controller_name}:{controller_protocol}",
+                name = KafkaListenerName::Client,
+                protocol = KafkaListenerProtocol::Ssl,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::Ssl,
+                controller_name = KafkaListenerName::Controller,
+                controller_protocol = KafkaListenerProtocol::Ssl,
+            )
+        );
+
+        // combined mode
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, true, &cluster_info)
+                .unwrap();
+
+        assert!(config.listeners().ends_with(&format!(
+            ",{name}://{host}:{port}",
+            name = KafkaListenerName::Controller,
+            host = LISTENER_LOCAL_ADDRESS,
+            port = kafka_security.controller_port(),
+        )));
+        assert!(!config
+            .advertised_listeners()
+            .contains(&KafkaListenerName::Controller.to_string()));
+
+        let config = get_kafka_controller_listener_config(&kafka, &kafka_security);
+
+        assert_eq!(
+            config.listeners(),
+            format!(
+                "{name}://{host}:{port}",
+                name = KafkaListenerName::Controller,
+                host = LISTENER_LOCAL_ADDRESS,
+                port = kafka_security.controller_port(),
+            )
+        );
+        assert_eq!(config.advertised_listeners(), "");
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol}",
+                name = KafkaListenerName::Controller,
+                protocol = KafkaListenerProtocol::Ssl,
+            )
+        );
+
+        // With an authorizer, the brokers and controllers log in as the internal super user
+        let kafka_security =
+            kafka_security.with_internal_credentials("simple-kafka-internal-credentials");
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol},{controller_name}:{controller_protocol}",
+                name = KafkaListenerName::Client,
+                protocol = KafkaListenerProtocol::Ssl,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::SaslSsl,
+                controller_name = KafkaListenerName::Controller,
+                controller_protocol = KafkaListenerProtocol::SaslSsl,
+            )
+        );
+    }
+
     #[test]
     fn test_get_kafka_kerberos_listeners_config() {
         let object_name = "simple-kafka-broker-default";
@@ -496,7 +862,8 @@ mod tests {
         );
 
         let config =
-            get_kafka_listener_config(&kafka, &kafka_security, object_name, &cluster_info).unwrap();
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
 
         assert_eq!(
             config.listeners(),
@@ -550,4 +917,369 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_get_kafka_kerberos_sasl_plaintext_listeners_config() {
+        let object_name = "simple-kafka-broker-default";
+        let cluster_info = default_cluster_info();
+
+        let kafka_cluster = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authentication:
+              - authenticationClass: kafka-kerberos
+                kerberos:
+                  saslPlaintext: true
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(kafka_cluster).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![AuthenticationClass {
+                metadata: ObjectMetaBuilder::new().name("auth-class").build(),
+                spec: AuthenticationClassSpec {
+                    provider: AuthenticationClassProvider::Kerberos(
+                        kerberos::AuthenticationProvider {
+                            kerberos_secret_class: "kerberos-secret-class".to_string(),
+                        },
+                    ),
+                },
+            }])
+            .with_kerberos_options(KafkaKerberosOptions {
+                sasl_plaintext: true,
+            }),
+            "tls".to_string(),
+            None,
+        );
+        kafka_security.validate_authentication_methods().unwrap();
+
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        assert_eq!(
+            config.listeners(),
+            format!(
+                "{name}://{host}:{port},{internal_name}://{host}:{internal_port},{bootstrap_name}://{host}:{bootstrap_port}",
+                name = KafkaListenerName::Client,
+                host = LISTENER_LOCAL_ADDRESS,
+                port = KafkaTlsSecurity::CLIENT_PORT,
+                internal_name = KafkaListenerName::Internal,
+                internal_port = KafkaTlsSecurity::SECURE_INTERNAL_PORT,
+                bootstrap_name = KafkaListenerName::Bootstrap,
+                bootstrap_port = KafkaTlsSecurity::BOOTSTRAP_PORT,
+            )
+        );
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol},{bootstrap_name}:{bootstrap_protocol}",
+                name = KafkaListenerName::Client,
+                protocol = KafkaListenerProtocol::SaslPlaintext,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::Ssl,
+                bootstrap_name = KafkaListenerName::Bootstrap,
+                bootstrap_protocol = KafkaListenerProtocol::SaslPlaintext,
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_kafka_scram_listeners_config() {
+        let object_name = "simple-kafka-broker-default";
+        let cluster_info = default_cluster_info();
+
+        let kafka_cluster = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authentication:
+              - authenticationClass: kafka-scram
+            tls:
+              serverSecretClass: tls
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(kafka_cluster).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![AuthenticationClass {
+                metadata: ObjectMetaBuilder::new().name("auth-class").build(),
+                spec: AuthenticationClassSpec {
+                    provider: AuthenticationClassProvider::Static(
+                        static_::AuthenticationProvider {
+                            user_credentials_secret: static_::UserCredentialsSecretRef {
+                                name: "kafka-users".to_string(),
+                            },
+                        },
+                    ),
+                },
+            }]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        assert_eq!(
+            config.listeners(),
+            format!(
+                "{name}://{host}:{port},{internal_name}://{internal_host}:{internal_port}",
+                name = KafkaListenerName::Client,
+                host = LISTENER_LOCAL_ADDRESS,
+                port = KafkaTlsSecurity::SECURE_CLIENT_PORT,
+                internal_name = KafkaListenerName::Internal,
+                internal_host = LISTENER_LOCAL_ADDRESS,
+                internal_port = kafka_security.internal_port(),
+            )
+        );
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol}",
+                name = KafkaListenerName::Client,
+                protocol = KafkaListenerProtocol::SaslSsl,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::Ssl,
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_kafka_dedicated_listeners_config() {
+        let object_name = "simple-kafka-broker-default";
+        let cluster_info = default_cluster_info();
+
+        let kafka_cluster = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authentication:
+              - authenticationClass: kafka-client-tls
+              - authenticationClass: kafka-scram
+                listener:
+                  name: scram
+            tls:
+              serverSecretClass: tls
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(kafka_cluster).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![
+                AuthenticationClass {
+                    metadata: ObjectMetaBuilder::new().name("kafka-client-tls").build(),
+                    spec: AuthenticationClassSpec {
+                        provider: AuthenticationClassProvider::Tls(tls::AuthenticationProvider {
+                            client_cert_secret_class: Some("client-auth-secret-class".to_string()),
+                        }),
+                    },
+                },
+                AuthenticationClass {
+                    metadata: ObjectMetaBuilder::new().name("kafka-scram").build(),
+                    spec: AuthenticationClassSpec {
+                        provider: AuthenticationClassProvider::Static(
+                            static_::AuthenticationProvider {
+                                user_credentials_secret: static_::UserCredentialsSecretRef {
+                                    name: "kafka-users".to_string(),
+                                },
+                            },
+                        ),
+                    },
+                },
+            ])
+            .with_listeners(vec![
+                None,
+                Some(KafkaAuthenticationListener {
+                    name: "scram".to_string(),
+                    ..KafkaAuthenticationListener::default()
+                }),
+            ]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        let scram_listener = KafkaListenerName::Custom("CLIENT_SCRAM".to_string());
+        assert_eq!(
+            config.listeners(),
+            format!(
+                "{name}://{host}:{port},{scram_name}://{host}:{scram_port},{internal_name}://{host}:{internal_port}",
+                name = KafkaListenerName::ClientAuth,
+                host = LISTENER_LOCAL_ADDRESS,
+                port = KafkaTlsSecurity::SECURE_CLIENT_PORT,
+                scram_name = scram_listener,
+                scram_port = KafkaTlsSecurity::ADDITIONAL_CLIENT_PORT + 1,
+                internal_name = KafkaListenerName::Internal,
+                internal_port = kafka_security.internal_port(),
+            )
+        );
+
+        assert_eq!(
+            config.advertised_listeners(),
+            format!(
+                "{name}://{host}:{port},{scram_name}://{scram_host}:{scram_port},{internal_name}://{internal_host}:{internal_port}",
+                name = KafkaListenerName::ClientAuth,
+                host = node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
+                port = node_port_cmd(
+                    STACKABLE_LISTENER_BROKER_DIR,
+                    kafka_security.client_port_name()
+                ),
+                scram_name = scram_listener,
+                scram_host = node_address_cmd(&format!("{STACKABLE_LISTENER_BROKER_DIR}-scram")),
+                scram_port = node_port_cmd(
+                    &format!("{STACKABLE_LISTENER_BROKER_DIR}-scram"),
+                    "kafka-scram"
+                ),
+                internal_name = KafkaListenerName::Internal,
+                internal_host = pod_fqdn(&kafka, object_name, &cluster_info).unwrap(),
+                internal_port = kafka_security.internal_port(),
+            )
+        );
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol},{scram_name}:{scram_protocol}",
+                name = KafkaListenerName::ClientAuth,
+                protocol = KafkaListenerProtocol::Ssl,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::Ssl,
+                scram_name = scram_listener,
+                scram_protocol = KafkaListenerProtocol::SaslSsl,
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_kafka_additional_listeners_config() {
+        let object_name = "simple-kafka-broker-default";
+        let cluster_info = default_cluster_info();
+
+        let kafka_cluster = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            listeners:
+              - name: repl
+                port: 9200
+                protocol: SSL
+                brokerListenerClass: external-stable
+              - name: sidecar
+                port: 9300
+                protocol: PLAINTEXT
+                loopback: true
+            tls:
+              internalSecretClass: internalTls
+              serverSecretClass: tls
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(kafka_cluster).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "internalTls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_additional_listeners(kafka.spec.cluster_config.listeners.clone());
+        kafka_security.validate_listeners().unwrap();
+
+        let config =
+            get_kafka_listener_config(&kafka, &kafka_security, object_name, false, &cluster_info)
+                .unwrap();
+
+        let repl_listener = KafkaListenerName::Custom("CLIENT_REPL".to_string());
+        let sidecar_listener = KafkaListenerName::Custom("CLIENT_SIDECAR".to_string());
+        assert_eq!(
+            config.listeners(),
+            format!(
+                "{name}://{host}:{port},{repl_name}://{host}:9200,{sidecar_name}://{loopback_address}:9300,{internal_name}://{host}:{internal_port}",
+                name = KafkaListenerName::Client,
+                host = LISTENER_LOCAL_ADDRESS,
+                port = KafkaTlsSecurity::SECURE_CLIENT_PORT,
+                repl_name = repl_listener,
+                sidecar_name = sidecar_listener,
+                loopback_address = LISTENER_LOOPBACK_ADDRESS,
+                internal_name = KafkaListenerName::Internal,
+                internal_port = kafka_security.internal_port(),
+            )
+        );
+
+        assert_eq!(
+            config.advertised_listeners(),
+            format!(
+                "{name}://{host}:{port},{repl_name}://{repl_host}:{repl_port},{sidecar_name}://{loopback_host}:9300,{internal_name}://{internal_host}:{internal_port}",
+                name = KafkaListenerName::Client,
+                host = node_address_cmd(STACKABLE_LISTENER_BROKER_DIR),
+                port = node_port_cmd(
+                    STACKABLE_LISTENER_BROKER_DIR,
+                    kafka_security.client_port_name()
+                ),
+                repl_name = repl_listener,
+                repl_host = node_address_cmd(&format!("{STACKABLE_LISTENER_BROKER_DIR}-repl")),
+                repl_port = node_port_cmd(
+                    &format!("{STACKABLE_LISTENER_BROKER_DIR}-repl"),
+                    "kafka-repl"
+                ),
+                sidecar_name = sidecar_listener,
+                loopback_host = LISTENER_LOOPBACK_HOST,
+                internal_name = KafkaListenerName::Internal,
+                internal_host = pod_fqdn(&kafka, object_name, &cluster_info).unwrap(),
+                internal_port = kafka_security.internal_port(),
+            )
+        );
+
+        assert_eq!(
+            config.listener_security_protocol_map(),
+            format!(
+                "{name}:{protocol},{internal_name}:{internal_protocol},{repl_name}:{repl_protocol},{sidecar_name}:{sidecar_protocol}",
+                name = KafkaListenerName::Client,
+                protocol = KafkaListenerProtocol::Ssl,
+                internal_name = KafkaListenerName::Internal,
+                internal_protocol = KafkaListenerProtocol::Ssl,
+                repl_name = repl_listener,
+                repl_protocol = KafkaListenerProtocol::Ssl,
+                sidecar_name = sidecar_listener,
+                sidecar_protocol = KafkaListenerProtocol::Plaintext,
+            )
+        );
+
+        // The loopback listener is not exposed
+        assert_eq!(
+            kafka_security.client_listener_volume_names(),
+            vec![
+                "listener-broker",
+                "listener-bootstrap",
+                "listener-broker-repl",
+                "listener-bootstrap-repl",
+            ]
+        );
+    }
 }
@@ -0,0 +1,153 @@
+//! Rack awareness of the brokers. Node labels are not available with the downward API, so the
+//! operator annotates every scheduled broker Pod with the value of the configured label of its
+//! node. The annotation is mounted with the downward API and read as `broker.rack`.
+use std::collections::BTreeMap;
+
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use stackable_operator::{
+    k8s_openapi::api::core::v1::{
+        DownwardAPIVolumeFile, DownwardAPIVolumeSource, ObjectFieldSelector, Volume,
+    },
+    schemars::{self, JsonSchema},
+};
+
+use crate::security::KafkaTlsSecurity;
+
+/// The annotation of the broker Pods with the rack of their node.
+pub const RACK_ANNOTATION: &str = "kafka.stackable.tech/rack";
+pub const RACK_VOLUME_NAME: &str = "rack";
+pub const STACKABLE_RACK_DIR: &str = "/stackable/rack";
+const RACK_FILE: &str = "rack";
+const RACK_NODE_LABEL_DEFAULT: &str = "topology.kubernetes.io/zone";
+/// The kubelet updates the downward API volume within its sync period (one minute by default),
+/// so the annotation is missing if the operator could not determine the rack.
+const RACK_WAIT_TIMEOUT_SECONDS: u32 = 300;
+const RACK_WAIT_INTERVAL_SECONDS: u32 = 5;
+// server.properties
+const BROKER_RACK: &str = "broker.rack";
+const REPLICA_SELECTOR_CLASS: &str = "replica.selector.class";
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaRackAwareness {
+    /// The label of the Kubernetes nodes, whose value is used as the rack of the brokers running
+    /// on them. All nodes the brokers are scheduled on must have this label.
+    ///
+    /// Defaults to `topology.kubernetes.io/zone`.
+    #[serde(default = "rack_node_label_default")]
+    pub node_label: String,
+}
+
+impl Default for KafkaRackAwareness {
+    fn default() -> Self {
+        Self {
+            node_label: rack_node_label_default(),
+        }
+    }
+}
+
+pub fn rack_node_label_default() -> String {
+    RACK_NODE_LABEL_DEFAULT.to_string()
+}
+
+/// Returns the settings for the `server.properties` file of the brokers. The rack is read from
+/// the downward API volume when the configuration is loaded. Consumers which set `client.rack`
+/// fetch from a replica in their rack.
+pub fn config_settings() -> BTreeMap<String, String> {
+    let mut config = KafkaTlsSecurity::dir_config_provider_settings();
+    config.insert(
+        BROKER_RACK.to_string(),
+        format!("${{dir:{STACKABLE_RACK_DIR}:{RACK_FILE}}}"),
+    );
+    config.insert(
+        REPLICA_SELECTOR_CLASS.to_string(),
+        "org.apache.kafka.common.replica.RackAwareReplicaSelector".to_string(),
+    );
+    config
+}
+
+/// The downward API volume with the rack annotation of the Pod. The file is empty until the
+/// operator has annotated the Pod.
+pub fn rack_volume() -> Volume {
+    Volume {
+        name: RACK_VOLUME_NAME.to_string(),
+        downward_api: Some(DownwardAPIVolumeSource {
+            items: Some(vec![DownwardAPIVolumeFile {
+                path: RACK_FILE.to_string(),
+                field_ref: Some(ObjectFieldSelector {
+                    field_path: format!("metadata.annotations['{RACK_ANNOTATION}']"),
+                    ..ObjectFieldSelector::default()
+                }),
+                ..DownwardAPIVolumeFile::default()
+            }]),
+            ..DownwardAPIVolumeSource::default()
+        }),
+        ..Volume::default()
+    }
+}
+
+/// Waits until the kubelet has updated the downward API volume with the rack annotation, which
+/// may take up to the sync period of the kubelet. The container fails if the annotation does not
+/// show up in time, so that the Pod is reported as crash looping instead of hanging silently.
+pub fn wait_for_rack_command() -> String {
+    formatdoc! {"
+        SECONDS_WAITED=0
+        until [ -s {STACKABLE_RACK_DIR}/{RACK_FILE} ]; do
+          if [ \"$SECONDS_WAITED\" -ge {RACK_WAIT_TIMEOUT_SECONDS} ]; then
+            echo \"The Pod was not annotated with its rack {RACK_ANNOTATION} within {RACK_WAIT_TIMEOUT_SECONDS}s, check the operator log\"
+            exit 1
+          fi
+          echo \"Waiting for the rack annotation {RACK_ANNOTATION} of the Pod\"
+          sleep {RACK_WAIT_INTERVAL_SECONDS}
+          SECONDS_WAITED=$((SECONDS_WAITED + {RACK_WAIT_INTERVAL_SECONDS}))
+        done
+        echo \"The broker is running in rack $(cat {STACKABLE_RACK_DIR}/{RACK_FILE})\""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::KafkaCluster;
+
+    #[test]
+    fn test_rack_awareness() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            rackAwareness: {}
+            zookeeperConfigMapName: xyz
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert_eq!(
+            kafka.spec.cluster_config.rack_awareness,
+            Some(KafkaRackAwareness {
+                node_label: "topology.kubernetes.io/zone".to_string()
+            })
+        );
+
+        let config = config_settings();
+        assert_eq!(
+            config.get("broker.rack").map(String::as_str),
+            Some("${dir:/stackable/rack:rack}")
+        );
+        assert_eq!(
+            config.get("config.providers").map(String::as_str),
+            Some("dir")
+        );
+
+        let command = wait_for_rack_command();
+        assert!(command.contains("until [ -s /stackable/rack/rack ]; do"));
+        assert!(command.contains("if [ \"$SECONDS_WAITED\" -ge 300 ]; then"));
+        assert!(command.contains("exit 1"));
+    }
+}
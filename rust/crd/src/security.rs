@@ -4,21 +4,35 @@
 //! and helper functions
 //!
 //! This is required due to overlaps between TLS encryption and e.g. mTLS authentication or Kerberos
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
-    authentication::{self, ResolvedAuthenticationClasses},
-    listener::{self, KafkaListenerConfig},
-    tls, KafkaCluster, LISTENER_BOOTSTRAP_VOLUME_NAME, SERVER_PROPERTIES_FILE,
-    STACKABLE_CONFIG_DIR,
-};
-use crate::{
-    listener::node_address_cmd, STACKABLE_KERBEROS_KRB5_PATH, STACKABLE_LISTENER_BOOTSTRAP_DIR,
-    STACKABLE_LISTENER_BROKER_DIR,
+    authentication::{
+        self, KafkaTlsClientAuth, ResolvedAuthenticationClasses, DEFAULT_JWKS_ENDPOINT_PATH,
+        DEFAULT_TOKEN_ENDPOINT_PATH,
+    },
+    authorization::INTERNAL_USER_NAME,
+    delegation_token,
+    listener::{
+        KafkaAdditionalListener, KafkaClientAuthentication, KafkaClientListener,
+        KafkaListenerConfig, KafkaListenerName, KafkaListenerProtocol,
+    },
+    tls::{
+        self, KafkaTlsPolicies, KafkaTlsPolicy, KafkaTlsProtocol, KafkaTlsStoreFormat,
+        TLS_STORE_PASSWORD_SECRET_KEY,
+    },
+    user,
+    zookeeper::zookeeper_kerberos_service,
+    KafkaCluster, KRB5_TRUSTED_REALMS_FILE, METRICS_PORT, SERVER_PROPERTIES_FILE,
+    STACKABLE_CONFIG_DIR, STACKABLE_DELEGATION_TOKEN_DIR, STACKABLE_LDAP_BIND_CREDENTIALS_DIR,
+    STACKABLE_TLS_STORE_PASSWORD_DIR, STACKABLE_TRUSTED_REALMS_KRB5_PATH,
+    STACKABLE_ZOOKEEPER_KERBEROS_DIR, STACKABLE_ZOOKEEPER_KERBEROS_KRB5_PATH,
+    ZOOKEEPER_JAAS_CONFIG_FILE,
 };
-use crate::{KafkaRole, LISTENER_BROKER_VOLUME_NAME, STACKABLE_LOG_DIR};
+use crate::{listener::node_address_cmd, STACKABLE_KERBEROS_KRB5_PATH};
+use crate::{KafkaRole, STACKABLE_LOG_DIR};
 use indoc::formatdoc;
-use snafu::{ensure, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use stackable_operator::time::Duration;
 use stackable_operator::{
     builder::{
@@ -30,8 +44,9 @@ use stackable_operator::{
         },
     },
     client::Client,
-    commons::authentication::{AuthenticationClass, AuthenticationClassProvider},
-    k8s_openapi::api::core::v1::Volume,
+    commons::authentication::{ldap, oidc, AuthenticationClass, AuthenticationClassProvider},
+    k8s_openapi::api::core::v1::{Secret, Volume},
+    kube::{runtime::reflector::ObjectRef, ResourceExt},
     product_logging::framework::{
         create_vector_shutdown_file_command, remove_vector_shutdown_file_command,
     },
@@ -56,8 +71,99 @@ pub enum Error {
         source: builder::pod::container::Error,
     },
 
-    #[snafu(display("kerberos enablement requires TLS activation"))]
+    #[snafu(display("kerberos enablement requires TLS activation, unless SASL_PLAINTEXT is explicitly enabled with kerberos.saslPlaintext"))]
     KerberosRequiresTls,
+
+    #[snafu(display(
+        "kerberos authentication between brokers requires the internal TLS SecretClass"
+    ))]
+    KerberosInterBrokerRequiresInternalTls,
+
+    #[snafu(display(
+        "SCRAM authentication with a Static AuthenticationClass requires TLS activation"
+    ))]
+    ScramRequiresTls,
+
+    #[snafu(display(
+        "OAUTHBEARER authentication with an OIDC AuthenticationClass requires TLS activation"
+    ))]
+    OidcRequiresTls,
+
+    #[snafu(display("failed to build the endpoint URL of the OIDC provider"))]
+    InvalidOidcEndpoint { source: oidc::Error },
+
+    #[snafu(display(
+        "PLAIN authentication with an LDAP AuthenticationClass requires TLS activation"
+    ))]
+    LdapRequiresTls,
+
+    #[snafu(display("failed to build the endpoint URL of the LDAP server"))]
+    InvalidLdapEndpoint { source: ldap::Error },
+
+    #[snafu(display("invalid TLS policy"))]
+    InvalidTlsPolicy { source: tls::Error },
+
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("failed to retrieve the TLS store password from {secret}"))]
+    RetrieveTlsStorePassword {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("the TLS store password in {secret} is missing"))]
+    MissingTlsStorePassword { secret: ObjectRef<Secret> },
+
+    #[snafu(display("invalid name of the listener [{listener}]"))]
+    InvalidListenerName {
+        source: authentication::Error,
+        listener: String,
+    },
+
+    #[snafu(display("the Kafka listener name [{name}] is used by multiple listeners"))]
+    DuplicateListenerName { name: String },
+
+    #[snafu(display("the port {port} of the listener [{listener}] is already in use"))]
+    DuplicateListenerPort { listener: String, port: u16 },
+
+    #[snafu(display("the listener [{listener}] references the authentication class [{authentication_class}], which is not listed in clusterConfig.authentication"))]
+    UnknownListenerAuthenticationClass {
+        listener: String,
+        authentication_class: String,
+    },
+
+    #[snafu(display("the listener [{listener}] cannot serve the Kerberos authentication class [{authentication_class}]"))]
+    ListenerKerberosNotSupported {
+        listener: String,
+        authentication_class: String,
+    },
+
+    #[snafu(display(
+        "the listener [{listener}] must use the protocol {expected}, but uses {protocol}"
+    ))]
+    ListenerProtocolMismatch {
+        listener: String,
+        protocol: KafkaListenerProtocol,
+        expected: KafkaListenerProtocol,
+    },
+
+    #[snafu(display("the listener [{listener}] uses TLS, which requires a server SecretClass or a TLS authentication class"))]
+    ListenerRequiresTls { listener: String },
+
+    #[snafu(display(
+        "the loopback listener [{listener}] must use PLAINTEXT without an authentication class"
+    ))]
+    LoopbackListenerRequiresPlaintext { listener: String },
+
+    #[snafu(display("the bootstrap SecretClass {secret_class:?} is only used by the bootstrap listener of a Kerberos AuthenticationClass with TLS, but there is no such listener"))]
+    BootstrapSecretClassRequiresKerberosTls { secret_class: String },
+
+    #[snafu(display("the ZooKeeper Kerberos SecretClass {zookeeper_secret_class:?} must be the Kerberos SecretClass {kerberos_secret_class:?} of the clients, as the brokers use a single krb5.conf"))]
+    ZookeeperKerberosSecretClassMismatch {
+        zookeeper_secret_class: String,
+        kerberos_secret_class: String,
+    },
 }
 
 /// Helper struct combining TLS settings for server and internal with the resolved AuthenticationClasses
@@ -65,6 +171,22 @@ pub struct KafkaTlsSecurity {
     resolved_authentication_classes: ResolvedAuthenticationClasses,
     internal_secret_class: String,
     server_secret_class: Option<String>,
+    bootstrap_secret_class: Option<String>,
+    tls_policies: KafkaTlsPolicies,
+    store_format: KafkaTlsStoreFormat,
+    store_password: Option<KafkaTlsStorePassword>,
+    zookeeper_tls_secret_class: Option<String>,
+    zookeeper_kerberos_secret_class: Option<String>,
+    opa_ca_cert_secret_class: Option<String>,
+    additional_listeners: Vec<KafkaAdditionalListener>,
+    scram_prober_secret_name: Option<String>,
+    internal_credentials_secret_name: Option<String>,
+}
+
+/// The generated password of the PKCS12 stores and the Secret it is mounted from.
+struct KafkaTlsStorePassword {
+    secret_name: String,
+    password: String,
 }
 
 impl KafkaTlsSecurity {
@@ -83,63 +205,116 @@ impl KafkaTlsSecurity {
     // internal
     pub const INTERNAL_PORT: u16 = 19092;
     pub const SECURE_INTERNAL_PORT: u16 = 19093;
-    // - TLS global
-    const SSL_STORE_PASSWORD: &'static str = "";
-    // - TLS client
-    const CLIENT_SSL_KEYSTORE_LOCATION: &'static str = "listener.name.client.ssl.keystore.location";
-    const CLIENT_SSL_KEYSTORE_PASSWORD: &'static str = "listener.name.client.ssl.keystore.password";
-    const CLIENT_SSL_KEYSTORE_TYPE: &'static str = "listener.name.client.ssl.keystore.type";
-    const CLIENT_SSL_TRUSTSTORE_LOCATION: &'static str =
-        "listener.name.client.ssl.truststore.location";
-    const CLIENT_SSL_TRUSTSTORE_PASSWORD: &'static str =
-        "listener.name.client.ssl.truststore.password";
-    const CLIENT_SSL_TRUSTSTORE_TYPE: &'static str = "listener.name.client.ssl.truststore.type";
-    // - Bootstrapper
-    const BOOTSTRAP_SSL_KEYSTORE_LOCATION: &'static str =
-        "listener.name.bootstrap.ssl.keystore.location";
-    const BOOTSTRAP_SSL_KEYSTORE_PASSWORD: &'static str =
-        "listener.name.bootstrap.ssl.keystore.password";
-    const BOOTSTRAP_SSL_KEYSTORE_TYPE: &'static str = "listener.name.bootstrap.ssl.keystore.type";
-    const BOOTSTRAP_SSL_TRUSTSTORE_LOCATION: &'static str =
-        "listener.name.bootstrap.ssl.truststore.location";
-    const BOOTSTRAP_SSL_TRUSTSTORE_PASSWORD: &'static str =
-        "listener.name.bootstrap.ssl.truststore.password";
-    const BOOTSTRAP_SSL_TRUSTSTORE_TYPE: &'static str =
-        "listener.name.bootstrap.ssl.truststore.type";
-    // - TLS client authentication
-    const CLIENT_AUTH_SSL_KEYSTORE_LOCATION: &'static str =
-        "listener.name.client_auth.ssl.keystore.location";
-    const CLIENT_AUTH_SSL_KEYSTORE_PASSWORD: &'static str =
-        "listener.name.client_auth.ssl.keystore.password";
-    const CLIENT_AUTH_SSL_KEYSTORE_TYPE: &'static str =
-        "listener.name.client_auth.ssl.keystore.type";
-    const CLIENT_AUTH_SSL_TRUSTSTORE_LOCATION: &'static str =
-        "listener.name.client_auth.ssl.truststore.location";
-    const CLIENT_AUTH_SSL_TRUSTSTORE_PASSWORD: &'static str =
-        "listener.name.client_auth.ssl.truststore.password";
-    const CLIENT_AUTH_SSL_TRUSTSTORE_TYPE: &'static str =
-        "listener.name.client_auth.ssl.truststore.type";
-    const CLIENT_AUTH_SSL_CLIENT_AUTH: &'static str = "listener.name.client_auth.ssl.client.auth";
+    // controller (KRaft)
+    pub const CONTROLLER_PORT_NAME: &'static str = "controller";
+    pub const CONTROLLER_PORT: u16 = 19094;
+    pub const SECURE_CONTROLLER_PORT: u16 = 19095;
+    // additional client listeners: the listener of the n-th additional AuthenticationClass
+    // listens on this port + n
+    pub const ADDITIONAL_CLIENT_PORT: u16 = 9100;
+    // ports which user-defined listeners must not use, independently of the settings
+    const RESERVED_PORTS: [u16; 9] = [
+        Self::CLIENT_PORT,
+        Self::SECURE_CLIENT_PORT,
+        Self::BOOTSTRAP_PORT,
+        Self::SECURE_BOOTSTRAP_PORT,
+        Self::INTERNAL_PORT,
+        Self::SECURE_INTERNAL_PORT,
+        Self::CONTROLLER_PORT,
+        Self::SECURE_CONTROLLER_PORT,
+        METRICS_PORT,
+    ];
+    // - TLS stores, optionally prefixed with `listener.name.<listener>.`
+    const SSL_KEYSTORE_LOCATION: &'static str = "ssl.keystore.location";
+    const SSL_KEYSTORE_PASSWORD: &'static str = "ssl.keystore.password";
+    const SSL_KEYSTORE_TYPE: &'static str = "ssl.keystore.type";
+    const SSL_KEYSTORE_KEY: &'static str = "ssl.keystore.key";
+    const SSL_KEYSTORE_CERTIFICATE_CHAIN: &'static str = "ssl.keystore.certificate.chain";
+    const SSL_TRUSTSTORE_LOCATION: &'static str = "ssl.truststore.location";
+    const SSL_TRUSTSTORE_PASSWORD: &'static str = "ssl.truststore.password";
+    const SSL_TRUSTSTORE_TYPE: &'static str = "ssl.truststore.type";
+    const SSL_TRUSTSTORE_CERTIFICATES: &'static str = "ssl.truststore.certificates";
+    // - Client listeners, prefixed with `listener.name.<listener>.`
+    const SSL_CLIENT_AUTH: &'static str = "ssl.client.auth";
+    const SSL_PRINCIPAL_MAPPING_RULES: &'static str = "ssl.principal.mapping.rules";
+    const SASL_ENABLED_MECHANISMS: &'static str = "sasl.enabled.mechanisms";
+    const SSL_ENABLED_PROTOCOLS: &'static str = "ssl.enabled.protocols";
+    const SSL_CIPHER_SUITES: &'static str = "ssl.cipher.suites";
+    // - GSSAPI
+    const SASL_KERBEROS_PRINCIPAL_TO_LOCAL_RULES: &'static str =
+        "sasl.kerberos.principal.to.local.rules";
+    const GSSAPI_JAAS_CONFIG: &'static str = "gssapi.sasl.jaas.config";
+    // - OAUTHBEARER
+    const OAUTHBEARER_JAAS_CONFIG: &'static str = "oauthbearer.sasl.jaas.config";
+    const OAUTHBEARER_SERVER_CALLBACK_HANDLER_CLASS: &'static str =
+        "oauthbearer.sasl.server.callback.handler.class";
+    const OAUTHBEARER_JWKS_ENDPOINT_URL: &'static str = "sasl.oauthbearer.jwks.endpoint.url";
+    const OAUTHBEARER_EXPECTED_ISSUER: &'static str = "sasl.oauthbearer.expected.issuer";
+    const OAUTHBEARER_EXPECTED_AUDIENCE: &'static str = "sasl.oauthbearer.expected.audience";
+    const OAUTHBEARER_SUB_CLAIM_NAME: &'static str = "sasl.oauthbearer.sub.claim.name";
+    // - PLAIN (LDAP)
+    const PLAIN_JAAS_CONFIG: &'static str = "plain.sasl.jaas.config";
+    const PLAIN_SERVER_CALLBACK_HANDLER_CLASS: &'static str =
+        "plain.sasl.server.callback.handler.class";
+    const LDAP_PLAIN_SERVER_CALLBACK_HANDLER: &'static str =
+        "tech.stackable.kafka.security.LdapPlainServerCallbackHandler";
+    // - Delegation tokens
+    const CONFIG_PROVIDERS: &'static str = "config.providers";
+    const CONFIG_PROVIDERS_DIR_CLASS: &'static str = "config.providers.dir.class";
+    const DELEGATION_TOKEN_SECRET_KEY: &'static str = "delegation.token.secret.key";
     // - TLS internal
     const INTER_BROKER_LISTENER_NAME: &'static str = "inter.broker.listener.name";
-    const INTER_SSL_KEYSTORE_LOCATION: &'static str =
-        "listener.name.internal.ssl.keystore.location";
-    const INTER_SSL_KEYSTORE_PASSWORD: &'static str =
-        "listener.name.internal.ssl.keystore.password";
-    const INTER_SSL_KEYSTORE_TYPE: &'static str = "listener.name.internal.ssl.keystore.type";
-    const INTER_SSL_TRUSTSTORE_LOCATION: &'static str =
-        "listener.name.internal.ssl.truststore.location";
-    const INTER_SSL_TRUSTSTORE_PASSWORD: &'static str =
-        "listener.name.internal.ssl.truststore.password";
-    const INTER_SSL_TRUSTSTORE_TYPE: &'static str = "listener.name.internal.ssl.truststore.type";
+    const SASL_MECHANISM_INTER_BROKER_PROTOCOL: &'static str =
+        "sasl.mechanism.inter.broker.protocol";
     const INTER_SSL_CLIENT_AUTH: &'static str = "listener.name.internal.ssl.client.auth";
+    // - TLS controller (KRaft)
+    const CONTROLLER_SSL_CLIENT_AUTH: &'static str = "listener.name.controller.ssl.client.auth";
+    const SASL_MECHANISM_CONTROLLER_PROTOCOL: &'static str = "sasl.mechanism.controller.protocol";
+    // - Admin client
+    const SECURITY_PROTOCOL: &'static str = "security.protocol";
+    // - OPA authorizer
+    const OPA_AUTHORIZER_TRUSTSTORE_PATH: &'static str = "opa.authorizer.truststore.path";
+    const OPA_AUTHORIZER_TRUSTSTORE_PASSWORD: &'static str = "opa.authorizer.truststore.password";
+    const OPA_AUTHORIZER_TRUSTSTORE_TYPE: &'static str = "opa.authorizer.truststore.type";
+    // - ZooKeeper client
+    const ZOOKEEPER_SSL_CLIENT_ENABLE: &'static str = "zookeeper.ssl.client.enable";
+    const ZOOKEEPER_CLIENT_CNXN_SOCKET: &'static str = "zookeeper.clientCnxnSocket";
+    const ZOOKEEPER_SSL_KEYSTORE_LOCATION: &'static str = "zookeeper.ssl.keystore.location";
+    const ZOOKEEPER_SSL_KEYSTORE_PASSWORD: &'static str = "zookeeper.ssl.keystore.password";
+    const ZOOKEEPER_SSL_KEYSTORE_TYPE: &'static str = "zookeeper.ssl.keystore.type";
+    const ZOOKEEPER_SSL_TRUSTSTORE_LOCATION: &'static str = "zookeeper.ssl.truststore.location";
+    const ZOOKEEPER_SSL_TRUSTSTORE_PASSWORD: &'static str = "zookeeper.ssl.truststore.password";
+    const ZOOKEEPER_SSL_TRUSTSTORE_TYPE: &'static str = "zookeeper.ssl.truststore.type";
+    const ZOOKEEPER_SET_ACL: &'static str = "zookeeper.set.acl";
+    // - KRaft
+    const KRAFT_SERVER_PROPERTIES: &'static str = "/tmp/server.properties";
+    // Written to the first entry of `log.dirs` when the storage is formatted (or by ZooKeeper based brokers)
+    const KRAFT_META_PROPERTIES: &'static str = "/stackable/data/topicdata/meta.properties";
     // directories
     const STACKABLE_TLS_KCAT_DIR: &'static str = "/stackable/tls-kcat";
     const STACKABLE_TLS_KCAT_VOLUME_NAME: &'static str = "tls-kcat";
     const STACKABLE_TLS_KAFKA_SERVER_DIR: &'static str = "/stackable/tls-kafka-server";
     const STACKABLE_TLS_KAFKA_SERVER_VOLUME_NAME: &'static str = "tls-kafka-server";
+    const STACKABLE_TLS_KAFKA_BOOTSTRAP_DIR: &'static str = "/stackable/tls-kafka-bootstrap";
+    const STACKABLE_TLS_KAFKA_BOOTSTRAP_VOLUME_NAME: &'static str = "tls-kafka-bootstrap";
     const STACKABLE_TLS_KAFKA_INTERNAL_DIR: &'static str = "/stackable/tls-kafka-internal";
     const STACKABLE_TLS_KAFKA_INTERNAL_VOLUME_NAME: &'static str = "tls-kafka-internal";
+    const STACKABLE_TLS_STORE_PASSWORD_VOLUME_NAME: &'static str = "tls-store-password";
+    const STACKABLE_TLS_ZOOKEEPER_DIR: &'static str = "/stackable/tls-zookeeper";
+    const STACKABLE_TLS_ZOOKEEPER_VOLUME_NAME: &'static str = "tls-zookeeper";
+    const STACKABLE_ZOOKEEPER_KERBEROS_VOLUME_NAME: &'static str = "zookeeper-kerberos";
+    const STACKABLE_SCRAM_PROBER_DIR: &'static str = "/stackable/scram-prober";
+    const STACKABLE_SCRAM_PROBER_VOLUME_NAME: &'static str = "scram-prober";
+    const STACKABLE_INTERNAL_CREDENTIALS_DIR: &'static str = "/stackable/internal-credentials";
+    const STACKABLE_INTERNAL_CREDENTIALS_VOLUME_NAME: &'static str = "internal-credentials";
+    const STACKABLE_TLS_OIDC_DIR: &'static str = "/stackable/tls-oidc";
+    const STACKABLE_TLS_OIDC_VOLUME_NAME: &'static str = "tls-oidc";
+    const STACKABLE_OIDC_PROBER_DIR: &'static str = "/stackable/oidc-prober";
+    const STACKABLE_OIDC_PROBER_VOLUME_NAME: &'static str = "oidc-prober";
+    const STACKABLE_TLS_LDAP_DIR: &'static str = "/stackable/tls-ldap";
+    const STACKABLE_TLS_LDAP_VOLUME_NAME: &'static str = "tls-ldap";
+    const STACKABLE_TLS_OPA_DIR: &'static str = "/stackable/tls-opa";
+    const STACKABLE_TLS_OPA_VOLUME_NAME: &'static str = "tls-opa";
 
     #[cfg(test)]
     pub fn new(
@@ -151,6 +326,91 @@ impl KafkaTlsSecurity {
             resolved_authentication_classes,
             internal_secret_class,
             server_secret_class,
+            bootstrap_secret_class: None,
+            tls_policies: KafkaTlsPolicies::default(),
+            store_format: KafkaTlsStoreFormat::default(),
+            store_password: None,
+            zookeeper_tls_secret_class: None,
+            zookeeper_kerberos_secret_class: None,
+            opa_ca_cert_secret_class: None,
+            additional_listeners: vec![],
+            scram_prober_secret_name: None,
+            internal_credentials_secret_name: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_internal_credentials(self, secret_name: &str) -> Self {
+        Self {
+            internal_credentials_secret_name: Some(secret_name.to_string()),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_bootstrap_secret_class(self, bootstrap_secret_class: &str) -> Self {
+        Self {
+            bootstrap_secret_class: Some(bootstrap_secret_class.to_string()),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_tls_policies(self, tls_policies: KafkaTlsPolicies) -> Self {
+        Self {
+            tls_policies,
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_store_format(self, store_format: KafkaTlsStoreFormat) -> Self {
+        Self {
+            store_format,
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_store_password(self, secret_name: &str, password: &str) -> Self {
+        Self {
+            store_password: Some(KafkaTlsStorePassword {
+                secret_name: secret_name.to_string(),
+                password: password.to_string(),
+            }),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_zookeeper(
+        self,
+        zookeeper_tls_secret_class: Option<&str>,
+        zookeeper_kerberos_secret_class: Option<&str>,
+    ) -> Self {
+        Self {
+            zookeeper_tls_secret_class: zookeeper_tls_secret_class.map(str::to_string),
+            zookeeper_kerberos_secret_class: zookeeper_kerberos_secret_class.map(str::to_string),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_opa_ca_cert_secret_class(self, opa_ca_cert_secret_class: &str) -> Self {
+        Self {
+            opa_ca_cert_secret_class: Some(opa_ca_cert_secret_class.to_string()),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_additional_listeners(
+        self,
+        additional_listeners: Vec<KafkaAdditionalListener>,
+    ) -> Self {
+        Self {
+            additional_listeners,
+            ..self
         }
     }
 
@@ -160,6 +420,54 @@ impl KafkaTlsSecurity {
         client: &Client,
         kafka: &KafkaCluster,
     ) -> Result<Self, Error> {
+        let tls_policies = kafka
+            .spec
+            .cluster_config
+            .tls
+            .as_ref()
+            .map(|tls| tls.policies.clone())
+            .unwrap_or_default();
+        tls_policies.validate().context(InvalidTlsPolicySnafu)?;
+
+        // The Secret is created by the KafkaCluster controller before the security settings are
+        // resolved
+        let store_format = kafka.tls_store_format();
+        let store_password = match store_format {
+            KafkaTlsStoreFormat::Pkcs12 => Some(Self::read_store_password(client, kafka).await?),
+            KafkaTlsStoreFormat::Pem => None,
+        };
+
+        // ZooKeeper is only secured if the brokers or the controllers (during a migration)
+        // connect to it
+        let zookeeper_config = &kafka.spec.cluster_config.zookeeper;
+        let (zookeeper_tls_secret_class, zookeeper_kerberos_secret_class) =
+            match &kafka.spec.cluster_config.zookeeper_config_map_name {
+                Some(_) => (
+                    zookeeper_config
+                        .tls
+                        .as_ref()
+                        .map(|tls| tls.client_cert_secret_class.clone()),
+                    zookeeper_config
+                        .kerberos
+                        .as_ref()
+                        .map(|kerberos| kerberos.secret_class.clone()),
+                ),
+                None => (None, None),
+            };
+
+        let internal_secret_class = kafka
+            .spec
+            .cluster_config
+            .tls
+            .as_ref()
+            .map(|tls| tls.internal_secret_class.clone())
+            .unwrap_or_else(tls::internal_tls_default);
+        // The internal super user only logs in on internal listeners secured with TLS
+        let internal_credentials_secret_name =
+            (kafka.spec.cluster_config.authorization.is_enabled()
+                && !internal_secret_class.is_empty())
+            .then(|| kafka.internal_credentials_secret_name());
+
         Ok(KafkaTlsSecurity {
             resolved_authentication_classes: ResolvedAuthenticationClasses::from_references(
                 client,
@@ -167,19 +475,60 @@ impl KafkaTlsSecurity {
             )
             .await
             .context(InvalidAuthenticationClassConfigurationSnafu)?,
-            internal_secret_class: kafka
+            internal_secret_class,
+            server_secret_class: kafka
                 .spec
                 .cluster_config
                 .tls
                 .as_ref()
-                .map(|tls| tls.internal_secret_class.clone())
-                .unwrap_or_else(tls::internal_tls_default),
-            server_secret_class: kafka
+                .and_then(|tls| tls.server_secret_class.clone()),
+            bootstrap_secret_class: kafka
                 .spec
                 .cluster_config
                 .tls
                 .as_ref()
-                .and_then(|tls| tls.server_secret_class.clone()),
+                .and_then(|tls| tls.bootstrap_secret_class.clone()),
+            tls_policies,
+            store_format,
+            store_password,
+            zookeeper_tls_secret_class,
+            zookeeper_kerberos_secret_class,
+            opa_ca_cert_secret_class: kafka
+                .spec
+                .cluster_config
+                .authorization
+                .opa
+                .as_ref()
+                .and_then(|opa| opa.tls.tls_ca_cert_secret_class()),
+            additional_listeners: kafka.spec.cluster_config.listeners.clone(),
+            scram_prober_secret_name: Some(kafka.scram_prober_secret_name()),
+            internal_credentials_secret_name,
+        })
+    }
+
+    async fn read_store_password(
+        client: &Client,
+        kafka: &KafkaCluster,
+    ) -> Result<KafkaTlsStorePassword, Error> {
+        let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+        let secret_name = kafka.tls_store_password_secret_name();
+        let secret_ref = ObjectRef::<Secret>::new(&secret_name).within(&namespace);
+
+        let password = client
+            .get_opt::<Secret>(&secret_name, &namespace)
+            .await
+            .with_context(|_| RetrieveTlsStorePasswordSnafu {
+                secret: secret_ref.clone(),
+            })?
+            .and_then(|secret| secret.data)
+            .and_then(|mut data| data.remove(TLS_STORE_PASSWORD_SECRET_KEY))
+            .and_then(|password| String::from_utf8(password.0).ok())
+            .filter(|password| !password.is_empty())
+            .context(MissingTlsStorePasswordSnafu { secret: secret_ref })?;
+
+        Ok(KafkaTlsStorePassword {
+            secret_name,
+            password,
         })
     }
 
@@ -205,6 +554,13 @@ impl KafkaTlsSecurity {
             .get_tls_authentication_class()
     }
 
+    /// Retrieve the optional bootstrap `SecretClass`. It may only be set if the BOOTSTRAP listener
+    /// exists and uses TLS, which is checked by `validate_authentication_methods`. If it is not set,
+    /// the BOOTSTRAP listener uses the server `SecretClass`.
+    pub fn tls_bootstrap_secret_class(&self) -> Option<&str> {
+        self.bootstrap_secret_class.as_deref()
+    }
+
     /// Retrieve the mandatory internal `SecretClass`.
     pub fn tls_internal_secret_class(&self) -> Option<&str> {
         if !self.internal_secret_class.is_empty() {
@@ -214,6 +570,23 @@ impl KafkaTlsSecurity {
         }
     }
 
+    /// Retrieve the optional `SecretClass` of the client certificate the ZooKeeper client connects
+    /// with, which is only set if ZooKeeper is used and serves TLS.
+    pub fn zookeeper_tls_secret_class(&self) -> Option<&str> {
+        self.zookeeper_tls_secret_class.as_deref()
+    }
+
+    /// Retrieve the optional `SecretClass` with the CA of OPA, which is only set if the connection
+    /// to OPA uses TLS and the certificate is not verified with the CAs of the JVM (`webPki`).
+    pub fn opa_ca_cert_secret_class(&self) -> Option<&str> {
+        self.opa_ca_cert_secret_class.as_deref()
+    }
+
+    /// Retrieve the optional Kerberos `SecretClass` the ZooKeeper client logs in with.
+    pub fn zookeeper_kerberos_secret_class(&self) -> Option<&str> {
+        self.zookeeper_kerberos_secret_class.as_deref()
+    }
+
     pub fn has_kerberos_enabled(&self) -> bool {
         self.kerberos_secret_class().is_some()
     }
@@ -234,14 +607,263 @@ impl KafkaTlsSecurity {
         }
     }
 
+    /// Whether Kerberos logins are accepted on unencrypted connections (`SASL_PLAINTEXT`), which
+    /// has to be explicitly enabled.
+    pub fn has_kerberos_sasl_plaintext_enabled(&self) -> bool {
+        self.has_kerberos_enabled()
+            && self
+                .resolved_authentication_classes
+                .kerberos_options()
+                .sasl_plaintext
+    }
+
+    /// Whether the brokers authenticate to each other with their Kerberos principals on the
+    /// INTERNAL listener, which has to be explicitly enabled.
+    pub fn has_kerberos_inter_broker_enabled(&self) -> bool {
+        self.has_kerberos_enabled()
+            && self
+                .resolved_authentication_classes
+                .kerberos_options()
+                .inter_broker
+    }
+
+    /// Whether the brokers, controllers and admin Jobs log in to the INTERNAL and CONTROLLER
+    /// listeners as the internal super user with a generated password, which is the case if an
+    /// authorizer is configured and the internal listeners use TLS. With Kerberos between the
+    /// brokers, the INTERNAL listener authenticates the Kerberos principals instead.
+    pub fn has_internal_credentials_enabled(&self) -> bool {
+        self.internal_credentials_secret_name.is_some()
+    }
+
+    /// Returns the `krb5.conf` sections of the realms trusted by the realm of the Kerberos
+    /// `SecretClass`, if there are any.
+    pub fn kerberos_trusted_realms_config(&self) -> Option<String> {
+        if !self.has_kerberos_enabled() {
+            return None;
+        }
+        self.resolved_authentication_classes
+            .kerberos_options()
+            .krb5_trusted_realms_config()
+    }
+
+    /// Returns the path of the `krb5.conf` the brokers use, which is extended by the trusted
+    /// realms at startup if there are any.
+    pub fn broker_krb5_config_path(&self) -> &'static str {
+        if self.kerberos_trusted_realms_config().is_some() {
+            STACKABLE_TRUSTED_REALMS_KRB5_PATH
+        } else {
+            STACKABLE_KERBEROS_KRB5_PATH
+        }
+    }
+
+    pub fn has_scram_enabled(&self) -> bool {
+        self.scram_user_credentials_secret().is_some()
+    }
+
+    /// Retrieve the Secret with the users of a Static `AuthenticationClass`, which are
+    /// bootstrapped as SCRAM credentials.
+    pub fn scram_user_credentials_secret(&self) -> Option<&str> {
+        self.resolved_authentication_classes
+            .get_static_authentication_class()
+            .and_then(|auth_class| match &auth_class.spec.provider {
+                AuthenticationClassProvider::Static(provider) => {
+                    Some(provider.user_credentials_secret.name.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    /// Whether the brokers issue delegation tokens, which is the case if clients authenticate with
+    /// Kerberos or SCRAM. Clients log in with a token by SCRAM on the listener of either method.
+    pub fn has_delegation_tokens_enabled(&self) -> bool {
+        self.has_kerberos_enabled() || self.has_scram_enabled()
+    }
+
+    pub fn has_oidc_enabled(&self) -> bool {
+        self.oidc_provider().is_some()
+    }
+
+    /// Retrieve the OIDC provider of an OIDC `AuthenticationClass`, whose access tokens are
+    /// accepted with OAUTHBEARER.
+    pub fn oidc_provider(&self) -> Option<&oidc::AuthenticationProvider> {
+        self.resolved_authentication_classes
+            .get_oidc_authentication_class()
+            .and_then(|auth_class| match &auth_class.spec.provider {
+                AuthenticationClassProvider::Oidc(provider) => Some(provider),
+                _ => None,
+            })
+    }
+
+    pub fn has_ldap_enabled(&self) -> bool {
+        self.ldap_provider().is_some()
+    }
+
+    /// Retrieve the LDAP provider of an LDAP `AuthenticationClass`, against which the passwords
+    /// of PLAIN logins are verified.
+    pub fn ldap_provider(&self) -> Option<&ldap::AuthenticationProvider> {
+        self.resolved_authentication_classes
+            .get_ldap_authentication_class()
+            .and_then(|auth_class| match &auth_class.spec.provider {
+                AuthenticationClassProvider::Ldap(provider) => Some(provider),
+                _ => None,
+            })
+    }
+
     pub fn validate_authentication_methods(&self) -> Result<(), Error> {
-        // Client TLS authentication and Kerberos authentication are mutually
-        // exclusive, but this has already been checked when checking the
-        // authentication classes. When users enable Kerberos we require them
+        // Every AuthenticationClass is served by its own listener, so their
+        // providers can be combined. When users enable Kerberos we require them
         // to also enable TLS for a) maximum security and b) to limit the
-        // number of combinations we need to support.
+        // number of combinations we need to support. Unencrypted connections
+        // (e.g. if encryption is done by a service mesh) must be explicitly requested.
         if self.has_kerberos_enabled() {
-            ensure!(self.server_secret_class.is_some(), KerberosRequiresTlsSnafu);
+            ensure!(
+                self.server_secret_class.is_some() || self.has_kerberos_sasl_plaintext_enabled(),
+                KerberosRequiresTlsSnafu
+            );
+        }
+        // Kerberos on the INTERNAL listener is used on top of the internal TLS
+        if self.has_kerberos_inter_broker_enabled() {
+            ensure!(
+                self.tls_internal_secret_class().is_some(),
+                KerberosInterBrokerRequiresInternalTlsSnafu
+            );
+        }
+        // The same applies to SCRAM, as the passwords must not be sent unencrypted.
+        if self.has_scram_enabled() {
+            ensure!(self.server_secret_class.is_some(), ScramRequiresTlsSnafu);
+        }
+        // ... and to OAUTHBEARER, as the access tokens must not be sent unencrypted.
+        if self.has_oidc_enabled() {
+            ensure!(self.server_secret_class.is_some(), OidcRequiresTlsSnafu);
+        }
+        // ... and to PLAIN, as the passwords are sent in clear text.
+        if self.has_ldap_enabled() {
+            ensure!(self.server_secret_class.is_some(), LdapRequiresTlsSnafu);
+        }
+        // The bootstrap SecretClass is only used for the BOOTSTRAP listener of Kerberos with TLS
+        if let Some(secret_class) = self.tls_bootstrap_secret_class() {
+            ensure!(
+                self.kerberos_client_listener()
+                    .is_some_and(|kerberos_listener| kerberos_listener.protocol.uses_tls()),
+                BootstrapSecretClassRequiresKerberosTlsSnafu { secret_class }
+            );
+        }
+        // The JVM reads a single krb5.conf, which is the one of the client Kerberos SecretClass
+        if let (Some(zookeeper_secret_class), Some(kerberos_secret_class)) = (
+            self.zookeeper_kerberos_secret_class(),
+            self.kerberos_secret_class(),
+        ) {
+            ensure!(
+                zookeeper_secret_class == kerberos_secret_class,
+                ZookeeperKerberosSecretClassMismatchSnafu {
+                    zookeeper_secret_class,
+                    kerberos_secret_class,
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validates the user-defined listeners. Currently errors out if:
+    /// - The name of a listener is invalid or its Kafka listener name is not unique
+    /// - The port of a listener is reserved or used by another client listener
+    /// - The AuthenticationClass of a listener is not listed or uses Kerberos
+    /// - The protocol does not match the AuthenticationClass or requires missing TLS settings
+    /// - A loopback listener does not use `PLAINTEXT` without an AuthenticationClass
+    pub fn validate_listeners(&self) -> Result<(), Error> {
+        for listener in &self.additional_listeners {
+            authentication::validate_listener_name(&listener.name).context(
+                InvalidListenerNameSnafu {
+                    listener: &listener.name,
+                },
+            )?;
+        }
+
+        let mut listener_names = BTreeSet::new();
+        let mut ports = BTreeSet::from(Self::RESERVED_PORTS);
+        for client_listener in self.client_listeners() {
+            let name = client_listener.name.to_string();
+            ensure!(
+                listener_names.insert(name.clone()),
+                DuplicateListenerNameSnafu { name }
+            );
+            // The ports of the default client listener are reserved anyway
+            if client_listener.dedicated.is_some() {
+                ensure!(
+                    ports.insert(client_listener.port),
+                    DuplicateListenerPortSnafu {
+                        listener: client_listener.name.to_string(),
+                        port: client_listener.port,
+                    }
+                );
+            }
+        }
+
+        for listener in &self.additional_listeners {
+            let expected_protocol = match &listener.authentication_class {
+                Some(authentication_class) => {
+                    let auth_class = self
+                        .resolved_authentication_classes
+                        .get_authentication_class(authentication_class)
+                        .with_context(|| UnknownListenerAuthenticationClassSnafu {
+                            listener: &listener.name,
+                            authentication_class,
+                        })?;
+                    let authentication =
+                        KafkaClientAuthentication::from_provider(&auth_class.spec.provider);
+                    // The BOOTSTRAP listener and the principals only serve a single Kerberos
+                    // listener
+                    ensure!(
+                        authentication != KafkaClientAuthentication::Kerberos,
+                        ListenerKerberosNotSupportedSnafu {
+                            listener: &listener.name,
+                            authentication_class,
+                        }
+                    );
+                    Some(authentication.protocol())
+                }
+                None => None,
+            };
+
+            if listener.loopback {
+                ensure!(
+                    listener.protocol == KafkaListenerProtocol::Plaintext
+                        && listener.authentication_class.is_none(),
+                    LoopbackListenerRequiresPlaintextSnafu {
+                        listener: &listener.name,
+                    }
+                );
+            }
+            match expected_protocol {
+                Some(expected) => ensure!(
+                    listener.protocol == expected,
+                    ListenerProtocolMismatchSnafu {
+                        listener: &listener.name,
+                        protocol: listener.protocol,
+                        expected,
+                    }
+                ),
+                None => ensure!(
+                    matches!(
+                        listener.protocol,
+                        KafkaListenerProtocol::Plaintext | KafkaListenerProtocol::Ssl
+                    ),
+                    ListenerProtocolMismatchSnafu {
+                        listener: &listener.name,
+                        protocol: listener.protocol,
+                        expected: KafkaListenerProtocol::Plaintext,
+                    }
+                ),
+            }
+            if listener.protocol.uses_tls() {
+                ensure!(
+                    self.get_tls_secret_class().is_some(),
+                    ListenerRequiresTlsSnafu {
+                        listener: &listener.name,
+                    }
+                );
+            }
         }
 
         Ok(())
@@ -249,7 +871,7 @@ impl KafkaTlsSecurity {
 
     /// Return the Kafka (secure) client port depending on tls or authentication settings.
     pub fn client_port(&self) -> u16 {
-        if self.tls_enabled() {
+        if self.default_client_listener_protocol().uses_tls() {
             Self::SECURE_CLIENT_PORT
         } else {
             Self::CLIENT_PORT
@@ -257,7 +879,7 @@ impl KafkaTlsSecurity {
     }
 
     pub fn bootstrap_port(&self) -> u16 {
-        if self.tls_enabled() {
+        if self.tls_enabled() && !self.has_kerberos_sasl_plaintext_enabled() {
             Self::SECURE_BOOTSTRAP_PORT
         } else {
             Self::BOOTSTRAP_PORT
@@ -270,13 +892,163 @@ impl KafkaTlsSecurity {
 
     /// Return the Kafka (secure) client port name depending on tls or authentication settings.
     pub fn client_port_name(&self) -> &str {
-        if self.tls_enabled() {
+        if self.default_client_listener_protocol().uses_tls() {
             Self::SECURE_CLIENT_PORT_NAME
         } else {
             Self::CLIENT_PORT_NAME
         }
     }
 
+    /// Returns the listeners for client connections: the default client listener, which serves
+    /// the first AuthenticationClass (if any), followed by a dedicated listener for every further
+    /// AuthenticationClass and by the user-defined listeners.
+    pub fn client_listeners(&self) -> Vec<KafkaClientListener> {
+        let mut auth_classes = self
+            .resolved_authentication_classes
+            .authentication_classes_with_listeners();
+
+        let default_listener = match auth_classes.next() {
+            Some((auth_class, _)) => {
+                let authentication =
+                    KafkaClientAuthentication::from_provider(&auth_class.spec.provider);
+                KafkaClientListener {
+                    // If client authentication is required, we expose the CLIENT_AUTH listener
+                    name: if authentication == KafkaClientAuthentication::Tls {
+                        KafkaListenerName::ClientAuth
+                    } else {
+                        KafkaListenerName::Client
+                    },
+                    protocol: self.client_listener_protocol(Some(authentication)),
+                    authentication: Some(authentication),
+                    port_name: self.client_port_name().to_string(),
+                    port: self.client_port(),
+                    dedicated: None,
+                    loopback: false,
+                }
+            }
+            None => KafkaClientListener {
+                name: KafkaListenerName::Client,
+                protocol: self.client_listener_protocol(None),
+                authentication: None,
+                port_name: self.client_port_name().to_string(),
+                port: self.client_port(),
+                dedicated: None,
+                loopback: false,
+            },
+        };
+
+        let dedicated_listeners =
+            auth_classes
+                .zip(1..)
+                .filter_map(|((auth_class, dedicated), index)| {
+                    // Has already been validated, only the first AuthenticationClass has no
+                    // dedicated listener
+                    let dedicated = dedicated?;
+                    let authentication =
+                        KafkaClientAuthentication::from_provider(&auth_class.spec.provider);
+                    Some(KafkaClientListener {
+                        name: KafkaListenerName::Custom(format!(
+                            "CLIENT_{}",
+                            dedicated.name.to_uppercase()
+                        )),
+                        protocol: self.client_listener_protocol(Some(authentication)),
+                        authentication: Some(authentication),
+                        port_name: format!("kafka-{}", dedicated.name),
+                        port: Self::ADDITIONAL_CLIENT_PORT + index,
+                        dedicated: Some(dedicated.clone()),
+                        loopback: false,
+                    })
+                });
+
+        let additional_listeners = self.additional_listeners.iter().map(|listener| {
+            // Unknown AuthenticationClasses are rejected by `validate_listeners`
+            let authentication = listener
+                .authentication_class
+                .as_deref()
+                .and_then(|name| {
+                    self.resolved_authentication_classes
+                        .get_authentication_class(name)
+                })
+                .map(|auth_class| {
+                    KafkaClientAuthentication::from_provider(&auth_class.spec.provider)
+                });
+            KafkaClientListener {
+                name: KafkaListenerName::Custom(format!("CLIENT_{}", listener.name.to_uppercase())),
+                protocol: listener.protocol,
+                authentication,
+                port_name: format!("kafka-{}", listener.name),
+                port: listener.port,
+                dedicated: Some(listener.authentication_listener()),
+                loopback: listener.loopback,
+            }
+        });
+
+        std::iter::once(default_listener)
+            .chain(dedicated_listeners)
+            .chain(additional_listeners)
+            .collect()
+    }
+
+    /// Returns the client listeners which are exposed with listener volumes, i.e. all but the
+    /// ones bound to the loopback interface.
+    pub fn exposed_client_listeners(&self) -> Vec<KafkaClientListener> {
+        self.client_listeners()
+            .into_iter()
+            .filter(|client_listener| !client_listener.loopback)
+            .collect()
+    }
+
+    /// The protocol of a client listener with the given authentication method.
+    fn client_listener_protocol(
+        &self,
+        authentication: Option<KafkaClientAuthentication>,
+    ) -> KafkaListenerProtocol {
+        match authentication {
+            Some(KafkaClientAuthentication::Kerberos)
+                if self.has_kerberos_sasl_plaintext_enabled() =>
+            {
+                KafkaListenerProtocol::SaslPlaintext
+            }
+            Some(authentication) => authentication.protocol(),
+            None if self.tls_server_secret_class().is_some() => KafkaListenerProtocol::Ssl,
+            None => KafkaListenerProtocol::Plaintext,
+        }
+    }
+
+    /// The protocol of the default client listener, which serves the first AuthenticationClass.
+    fn default_client_listener_protocol(&self) -> KafkaListenerProtocol {
+        let authentication = self
+            .resolved_authentication_classes
+            .authentication_classes_with_listeners()
+            .next()
+            .map(|(auth_class, _)| {
+                KafkaClientAuthentication::from_provider(&auth_class.spec.provider)
+            });
+        self.client_listener_protocol(authentication)
+    }
+
+    /// Returns the client listener which serves the Kerberos `AuthenticationClass`, if any.
+    /// Its addresses are used for the principals in the keytab.
+    pub fn kerberos_client_listener(&self) -> Option<KafkaClientListener> {
+        self.client_listeners().into_iter().find(|client_listener| {
+            client_listener.authentication == Some(KafkaClientAuthentication::Kerberos)
+        })
+    }
+
+    /// Returns the names of all listener volumes of the client listeners. Certificates and
+    /// keytabs are issued for their addresses.
+    pub fn client_listener_volume_names(&self) -> Vec<String> {
+        self.exposed_client_listeners()
+            .iter()
+            .flat_map(|client_listener| {
+                [
+                    client_listener.broker_listener_volume_name(),
+                    client_listener.bootstrap_listener_volume_name(),
+                ]
+            })
+            .collect()
+    }
+
     /// Return the Kafka (secure) internal port depending on tls settings.
     pub fn internal_port(&self) -> u16 {
         if self.tls_internal_secret_class().is_some() {
@@ -286,17 +1058,31 @@ impl KafkaTlsSecurity {
         }
     }
 
+    /// Return the port of the KRaft controller listener depending on the internal tls settings.
+    pub fn controller_port(&self) -> u16 {
+        if self.tls_internal_secret_class().is_some() {
+            Self::SECURE_CONTROLLER_PORT
+        } else {
+            Self::CONTROLLER_PORT
+        }
+    }
+
     /// Returns the commands for the kcat readiness probe.
     pub fn kcat_prober_container_commands(&self, pod_fqdn: &String) -> Vec<String> {
         let mut args = vec![];
         let port = self.client_port();
+        // The prober connects to the default client listener
+        let default_listener = &self.client_listeners()[0];
+        let authentication = default_listener.authentication;
 
-        if self.tls_client_authentication_class().is_some() {
+        if authentication == Some(KafkaClientAuthentication::Tls) {
+            // The prober presents a certificate, which is accepted with required as well as
+            // requested client authentication
             args.push("/stackable/kcat".to_string());
             args.push("-b".to_string());
             args.push(format!("localhost:{}", port));
-            args.extend(Self::kcat_client_auth_ssl(Self::STACKABLE_TLS_KCAT_DIR));
-        } else if self.has_kerberos_enabled() {
+            args.extend(self.kcat_client_auth_ssl(Self::STACKABLE_TLS_KCAT_DIR));
+        } else if authentication == Some(KafkaClientAuthentication::Kerberos) {
             let service_name = KafkaRole::Broker.kerberos_service_name();
             // here we need to specify a shell so that variable substitution will work
             // see e.g. https://github.com/kubernetes-client/python/blob/master/kubernetes/docs/V1ExecAction.md
@@ -315,16 +1101,64 @@ impl KafkaTlsSecurity {
             args.push("/stackable/kcat".to_string());
             args.push("-b".to_string());
             args.push(format!("{pod_fqdn}:{port}"));
-            args.extend(Self::kcat_client_sasl_ssl(
-                Self::STACKABLE_TLS_KCAT_DIR,
-                service_name,
-                pod_fqdn,
+            args.extend(
+                self.kcat_client_sasl_kerberos(
+                    default_listener
+                        .protocol
+                        .uses_tls()
+                        .then_some(Self::STACKABLE_TLS_KCAT_DIR),
+                    service_name,
+                    pod_fqdn,
+                ),
+            );
+        } else if authentication == Some(KafkaClientAuthentication::Scram) {
+            // The password is read from the mounted Secret, so a shell is required. Tracing is
+            // not enabled, as the password would be part of the probe output.
+            args.push("/bin/bash".to_string());
+            args.push("-euo".to_string());
+            args.push("pipefail".to_string());
+            args.push("-c".to_string());
+            args.push(format!(
+                "/stackable/kcat -b localhost:{port} {sasl_ssl} -L",
+                sasl_ssl = self
+                    .kcat_client_sasl_scram(Self::STACKABLE_TLS_KCAT_DIR)
+                    .join(" "),
+            ));
+            // the metadata request is already part of the shell command
+            return args;
+        } else if let Some(oauthbearer_settings) = authentication
+            .filter(|authentication| *authentication == KafkaClientAuthentication::Oidc)
+            .and_then(|_| self.kcat_client_sasl_oauthbearer(Self::STACKABLE_TLS_KCAT_DIR))
+        {
+            // The client credentials are read from the mounted Secret, so a shell is required.
+            // Tracing is not enabled, as the client secret would be part of the probe output.
+            args.push("/bin/bash".to_string());
+            args.push("-euo".to_string());
+            args.push("pipefail".to_string());
+            args.push("-c".to_string());
+            args.push(format!(
+                "/stackable/kcat -b localhost:{port} {oauthbearer} -L",
+                oauthbearer = oauthbearer_settings.join(" "),
             ));
+            // the metadata request is already part of the shell command
+            return args;
+        } else if matches!(
+            authentication,
+            Some(KafkaClientAuthentication::Oidc | KafkaClientAuthentication::Ldap)
+        ) {
+            // Without prober credentials, kcat cannot obtain an access token, and there is no LDAP
+            // user it could log in with. A broken TLS or SASL configuration of the listener makes
+            // the brokers fail at startup, so it is only checked that the listener accepts
+            // connections.
+            args.push("/bin/bash".to_string());
+            args.push("-c".to_string());
+            args.push(format!("exec 3<>/dev/tcp/localhost/{port}"));
+            return args;
         } else if self.tls_server_secret_class().is_some() {
             args.push("/stackable/kcat".to_string());
             args.push("-b".to_string());
             args.push(format!("localhost:{}", port));
-            args.extend(Self::kcat_client_ssl(Self::STACKABLE_TLS_KCAT_DIR));
+            args.extend(self.kcat_client_ssl(Self::STACKABLE_TLS_KCAT_DIR));
         } else {
             args.push("/stackable/kcat".to_string());
             args.push("-b".to_string());
@@ -335,12 +1169,14 @@ impl KafkaTlsSecurity {
         args
     }
 
-    /// Returns the commands to start the main Kafka container
+    /// Returns the commands to start the main Kafka container.
+    /// The KRaft cluster id must be provided if the cluster runs in KRaft mode.
     pub fn kafka_container_commands(
         &self,
         kafka_listeners: &KafkaListenerConfig,
         opa_connect_string: Option<&str>,
         kerberos_enabled: bool,
+        kraft_cluster_id: Option<&str>,
     ) -> Vec<String> {
         vec![formatdoc! {"
             {COMMON_BASH_TRAP_FUNCTIONS}
@@ -348,7 +1184,8 @@ impl KafkaTlsSecurity {
             prepare_signal_handlers
             containerdebug --output={STACKABLE_LOG_DIR}/containerdebug-state.json --loop &
             {set_realm_env}
-            bin/kafka-server-start.sh {STACKABLE_CONFIG_DIR}/{SERVER_PROPERTIES_FILE} --override \"zookeeper.connect=$ZOOKEEPER\" --override \"listeners={listeners}\" --override \"advertised.listeners={advertised_listeners}\" --override \"listener.security.protocol.map={listener_security_protocol_map}\"{opa_config}{jaas_config} &
+            {check_ldap_callback_handler}
+            {start_command}{opa_config}{jaas_config} &
             wait_for_termination $!
             {create_vector_shutdown_file_command}
             ",
@@ -357,28 +1194,157 @@ impl KafkaTlsSecurity {
         create_vector_shutdown_file_command =
             create_vector_shutdown_file_command(STACKABLE_LOG_DIR),
             set_realm_env = match kerberos_enabled {
-                true => format!("export KERBEROS_REALM=$(grep -oP 'default_realm = \\K.*' {})", STACKABLE_KERBEROS_KRB5_PATH),
+                true => self.kerberos_realm_commands(),
+                false => "".to_string(),
+            },
+            check_ldap_callback_handler = match self.has_ldap_enabled() {
+                true => Self::ldap_callback_handler_check_command(),
                 false => "".to_string(),
             },
-            listeners = kafka_listeners.listeners(),
-            advertised_listeners = kafka_listeners.advertised_listeners(),
-            listener_security_protocol_map = kafka_listeners.listener_security_protocol_map(),
+            start_command = match kraft_cluster_id {
+                Some(cluster_id) => self.kraft_start_command(kafka_listeners, cluster_id),
+                None => format!(
+                    "bin/kafka-server-start.sh {STACKABLE_CONFIG_DIR}/{SERVER_PROPERTIES_FILE} --override \"zookeeper.connect=$ZOOKEEPER\" --override \"listeners={listeners}\" --override \"advertised.listeners={advertised_listeners}\" --override \"listener.security.protocol.map={listener_security_protocol_map}\"",
+                    listeners = kafka_listeners.listeners(),
+                    advertised_listeners = kafka_listeners.advertised_listeners(),
+                    listener_security_protocol_map = kafka_listeners.listener_security_protocol_map(),
+                ),
+            },
             opa_config = match opa_connect_string {
                 None => "".to_string(),
                 Some(opa_connect_string) => format!(" --override \"opa.authorizer.url={opa_connect_string}\""),
             },
             jaas_config = match kerberos_enabled {
-                true => {
-                    let service_name = KafkaRole::Broker.kerberos_service_name();
-                    let broker_address = node_address_cmd(STACKABLE_LISTENER_BROKER_DIR);
-                    let bootstrap_address = node_address_cmd(STACKABLE_LISTENER_BOOTSTRAP_DIR);
-                    // TODO replace client and bootstrap below with constants
-                    format!(" --override \"listener.name.client.gssapi.sasl.jaas.config=com.sun.security.auth.module.Krb5LoginModule required useKeyTab=true storeKey=true isInitiator=false keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"{service_name}/{broker_address}@$KERBEROS_REALM\\\";\" --override \"listener.name.bootstrap.gssapi.sasl.jaas.config=com.sun.security.auth.module.Krb5LoginModule required useKeyTab=true storeKey=true isInitiator=false keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"{service_name}/{bootstrap_address}@$KERBEROS_REALM\\\";\"").to_string()},
+                true => self.kerberos_jaas_config_overrides(kafka_listeners),
                 false => "".to_string(),
             },
         }]
     }
 
+    /// The LDAP callback handler is not part of Apache Kafka, but of the product image. Instead of
+    /// failing with a `ClassNotFoundException` on the first PLAIN login, the broker refuses to start
+    /// if the image does not contain it.
+    fn ldap_callback_handler_check_command() -> String {
+        let class_file = format!(
+            "{}.class",
+            Self::LDAP_PLAIN_SERVER_CALLBACK_HANDLER.replace('.', "/")
+        );
+        formatdoc! {"
+            if ! grep -rqF '{class_file}' libs; then
+              echo 'LDAP authentication requires a Kafka product image which contains the {class_name}, see https://docs.stackable.tech/home/stable/kafka/usage-guide/security#_ldap'
+              exit 1
+            fi",
+            class_name = Self::LDAP_PLAIN_SERVER_CALLBACK_HANDLER,
+        }
+    }
+
+    /// Exports the default realm of the Kerberos `SecretClass` and appends the trusted realms to
+    /// a copy of its `krb5.conf`. Sections which occur twice are merged by Kerberos.
+    fn kerberos_realm_commands(&self) -> String {
+        let mut commands = vec![format!(
+            "export KERBEROS_REALM=$(grep -oP 'default_realm = \\K.*' {STACKABLE_KERBEROS_KRB5_PATH})"
+        )];
+        if self.kerberos_trusted_realms_config().is_some() {
+            commands.push(format!(
+                "(cat {STACKABLE_KERBEROS_KRB5_PATH}; echo; cat {STACKABLE_CONFIG_DIR}/{KRB5_TRUSTED_REALMS_FILE}) > {STACKABLE_TRUSTED_REALMS_KRB5_PATH}"
+            ));
+        }
+        commands.join("\n")
+    }
+
+    /// Returns the `--override` arguments with the JAAS configurations of the listeners which
+    /// authenticate with Kerberos. The principals are taken from the keytab, so they have to match
+    /// the scopes of the Kerberos volume.
+    fn kerberos_jaas_config_overrides(&self, kafka_listeners: &KafkaListenerConfig) -> String {
+        let service_name = KafkaRole::Broker.kerberos_service_name();
+        let mut jaas_configs = Vec::new();
+
+        if let Some(kerberos_listener) = self.kerberos_client_listener() {
+            let broker_address = node_address_cmd(&kerberos_listener.broker_listener_dir());
+            let bootstrap_address = node_address_cmd(&kerberos_listener.bootstrap_listener_dir());
+            jaas_configs.push((
+                kerberos_listener.name.config_key(Self::GSSAPI_JAAS_CONFIG),
+                format!("isInitiator=false keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"{service_name}/{broker_address}@$KERBEROS_REALM\\\""),
+            ));
+            jaas_configs.push((
+                KafkaListenerName::Bootstrap.config_key(Self::GSSAPI_JAAS_CONFIG),
+                format!("isInitiator=false keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"{service_name}/{bootstrap_address}@$KERBEROS_REALM\\\""),
+            ));
+        }
+
+        // The brokers use the INTERNAL JAAS configuration as server as well as when connecting to
+        // other brokers, so it must be able to initiate a security context.
+        if let Some(pod_fqdn) = self
+            .has_kerberos_inter_broker_enabled()
+            .then(|| kafka_listeners.advertised_host(&KafkaListenerName::Internal))
+            .flatten()
+        {
+            jaas_configs.push((
+                KafkaListenerName::Internal.config_key(Self::GSSAPI_JAAS_CONFIG),
+                format!("keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"{service_name}/{pod_fqdn}@$KERBEROS_REALM\\\""),
+            ));
+        }
+
+        jaas_configs
+            .into_iter()
+            .map(|(key, options)| {
+                format!(" --override \"{key}=com.sun.security.auth.module.Krb5LoginModule required useKeyTab=true storeKey=true {options};\"")
+            })
+            .collect()
+    }
+
+    /// In KRaft mode the metadata log directories have to be formatted before the first start, which
+    /// requires a complete configuration file. The Pod specific settings are therefore appended to a
+    /// copy of the `server.properties` instead of being passed as overrides.
+    ///
+    /// An existing `node.id` (or `broker.id` of a broker migrated from ZooKeeper) is kept, otherwise
+    /// it is derived from the Pod ordinal. The ZooKeeper connection is only added if the `ZOOKEEPER`
+    /// env var is set, which is the case for controllers during a migration from ZooKeeper.
+    /// These controllers refuse to start unless the KRaft cluster id matches the `cluster.id` stored
+    /// in ZooKeeper, as the migration would otherwise create a second, empty cluster.
+    fn kraft_start_command(
+        &self,
+        kafka_listeners: &KafkaListenerConfig,
+        cluster_id: &str,
+    ) -> String {
+        let mut settings = vec![
+            "node.id=$NODE_ID".to_string(),
+            format!("listeners={}", kafka_listeners.listeners()),
+            format!(
+                "listener.security.protocol.map={}",
+                kafka_listeners.listener_security_protocol_map()
+            ),
+        ];
+        let advertised_listeners = kafka_listeners.advertised_listeners();
+        if !advertised_listeners.is_empty() {
+            settings.push(format!("advertised.listeners={advertised_listeners}"));
+        }
+
+        formatdoc! {"
+            cp {STACKABLE_CONFIG_DIR}/{SERVER_PROPERTIES_FILE} {server_properties}
+            NODE_ID=$(grep -oP '^(node|broker)\\.id=\\K.*' {meta_properties} 2>/dev/null || echo $((NODE_ID_OFFSET + ${{POD_NAME##*-}})))
+            {append_settings}
+            if [ -n \"${{ZOOKEEPER:-}}\" ]; then
+              echo \"zookeeper.connect=$ZOOKEEPER\" >> {server_properties}
+              ZOOKEEPER_CLUSTER_ID=$(KAFKA_OPTS=\"{zookeeper_jvm_args}\" bin/zookeeper-shell.sh \"$ZOOKEEPER\" -zk-tls-config-file {server_properties} get /cluster/id | grep -oP '\"id\":\"\\K[^\"]+' || true)
+              if [ \"$ZOOKEEPER_CLUSTER_ID\" != \"{cluster_id}\" ]; then
+                echo \"The KRaft cluster id {cluster_id} does not match the cluster id [$ZOOKEEPER_CLUSTER_ID] stored in ZooKeeper\"
+                exit 1
+              fi
+            fi
+            bin/kafka-storage.sh format --cluster-id {cluster_id} --config {server_properties} --ignore-formatted
+            bin/kafka-server-start.sh {server_properties}",
+            server_properties = Self::KRAFT_SERVER_PROPERTIES,
+            meta_properties = Self::KRAFT_META_PROPERTIES,
+            zookeeper_jvm_args = self.zookeeper_jvm_args(&KafkaRole::Controller).join(" "),
+            append_settings = settings
+                .iter()
+                .map(|setting| format!("echo \"{setting}\" >> {}", Self::KRAFT_SERVER_PROPERTIES))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
     /// Adds required volumes and volume mounts to the pod and container builders
     /// depending on the tls and authentication settings.
     pub fn add_volume_and_volume_mounts(
@@ -388,6 +1354,9 @@ impl KafkaTlsSecurity {
         cb_kafka: &mut ContainerBuilder,
         requested_secret_lifetime: &Duration,
     ) -> Result<(), Error> {
+        // The certificates are issued for the addresses of all client listeners
+        let listener_volume_names = self.client_listener_volume_names();
+
         // add tls (server or client authentication volumes) if required
         if let Some(tls_server_secret_class) = self.get_tls_secret_class() {
             // We have to mount tls pem files for kcat (the mount can be used directly)
@@ -406,10 +1375,13 @@ impl KafkaTlsSecurity {
                 .context(AddVolumeMountSnafu)?;
             // Keystores fore the kafka container
             pod_builder
-                .add_volume(Self::create_tls_keystore_volume(
+                .add_volume(self.create_tls_keystore_volume(
                     Self::STACKABLE_TLS_KAFKA_SERVER_VOLUME_NAME,
                     tls_server_secret_class,
+                    self.store_format,
                     requested_secret_lifetime,
+                    true,
+                    &listener_volume_names,
                 )?)
                 .context(AddVolumeSnafu)?;
             cb_kafka
@@ -422,10 +1394,13 @@ impl KafkaTlsSecurity {
 
         if let Some(tls_internal_secret_class) = self.tls_internal_secret_class() {
             pod_builder
-                .add_volume(Self::create_tls_keystore_volume(
+                .add_volume(self.create_tls_keystore_volume(
                     Self::STACKABLE_TLS_KAFKA_INTERNAL_VOLUME_NAME,
                     tls_internal_secret_class,
+                    self.store_format,
                     requested_secret_lifetime,
+                    true,
+                    &listener_volume_names,
                 )?)
                 .context(AddVolumeSnafu)?;
             cb_kafka
@@ -436,153 +1411,966 @@ impl KafkaTlsSecurity {
                 .context(AddVolumeMountSnafu)?;
         }
 
-        Ok(())
-    }
-
-    /// Returns required Kafka configuration settings for the `server.properties` file
-    /// depending on the tls and authentication settings.
-    pub fn config_settings(&self) -> BTreeMap<String, String> {
-        let mut config = BTreeMap::new();
+        // The certificate is only issued for the bootstrap address, the Pod addresses might not
+        // be accepted by the issuer of the SecretClass, e.g. a public CA
+        if let (Some(tls_bootstrap_secret_class), Some(kerberos_listener)) = (
+            self.tls_bootstrap_secret_class(),
+            self.kerberos_client_listener(),
+        ) {
+            pod_builder
+                .add_volume(self.create_tls_keystore_volume(
+                    Self::STACKABLE_TLS_KAFKA_BOOTSTRAP_VOLUME_NAME,
+                    tls_bootstrap_secret_class,
+                    self.store_format,
+                    requested_secret_lifetime,
+                    false,
+                    &[kerberos_listener.bootstrap_listener_volume_name()],
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_KAFKA_BOOTSTRAP_VOLUME_NAME,
+                    Self::STACKABLE_TLS_KAFKA_BOOTSTRAP_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        if self.get_tls_secret_class().is_some() || self.tls_internal_secret_class().is_some() {
+            self.add_store_password_volume_and_volume_mount(pod_builder, cb_kafka)?;
+        }
+
+        // The CA of the identity provider is required to retrieve the keys from its JWKS endpoint,
+        // and by the kcat prober to obtain access tokens
+        let oidc_prober_credentials_secret = self
+            .has_oidc_enabled()
+            .then(|| {
+                self.resolved_authentication_classes
+                    .oidc_options()
+                    .prober_credentials_secret
+                    .as_deref()
+            })
+            .flatten();
+        if let Some(ca_cert_secret_class) = self
+            .oidc_provider()
+            .and_then(|oidc| oidc.tls.tls_ca_cert_secret_class())
+        {
+            pod_builder
+                .add_volume(Self::create_kcat_tls_volume(
+                    Self::STACKABLE_TLS_OIDC_VOLUME_NAME,
+                    &ca_cert_secret_class,
+                    requested_secret_lifetime,
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_OIDC_VOLUME_NAME,
+                    Self::STACKABLE_TLS_OIDC_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+            if oidc_prober_credentials_secret.is_some() {
+                cb_kcat_prober
+                    .add_volume_mount(
+                        Self::STACKABLE_TLS_OIDC_VOLUME_NAME,
+                        Self::STACKABLE_TLS_OIDC_DIR,
+                    )
+                    .context(AddVolumeMountSnafu)?;
+            }
+        }
+        if let Some(prober_credentials_secret) = oidc_prober_credentials_secret {
+            pod_builder
+                .add_volume(
+                    VolumeBuilder::new(Self::STACKABLE_OIDC_PROBER_VOLUME_NAME)
+                        .with_secret(prober_credentials_secret, false)
+                        .build(),
+                )
+                .context(AddVolumeSnafu)?;
+            cb_kcat_prober
+                .add_volume_mount(
+                    Self::STACKABLE_OIDC_PROBER_VOLUME_NAME,
+                    Self::STACKABLE_OIDC_PROBER_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        // The CA of the LDAP server is required by the callback handler to connect via LDAPS
+        if let Some(ca_cert_secret_class) = self
+            .ldap_provider()
+            .and_then(|ldap| ldap.tls.tls_ca_cert_secret_class())
+        {
+            pod_builder
+                .add_volume(Self::create_kcat_tls_volume(
+                    Self::STACKABLE_TLS_LDAP_VOLUME_NAME,
+                    &ca_cert_secret_class,
+                    requested_secret_lifetime,
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_LDAP_VOLUME_NAME,
+                    Self::STACKABLE_TLS_LDAP_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        // The OPA authorizer only reads PKCS12 truststores
+        if let Some(ca_cert_secret_class) = self.opa_ca_cert_secret_class() {
+            pod_builder
+                .add_volume(self.create_tls_keystore_volume(
+                    Self::STACKABLE_TLS_OPA_VOLUME_NAME,
+                    ca_cert_secret_class,
+                    KafkaTlsStoreFormat::Pkcs12,
+                    requested_secret_lifetime,
+                    true,
+                    &[],
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_OPA_VOLUME_NAME,
+                    Self::STACKABLE_TLS_OPA_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+            self.add_store_password_volume_and_volume_mount(pod_builder, cb_kafka)?;
+        }
+
+        // The brokers read the SCRAM credentials from the metadata, only the kcat prober needs
+        // a password to log in. It is generated by the operator, so that the user credentials
+        // Secret is not mounted.
+        if let Some(scram_prober_secret_name) = self
+            .scram_prober_secret_name
+            .as_deref()
+            .filter(|_| self.has_scram_enabled())
+        {
+            pod_builder
+                .add_volume(
+                    VolumeBuilder::new(Self::STACKABLE_SCRAM_PROBER_VOLUME_NAME)
+                        .with_secret(scram_prober_secret_name, false)
+                        .build(),
+                )
+                .context(AddVolumeSnafu)?;
+            cb_kcat_prober
+                .add_volume_mount(
+                    Self::STACKABLE_SCRAM_PROBER_VOLUME_NAME,
+                    Self::STACKABLE_SCRAM_PROBER_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        self.add_internal_credentials_volume_and_volume_mount(pod_builder, cb_kafka)?;
+
+        Ok(())
+    }
+
+    /// Adds the internal TLS certificates to the pod and container builders of Pods without listener
+    /// volumes, i.e. the KRaft controllers and the admin jobs. The certificates are issued for the
+    /// Pod FQDN, which is used in the quorum voters.
+    pub fn add_internal_volume_and_volume_mounts(
+        &self,
+        pod_builder: &mut PodBuilder,
+        cb_kafka: &mut ContainerBuilder,
+        requested_secret_lifetime: &Duration,
+    ) -> Result<(), Error> {
+        if let Some(tls_internal_secret_class) = self.tls_internal_secret_class() {
+            pod_builder
+                .add_volume(self.create_tls_keystore_volume(
+                    Self::STACKABLE_TLS_KAFKA_INTERNAL_VOLUME_NAME,
+                    tls_internal_secret_class,
+                    self.store_format,
+                    requested_secret_lifetime,
+                    true,
+                    &[],
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_KAFKA_INTERNAL_VOLUME_NAME,
+                    Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+            self.add_store_password_volume_and_volume_mount(pod_builder, cb_kafka)?;
+        }
 
-        // We set either client tls with authentication or client tls without authentication
-        // If authentication is explicitly required we do not want to have any other CAs to
-        // be trusted.
-        if self.tls_client_authentication_class().is_some() {
+        self.add_internal_credentials_volume_and_volume_mount(pod_builder, cb_kafka)?;
+
+        Ok(())
+    }
+
+    /// Adds the client certificate and the keytab the ZooKeeper client connects with to the pod and
+    /// container builders of the brokers and of the controllers during a migration.
+    pub fn add_zookeeper_volume_and_volume_mounts(
+        &self,
+        kafka: &KafkaCluster,
+        pod_builder: &mut PodBuilder,
+        cb_kafka: &mut ContainerBuilder,
+        requested_secret_lifetime: &Duration,
+    ) -> Result<(), Error> {
+        if let Some(zookeeper_tls_secret_class) = self.zookeeper_tls_secret_class() {
+            pod_builder
+                .add_volume(self.create_tls_keystore_volume(
+                    Self::STACKABLE_TLS_ZOOKEEPER_VOLUME_NAME,
+                    zookeeper_tls_secret_class,
+                    KafkaTlsStoreFormat::Pkcs12,
+                    requested_secret_lifetime,
+                    true,
+                    &[],
+                )?)
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_ZOOKEEPER_VOLUME_NAME,
+                    Self::STACKABLE_TLS_ZOOKEEPER_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+            self.add_store_password_volume_and_volume_mount(pod_builder, cb_kafka)?;
+        }
+
+        // All brokers and controllers share the principal of the service scope, as the znode ACLs
+        // only grant access to the principal which created the znode
+        if let Some(zookeeper_kerberos_secret_class) = self.zookeeper_kerberos_secret_class() {
+            pod_builder
+                .add_volume(
+                    VolumeBuilder::new(Self::STACKABLE_ZOOKEEPER_KERBEROS_VOLUME_NAME)
+                        .ephemeral(
+                            SecretOperatorVolumeSourceBuilder::new(zookeeper_kerberos_secret_class)
+                                .with_service_scope(zookeeper_kerberos_service(kafka))
+                                .with_kerberos_service_name(
+                                    KafkaRole::Broker.kerberos_service_name(),
+                                )
+                                .build()
+                                .context(SecretVolumeBuildSnafu)?,
+                        )
+                        .build(),
+                )
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_ZOOKEEPER_KERBEROS_VOLUME_NAME,
+                    STACKABLE_ZOOKEEPER_KERBEROS_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    /// Mounts the generated password of the PKCS12 stores, which is read by the `dir` config
+    /// provider.
+    fn add_store_password_volume_and_volume_mount(
+        &self,
+        pod_builder: &mut PodBuilder,
+        cb_kafka: &mut ContainerBuilder,
+    ) -> Result<(), Error> {
+        if let Some(store_password) = &self.store_password {
+            pod_builder
+                .add_volume(
+                    VolumeBuilder::new(Self::STACKABLE_TLS_STORE_PASSWORD_VOLUME_NAME)
+                        .with_secret(&store_password.secret_name, false)
+                        .build(),
+                )
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_TLS_STORE_PASSWORD_VOLUME_NAME,
+                    STACKABLE_TLS_STORE_PASSWORD_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    /// Mounts the generated password of the internal super user, which is read by the `dir` config
+    /// provider.
+    fn add_internal_credentials_volume_and_volume_mount(
+        &self,
+        pod_builder: &mut PodBuilder,
+        cb_kafka: &mut ContainerBuilder,
+    ) -> Result<(), Error> {
+        if let Some(secret_name) = &self.internal_credentials_secret_name {
+            pod_builder
+                .add_volume(
+                    VolumeBuilder::new(Self::STACKABLE_INTERNAL_CREDENTIALS_VOLUME_NAME)
+                        .with_secret(secret_name, false)
+                        .build(),
+                )
+                .context(AddVolumeSnafu)?;
+            cb_kafka
+                .add_volume_mount(
+                    Self::STACKABLE_INTERNAL_CREDENTIALS_VOLUME_NAME,
+                    Self::STACKABLE_INTERNAL_CREDENTIALS_DIR,
+                )
+                .context(AddVolumeMountSnafu)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns required Kafka configuration settings for the `server.properties` file
+    /// depending on the tls and authentication settings.
+    pub fn config_settings(&self) -> Result<BTreeMap<String, String>, Error> {
+        let mut config = BTreeMap::new();
+
+        for client_listener in self.client_listeners() {
+            config.extend(self.client_listener_config_settings(&client_listener)?);
+        }
+
+        if self
+            .kerberos_client_listener()
+            .is_some_and(|kerberos_listener| kerberos_listener.protocol.uses_tls())
+        {
+            // Bootstrap
+            let bootstrap_tls_dir = match self.tls_bootstrap_secret_class() {
+                Some(_) => Self::STACKABLE_TLS_KAFKA_BOOTSTRAP_DIR,
+                None => Self::STACKABLE_TLS_KAFKA_SERVER_DIR,
+            };
+            config.extend(
+                self.store_config_settings(Some(&KafkaListenerName::Bootstrap), bootstrap_tls_dir),
+            );
+            config.extend(Self::tls_policy_config_settings(
+                Some(&KafkaListenerName::Bootstrap),
+                self.tls_policies.bootstrap(),
+            ));
+        }
+
+        config.extend(self.inter_broker_config_settings());
+
+        config.extend(self.delegation_token_config_settings());
+
+        // The URL of OPA is passed as an override when the broker is started
+        if self.opa_ca_cert_secret_class().is_some() {
+            if self.store_password.is_some() {
+                config.extend(Self::dir_config_provider_settings());
+            }
             config.insert(
-                Self::CLIENT_AUTH_SSL_KEYSTORE_LOCATION.to_string(),
-                format!("{}/keystore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
+                Self::OPA_AUTHORIZER_TRUSTSTORE_PATH.to_string(),
+                format!("{}/truststore.p12", Self::STACKABLE_TLS_OPA_DIR),
             );
             config.insert(
-                Self::CLIENT_AUTH_SSL_KEYSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::OPA_AUTHORIZER_TRUSTSTORE_PASSWORD.to_string(),
+                self.pkcs12_store_password(),
             );
             config.insert(
-                Self::CLIENT_AUTH_SSL_KEYSTORE_TYPE.to_string(),
+                Self::OPA_AUTHORIZER_TRUSTSTORE_TYPE.to_string(),
                 "PKCS12".to_string(),
             );
+        }
+
+        // Kerberos
+        if self.has_kerberos_enabled() {
+            // Clients with a delegation token log in by SCRAM on the Kerberos listener
+            let mut mechanisms = vec!["GSSAPI"];
+            if self.has_delegation_tokens_enabled() {
+                mechanisms.extend(user::SCRAM_MECHANISMS);
+                if let Some(kerberos_listener) = self.kerberos_client_listener() {
+                    config.extend(Self::scram_config_settings(&kerberos_listener.name));
+                }
+                config.extend(Self::scram_config_settings(&KafkaListenerName::Bootstrap));
+            }
+            config.insert("sasl.enabled.mechanisms".to_string(), mechanisms.join(","));
             config.insert(
-                Self::CLIENT_AUTH_SSL_TRUSTSTORE_LOCATION.to_string(),
-                format!("{}/truststore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
+                "sasl.kerberos.service.name".to_string(),
+                KafkaRole::Broker.kerberos_service_name().to_string(),
+            );
+            if let Some(rules) = self
+                .resolved_authentication_classes
+                .kerberos_options()
+                .sasl_kerberos_principal_to_local_rules()
+            {
+                config.insert(
+                    Self::SASL_KERBEROS_PRINCIPAL_TO_LOCAL_RULES.to_string(),
+                    rules,
+                );
+            }
+            tracing::debug!("Kerberos configs added: [{:#?}]", config);
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the settings of a client listener, which depend on the authentication method of
+    /// its AuthenticationClass.
+    fn client_listener_config_settings(
+        &self,
+        client_listener: &KafkaClientListener,
+    ) -> Result<BTreeMap<String, String>, Error> {
+        let mut config = BTreeMap::new();
+        let listener_name = &client_listener.name;
+
+        if client_listener.protocol.uses_tls() {
+            config.extend(
+                self.store_config_settings(
+                    Some(listener_name),
+                    Self::STACKABLE_TLS_KAFKA_SERVER_DIR,
+                ),
             );
+            config.extend(Self::tls_policy_config_settings(
+                Some(listener_name),
+                self.tls_policies.client.as_ref(),
+            ));
+        }
+
+        match client_listener.authentication {
+            Some(KafkaClientAuthentication::Tls) => {
+                let tls_options = self.resolved_authentication_classes.tls_options();
+                if let Some(client_auth) = self.ssl_client_auth(client_listener) {
+                    config.insert(
+                        listener_name.config_key(Self::SSL_CLIENT_AUTH),
+                        client_auth.to_string(),
+                    );
+                }
+                if let Some(rules) = tls_options.ssl_principal_mapping_rules() {
+                    config.insert(
+                        listener_name.config_key(Self::SSL_PRINCIPAL_MAPPING_RULES),
+                        rules,
+                    );
+                }
+            }
+            Some(KafkaClientAuthentication::Scram) => {
+                config.insert(
+                    listener_name.config_key(Self::SASL_ENABLED_MECHANISMS),
+                    user::SCRAM_MECHANISMS.join(","),
+                );
+                config.extend(Self::scram_config_settings(listener_name));
+            }
+            Some(KafkaClientAuthentication::Oidc) => {
+                if let Some(oidc) = self.oidc_provider() {
+                    config.extend(self.oauthbearer_config_settings(listener_name, oidc)?);
+                }
+            }
+            Some(KafkaClientAuthentication::Ldap) => {
+                if let Some(ldap) = self.ldap_provider() {
+                    config.extend(Self::ldap_plain_config_settings(listener_name, ldap)?);
+                }
+            }
+            // The GSSAPI settings are shared with the BOOTSTRAP listener
+            Some(KafkaClientAuthentication::Kerberos) | None => {}
+        }
+
+        Ok(config)
+    }
+
+    /// Returns whether clients of a listener with TLS authentication must (`required`) or may
+    /// (`requested`) present a certificate. It is published in the discovery ConfigMap, so that
+    /// clients without a certificate know whether they can connect.
+    pub fn ssl_client_auth(
+        &self,
+        client_listener: &KafkaClientListener,
+    ) -> Option<KafkaTlsClientAuth> {
+        (client_listener.authentication == Some(KafkaClientAuthentication::Tls)).then(|| {
+            self.resolved_authentication_classes
+                .tls_options()
+                .client_auth
+        })
+    }
+
+    /// Returns the JAAS configurations of the SCRAM mechanisms of the listener. The credentials are
+    /// looked up in the metadata, so the login module needs no options.
+    fn scram_config_settings(listener_name: &KafkaListenerName) -> BTreeMap<String, String> {
+        user::SCRAM_MECHANISMS
+            .into_iter()
+            .map(|mechanism| {
+                (
+                    listener_name
+                        .config_key(&format!("{}.sasl.jaas.config", mechanism.to_lowercase())),
+                    "org.apache.kafka.common.security.scram.ScramLoginModule required;".to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the settings of the delegation tokens for the `server.properties` file, which are
+    /// required by the brokers as well as by the KRaft controllers. The key the tokens are signed
+    /// with is read from the mounted Secret when the configuration is loaded, so that it is
+    /// neither part of the ConfigMap nor of the logs.
+    pub fn delegation_token_config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if self.has_delegation_tokens_enabled() {
+            config.extend(Self::dir_config_provider_settings());
             config.insert(
-                Self::CLIENT_AUTH_SSL_TRUSTSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::DELEGATION_TOKEN_SECRET_KEY.to_string(),
+                format!(
+                    "${{dir:{STACKABLE_DELEGATION_TOKEN_DIR}:{}}}",
+                    delegation_token::DELEGATION_TOKEN_SECRET_KEY
+                ),
             );
+        }
+
+        config
+    }
+
+    /// Returns the settings of the ZooKeeper client for the `server.properties` file of the brokers
+    /// and of the controllers during a migration.
+    /// The ZooKeeper client only supports PKCS12 stores, so the client certificate is always
+    /// mounted in this format, even if `storeFormat` is `Pem`.
+    pub fn zookeeper_config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if self.zookeeper_tls_secret_class().is_some() {
+            let password = self.pkcs12_store_password();
+            if self.store_password.is_some() {
+                config.extend(Self::dir_config_provider_settings());
+            }
             config.insert(
-                Self::CLIENT_AUTH_SSL_TRUSTSTORE_TYPE.to_string(),
-                "PKCS12".to_string(),
+                Self::ZOOKEEPER_SSL_CLIENT_ENABLE.to_string(),
+                "true".to_string(),
             );
-            // client auth required
+            // TLS requires the Netty based client
             config.insert(
-                Self::CLIENT_AUTH_SSL_CLIENT_AUTH.to_string(),
-                "required".to_string(),
+                Self::ZOOKEEPER_CLIENT_CNXN_SOCKET.to_string(),
+                "org.apache.zookeeper.ClientCnxnSocketNetty".to_string(),
             );
-        } else if self.tls_server_secret_class().is_some() {
             config.insert(
-                Self::CLIENT_SSL_KEYSTORE_LOCATION.to_string(),
-                format!("{}/keystore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
+                Self::ZOOKEEPER_SSL_KEYSTORE_LOCATION.to_string(),
+                format!("{}/keystore.p12", Self::STACKABLE_TLS_ZOOKEEPER_DIR),
             );
             config.insert(
-                Self::CLIENT_SSL_KEYSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::ZOOKEEPER_SSL_KEYSTORE_PASSWORD.to_string(),
+                password.clone(),
             );
             config.insert(
-                Self::CLIENT_SSL_KEYSTORE_TYPE.to_string(),
+                Self::ZOOKEEPER_SSL_KEYSTORE_TYPE.to_string(),
                 "PKCS12".to_string(),
             );
             config.insert(
-                Self::CLIENT_SSL_TRUSTSTORE_LOCATION.to_string(),
-                format!("{}/truststore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
+                Self::ZOOKEEPER_SSL_TRUSTSTORE_LOCATION.to_string(),
+                format!("{}/truststore.p12", Self::STACKABLE_TLS_ZOOKEEPER_DIR),
             );
             config.insert(
-                Self::CLIENT_SSL_TRUSTSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::ZOOKEEPER_SSL_TRUSTSTORE_PASSWORD.to_string(),
+                password,
             );
             config.insert(
-                Self::CLIENT_SSL_TRUSTSTORE_TYPE.to_string(),
+                Self::ZOOKEEPER_SSL_TRUSTSTORE_TYPE.to_string(),
                 "PKCS12".to_string(),
             );
         }
 
-        if self.has_kerberos_enabled() {
-            // Bootstrap
-            config.insert(
-                Self::BOOTSTRAP_SSL_KEYSTORE_LOCATION.to_string(),
-                format!("{}/keystore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
-            );
-            config.insert(
-                Self::BOOTSTRAP_SSL_KEYSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
-            );
-            config.insert(
-                Self::BOOTSTRAP_SSL_KEYSTORE_TYPE.to_string(),
-                "PKCS12".to_string(),
-            );
-            config.insert(
-                Self::BOOTSTRAP_SSL_TRUSTSTORE_LOCATION.to_string(),
-                format!("{}/truststore.p12", Self::STACKABLE_TLS_KAFKA_SERVER_DIR),
-            );
-            config.insert(
-                Self::BOOTSTRAP_SSL_TRUSTSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
-            );
+        // The znodes created by Kafka are only writable by its principal
+        if self.zookeeper_kerberos_secret_class().is_some() {
+            config.insert(Self::ZOOKEEPER_SET_ACL.to_string(), "true".to_string());
+        }
+
+        config
+    }
+
+    /// Returns the JVM arguments which let the ZooKeeper client log in with Kerberos, using the
+    /// `Client` section of the JAAS configuration in the rolegroup ConfigMap. The `krb5.conf` is
+    /// already set for brokers which authenticate clients with Kerberos, which is the same.
+    pub fn zookeeper_jvm_args(&self, kafka_role: &KafkaRole) -> Vec<String> {
+        let mut jvm_args = Vec::new();
+
+        if self.zookeeper_kerberos_secret_class().is_some() {
+            jvm_args.push(format!(
+                "-Djava.security.auth.login.config={STACKABLE_CONFIG_DIR}/{ZOOKEEPER_JAAS_CONFIG_FILE}"
+            ));
+            if kafka_role == &KafkaRole::Controller || !self.has_kerberos_enabled() {
+                jvm_args.push(format!(
+                    "-Djava.security.krb5.conf={STACKABLE_ZOOKEEPER_KERBEROS_KRB5_PATH}"
+                ));
+            }
+        }
+
+        jvm_args
+    }
+
+    /// Enables the `dir` config provider, which reads the value of `${dir:<directory>:<file>}`
+    /// from the file in the directory.
+    pub fn dir_config_provider_settings() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (Self::CONFIG_PROVIDERS.to_string(), "dir".to_string()),
+            (
+                Self::CONFIG_PROVIDERS_DIR_CLASS.to_string(),
+                "org.apache.kafka.common.config.provider.DirectoryConfigProvider".to_string(),
+            ),
+        ])
+    }
+
+    /// Returns the settings to validate the access tokens of the OIDC provider, which are signed
+    /// with the keys of its JWKS endpoint.
+    fn oauthbearer_config_settings(
+        &self,
+        listener_name: &KafkaListenerName,
+        oidc: &oidc::AuthenticationProvider,
+    ) -> Result<BTreeMap<String, String>, Error> {
+        let mut config = BTreeMap::new();
+        let oidc_options = self.resolved_authentication_classes.oidc_options();
+        let issuer = oidc.endpoint_url().context(InvalidOidcEndpointSnafu)?;
+        let jwks_endpoint_path = oidc_options
+            .jwks_endpoint_path
+            .as_deref()
+            .unwrap_or(DEFAULT_JWKS_ENDPOINT_PATH);
+
+        config.insert(
+            listener_name.config_key(Self::SASL_ENABLED_MECHANISMS),
+            "OAUTHBEARER".to_string(),
+        );
+        // The JWKS endpoint is called with the SSL settings of the JAAS configuration
+        let mut jaas_config =
+            "org.apache.kafka.common.security.oauthbearer.OAuthBearerLoginModule required"
+                .to_string();
+        if oidc.tls.tls_ca_cert_secret_class().is_some() {
+            jaas_config.push_str(&format!(
+                " ssl.truststore.location=\"{}/ca.crt\" ssl.truststore.type=\"PEM\"",
+                Self::STACKABLE_TLS_OIDC_DIR
+            ));
+        }
+        jaas_config.push(';');
+        config.insert(
+            listener_name.config_key(Self::OAUTHBEARER_JAAS_CONFIG),
+            jaas_config,
+        );
+        config.insert(
+            listener_name.config_key(Self::OAUTHBEARER_SERVER_CALLBACK_HANDLER_CLASS),
+            "org.apache.kafka.common.security.oauthbearer.OAuthBearerValidatorCallbackHandler"
+                .to_string(),
+        );
+        config.insert(
+            Self::OAUTHBEARER_JWKS_ENDPOINT_URL.to_string(),
+            format!(
+                "{}/{}",
+                issuer.as_str().trim_end_matches('/'),
+                jwks_endpoint_path.trim_start_matches('/')
+            ),
+        );
+        config.insert(
+            Self::OAUTHBEARER_EXPECTED_ISSUER.to_string(),
+            issuer.to_string(),
+        );
+        if let Some(expected_audience) = &oidc_options.expected_audience {
             config.insert(
-                Self::BOOTSTRAP_SSL_TRUSTSTORE_TYPE.to_string(),
-                "PKCS12".to_string(),
+                Self::OAUTHBEARER_EXPECTED_AUDIENCE.to_string(),
+                expected_audience.clone(),
             );
         }
+        config.insert(
+            Self::OAUTHBEARER_SUB_CLAIM_NAME.to_string(),
+            oidc.principal_claim.clone(),
+        );
+
+        Ok(config)
+    }
+
+    /// Returns the settings to verify PLAIN logins against the LDAP server. The callback handler
+    /// takes its options from the JAAS configuration of the listener.
+    fn ldap_plain_config_settings(
+        listener_name: &KafkaListenerName,
+        ldap: &ldap::AuthenticationProvider,
+    ) -> Result<BTreeMap<String, String>, Error> {
+        let mut config = BTreeMap::new();
+        let endpoint_url = ldap.endpoint_url().context(InvalidLdapEndpointSnafu)?;
+
+        let mut jaas_options = vec![
+            ("ldap.url", endpoint_url.to_string()),
+            ("ldap.search.base", ldap.search_base.clone()),
+            ("ldap.search.filter", ldap.search_filter.clone()),
+            ("ldap.uid.attribute", ldap.ldap_field_names.uid.clone()),
+        ];
+        if ldap.bind_credentials.is_some() {
+            jaas_options.push((
+                "ldap.bind.credentials.dir",
+                STACKABLE_LDAP_BIND_CREDENTIALS_DIR.to_string(),
+            ));
+        }
+        if ldap.tls.tls_ca_cert_secret_class().is_some() {
+            jaas_options.push((
+                "ldap.truststore.location",
+                format!("{}/ca.crt", Self::STACKABLE_TLS_LDAP_DIR),
+            ));
+        }
+
+        let mut jaas_config =
+            "org.apache.kafka.common.security.plain.PlainLoginModule required".to_string();
+        for (key, value) in jaas_options {
+            // values are quoted, so quotes and backslashes (e.g. in the search filter) are escaped
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            jaas_config.push_str(&format!(" {key}=\"{value}\""));
+        }
+        jaas_config.push(';');
+
+        config.insert(
+            listener_name.config_key(Self::SASL_ENABLED_MECHANISMS),
+            "PLAIN".to_string(),
+        );
+        config.insert(
+            listener_name.config_key(Self::PLAIN_JAAS_CONFIG),
+            jaas_config,
+        );
+        config.insert(
+            listener_name.config_key(Self::PLAIN_SERVER_CALLBACK_HANDLER_CLASS),
+            Self::LDAP_PLAIN_SERVER_CALLBACK_HANDLER.to_string(),
+        );
+
+        Ok(config)
+    }
+
+    /// Returns the settings of the INTERNAL listener for the `server.properties` file.
+    /// Besides the brokers, these are required by the KRaft controllers during a migration from
+    /// ZooKeeper, as they send requests to the (ZooKeeper based) brokers.
+    pub fn inter_broker_config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
 
         // Internal TLS
         if self.tls_internal_secret_class().is_some() {
+            config.extend(self.store_config_settings(
+                Some(&KafkaListenerName::Internal),
+                Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR,
+            ));
             config.insert(
-                Self::INTER_SSL_KEYSTORE_LOCATION.to_string(),
-                format!("{}/keystore.p12", Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR),
-            );
-            config.insert(
-                Self::INTER_SSL_KEYSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::INTER_SSL_CLIENT_AUTH.to_string(),
+                "required".to_string(),
             );
+            config.extend(Self::tls_policy_config_settings(
+                Some(&KafkaListenerName::Internal),
+                self.tls_policies.internal.as_ref(),
+            ));
+        }
+
+        // Kerberos on top of the internal TLS
+        if self.has_kerberos_inter_broker_enabled() {
             config.insert(
-                Self::INTER_SSL_KEYSTORE_TYPE.to_string(),
-                "PKCS12".to_string(),
+                KafkaListenerName::Internal.config_key(Self::SASL_ENABLED_MECHANISMS),
+                "GSSAPI".to_string(),
             );
             config.insert(
-                Self::INTER_SSL_TRUSTSTORE_LOCATION.to_string(),
-                format!("{}/truststore.p12", Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR),
+                Self::SASL_MECHANISM_INTER_BROKER_PROTOCOL.to_string(),
+                "GSSAPI".to_string(),
             );
+        } else if self.has_internal_credentials_enabled() {
+            // The internal super user on top of the internal TLS
+            config.extend(Self::internal_credentials_config_settings(
+                &KafkaListenerName::Internal,
+            ));
             config.insert(
-                Self::INTER_SSL_TRUSTSTORE_PASSWORD.to_string(),
-                Self::SSL_STORE_PASSWORD.to_string(),
+                Self::SASL_MECHANISM_INTER_BROKER_PROTOCOL.to_string(),
+                "PLAIN".to_string(),
             );
+        }
+
+        // common
+        config.insert(
+            Self::INTER_BROKER_LISTENER_NAME.to_string(),
+            KafkaListenerName::Internal.to_string(),
+        );
+
+        config
+    }
+
+    /// Returns the settings of the KRaft CONTROLLER listener for the `server.properties` file.
+    /// These are required by the controllers as well as by the brokers connecting to them.
+    pub fn controller_config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if self.tls_internal_secret_class().is_some() {
+            config.extend(self.store_config_settings(
+                Some(&KafkaListenerName::Controller),
+                Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR,
+            ));
             config.insert(
-                Self::INTER_SSL_TRUSTSTORE_TYPE.to_string(),
-                "PKCS12".to_string(),
+                Self::CONTROLLER_SSL_CLIENT_AUTH.to_string(),
+                "required".to_string(),
             );
+            config.extend(Self::tls_policy_config_settings(
+                Some(&KafkaListenerName::Controller),
+                self.tls_policies.internal.as_ref(),
+            ));
+        }
+
+        if self.has_internal_credentials_enabled() {
+            config.extend(Self::internal_credentials_config_settings(
+                &KafkaListenerName::Controller,
+            ));
             config.insert(
-                Self::INTER_SSL_CLIENT_AUTH.to_string(),
-                "required".to_string(),
+                Self::SASL_MECHANISM_CONTROLLER_PROTOCOL.to_string(),
+                "PLAIN".to_string(),
             );
         }
 
-        // Kerberos
-        if self.has_kerberos_enabled() {
-            config.insert("sasl.enabled.mechanisms".to_string(), "GSSAPI".to_string());
-            config.insert(
-                "sasl.kerberos.service.name".to_string(),
-                KafkaRole::Broker.kerberos_service_name().to_string(),
-            );
-            config.insert(
-                "sasl.mechanism.inter.broker.protocol".to_string(),
-                "GSSAPI".to_string(),
-            );
-            tracing::debug!("Kerberos configs added: [{:#?}]", config);
+        config
+    }
+
+    /// Returns the settings of the internal super user on the given internal listener. The
+    /// brokers and controllers accept it with the PLAIN mechanism and log in with it themselves.
+    /// The password is read from the generated Secret by the `dir` config provider.
+    fn internal_credentials_config_settings(
+        listener_name: &KafkaListenerName,
+    ) -> BTreeMap<String, String> {
+        let password = Self::internal_credentials_password();
+        let mut config = Self::dir_config_provider_settings();
+        config.insert(
+            listener_name.config_key(Self::SASL_ENABLED_MECHANISMS),
+            "PLAIN".to_string(),
+        );
+        config.insert(
+            listener_name.config_key(Self::PLAIN_JAAS_CONFIG),
+            format!(
+                "org.apache.kafka.common.security.plain.PlainLoginModule required username=\"{INTERNAL_USER_NAME}\" password=\"{password}\" user_{INTERNAL_USER_NAME}=\"{password}\";"
+            ),
+        );
+        config
+    }
+
+    /// The password of the internal super user, which is read from the mounted Secret by the `dir`
+    /// config provider.
+    fn internal_credentials_password() -> String {
+        format!(
+            "${{dir:{}:{}}}",
+            Self::STACKABLE_INTERNAL_CREDENTIALS_DIR,
+            user::PASSWORD_SECRET_KEY
+        )
+    }
+
+    /// Returns the `client.properties` settings for the Kafka admin tools, which connect to the
+    /// INTERNAL listener of the brokers.
+    pub fn admin_client_config_settings(&self) -> BTreeMap<String, String> {
+        let mut config = BTreeMap::new();
+
+        if self.tls_internal_secret_class().is_some() {
+            if self.has_kerberos_inter_broker_enabled() {
+                // The JAAS configuration contains the Kerberos realm, which is only known at
+                // runtime, see `admin::admin_tool_commands`
+                config.insert(
+                    Self::SECURITY_PROTOCOL.to_string(),
+                    KafkaListenerProtocol::SaslSsl.to_string(),
+                );
+                config.insert("sasl.mechanism".to_string(), "GSSAPI".to_string());
+                config.insert(
+                    "sasl.kerberos.service.name".to_string(),
+                    KafkaRole::Broker.kerberos_service_name().to_string(),
+                );
+            } else if self.has_internal_credentials_enabled() {
+                config.insert(
+                    Self::SECURITY_PROTOCOL.to_string(),
+                    KafkaListenerProtocol::SaslSsl.to_string(),
+                );
+                config.insert("sasl.mechanism".to_string(), "PLAIN".to_string());
+                config.insert(
+                    "sasl.jaas.config".to_string(),
+                    format!(
+                        "org.apache.kafka.common.security.plain.PlainLoginModule required username=\"{INTERNAL_USER_NAME}\" password=\"{}\";",
+                        Self::internal_credentials_password()
+                    ),
+                );
+                config.extend(Self::dir_config_provider_settings());
+            } else {
+                config.insert(Self::SECURITY_PROTOCOL.to_string(), "SSL".to_string());
+            }
+            config.extend(self.store_config_settings(None, Self::STACKABLE_TLS_KAFKA_INTERNAL_DIR));
+            config.extend(Self::tls_policy_config_settings(
+                None,
+                self.tls_policies.internal.as_ref(),
+            ));
+        } else {
+            config.insert(Self::SECURITY_PROTOCOL.to_string(), "PLAINTEXT".to_string());
+        }
+
+        config
+    }
+
+    /// Returns the keystore and truststore settings of the SecretClass mounted at `tls_dir`,
+    /// prefixed with the listener name if given. The password of PKCS12 stores and the PEM files
+    /// are read by the `dir` config provider, so they are not part of the ConfigMap.
+    fn store_config_settings(
+        &self,
+        listener_name: Option<&KafkaListenerName>,
+        tls_dir: &str,
+    ) -> BTreeMap<String, String> {
+        let key = |key: &str| match listener_name {
+            Some(listener_name) => listener_name.config_key(key),
+            None => key.to_string(),
+        };
+        let mut config = BTreeMap::new();
+
+        match self.store_format {
+            KafkaTlsStoreFormat::Pkcs12 => {
+                let password = self.pkcs12_store_password();
+                config.insert(
+                    key(Self::SSL_KEYSTORE_LOCATION),
+                    format!("{tls_dir}/keystore.p12"),
+                );
+                config.insert(key(Self::SSL_KEYSTORE_PASSWORD), password.clone());
+                config.insert(key(Self::SSL_KEYSTORE_TYPE), "PKCS12".to_string());
+                config.insert(
+                    key(Self::SSL_TRUSTSTORE_LOCATION),
+                    format!("{tls_dir}/truststore.p12"),
+                );
+                config.insert(key(Self::SSL_TRUSTSTORE_PASSWORD), password);
+                config.insert(key(Self::SSL_TRUSTSTORE_TYPE), "PKCS12".to_string());
+            }
+            // Kafka reads a PEM keystore from a single file with the key and the certificate
+            // chain, so the separate files of the SecretClass are inlined instead
+            KafkaTlsStoreFormat::Pem => {
+                config.insert(key(Self::SSL_KEYSTORE_TYPE), "PEM".to_string());
+                config.insert(
+                    key(Self::SSL_KEYSTORE_KEY),
+                    format!("${{dir:{tls_dir}:tls.key}}"),
+                );
+                config.insert(
+                    key(Self::SSL_KEYSTORE_CERTIFICATE_CHAIN),
+                    format!("${{dir:{tls_dir}:tls.crt}}"),
+                );
+                config.insert(key(Self::SSL_TRUSTSTORE_TYPE), "PEM".to_string());
+                config.insert(
+                    key(Self::SSL_TRUSTSTORE_CERTIFICATES),
+                    format!("${{dir:{tls_dir}:ca.crt}}"),
+                );
+            }
+        }
+
+        if self.store_password.is_some() || self.store_format == KafkaTlsStoreFormat::Pem {
+            config.extend(Self::dir_config_provider_settings());
         }
 
-        // common
-        config.insert(
-            Self::INTER_BROKER_LISTENER_NAME.to_string(),
-            listener::KafkaListenerName::Internal.to_string(),
-        );
+        config
+    }
+
+    /// The password of the PKCS12 stores, which is read from the mounted Secret by the `dir` config
+    /// provider. Stores without a generated password are not protected.
+    fn pkcs12_store_password(&self) -> String {
+        match self.store_password {
+            Some(_) => format!(
+                "${{dir:{STACKABLE_TLS_STORE_PASSWORD_DIR}:{TLS_STORE_PASSWORD_SECRET_KEY}}}"
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Returns the accepted protocols and cipher suites of the TLS policy, prefixed with the
+    /// listener name if given. Without a policy, the defaults of Kafka and the JVM apply.
+    fn tls_policy_config_settings(
+        listener_name: Option<&KafkaListenerName>,
+        policy: Option<&KafkaTlsPolicy>,
+    ) -> BTreeMap<String, String> {
+        let key = |key: &str| match listener_name {
+            Some(listener_name) => listener_name.config_key(key),
+            None => key.to_string(),
+        };
+        let mut config = BTreeMap::new();
+
+        if let Some(policy) = policy {
+            if !policy.protocols.is_empty() {
+                config.insert(
+                    key(Self::SSL_ENABLED_PROTOCOLS),
+                    policy
+                        .protocols
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+            }
+            if !policy.cipher_suites.is_empty() {
+                config.insert(
+                    key(Self::SSL_CIPHER_SUITES),
+                    policy
+                        .cipher_suites
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+            }
+        }
 
         config
     }
@@ -618,17 +2406,37 @@ impl KafkaTlsSecurity {
 
     /// Creates ephemeral volumes to mount the `SecretClass` into the Pods as keystores
     fn create_tls_keystore_volume(
+        &self,
         volume_name: &str,
         secret_class_name: &str,
+        store_format: KafkaTlsStoreFormat,
         requested_secret_lifetime: &Duration,
+        pod_scope: bool,
+        listener_volume_scopes: &[String],
     ) -> Result<Volume, Error> {
+        let mut secret_volume_source_builder =
+            SecretOperatorVolumeSourceBuilder::new(secret_class_name);
+        if pod_scope {
+            secret_volume_source_builder.with_pod_scope();
+        }
+        for listener_volume in listener_volume_scopes {
+            secret_volume_source_builder.with_listener_volume_scope(listener_volume);
+        }
+        match store_format {
+            KafkaTlsStoreFormat::Pkcs12 => {
+                secret_volume_source_builder.with_format(SecretFormat::TlsPkcs12);
+                if let Some(store_password) = &self.store_password {
+                    secret_volume_source_builder.with_tls_pkcs12_password(&store_password.password);
+                }
+            }
+            KafkaTlsStoreFormat::Pem => {
+                secret_volume_source_builder.with_format(SecretFormat::TlsPem);
+            }
+        }
+
         Ok(VolumeBuilder::new(volume_name)
             .ephemeral(
-                SecretOperatorVolumeSourceBuilder::new(secret_class_name)
-                    .with_pod_scope()
-                    .with_listener_volume_scope(LISTENER_BROKER_VOLUME_NAME)
-                    .with_listener_volume_scope(LISTENER_BOOTSTRAP_VOLUME_NAME)
-                    .with_format(SecretFormat::TlsPkcs12)
+                secret_volume_source_builder
                     .with_auto_tls_cert_lifetime(*requested_secret_lifetime)
                     .build()
                     .context(SecretVolumeBuildSnafu)?,
@@ -636,8 +2444,33 @@ impl KafkaTlsSecurity {
             .build())
     }
 
-    fn kcat_client_auth_ssl(cert_directory: &str) -> Vec<String> {
-        vec![
+    /// Restricts the TLSv1.2 cipher suites of kcat to the policy of the client listeners.
+    /// librdkafka can neither restrict the protocols nor the TLSv1.3 cipher suites, it negotiates
+    /// them with the broker, which enforces the policy.
+    fn kcat_tls_policy_settings(&self) -> Vec<String> {
+        let cipher_suites = self
+            .tls_policies
+            .client
+            .iter()
+            .filter(|policy| {
+                policy.protocols.is_empty() || policy.protocols.contains(&KafkaTlsProtocol::Tls12)
+            })
+            .flat_map(|policy| &policy.cipher_suites)
+            .filter_map(|cipher_suite| cipher_suite.openssl_name())
+            .collect::<Vec<_>>();
+
+        if cipher_suites.is_empty() {
+            vec![]
+        } else {
+            vec![
+                "-X".to_string(),
+                format!("ssl.cipher.suites={}", cipher_suites.join(":")),
+            ]
+        }
+    }
+
+    fn kcat_client_auth_ssl(&self, cert_directory: &str) -> Vec<String> {
+        let mut args = vec![
             "-X".to_string(),
             "security.protocol=SSL".to_string(),
             "-X".to_string(),
@@ -646,28 +2479,127 @@ impl KafkaTlsSecurity {
             format!("ssl.certificate.location={cert_directory}/tls.crt"),
             "-X".to_string(),
             format!("ssl.ca.location={cert_directory}/ca.crt"),
-        ]
+        ];
+        args.extend(self.kcat_tls_policy_settings());
+        args
     }
 
-    fn kcat_client_ssl(cert_directory: &str) -> Vec<String> {
-        vec![
+    fn kcat_client_ssl(&self, cert_directory: &str) -> Vec<String> {
+        let mut args = vec![
             "-X".to_string(),
             "security.protocol=SSL".to_string(),
             "-X".to_string(),
             format!("ssl.ca.location={cert_directory}/ca.crt"),
-        ]
+        ];
+        args.extend(self.kcat_tls_policy_settings());
+        args
     }
 
-    fn kcat_client_sasl_ssl(
-        cert_directory: &str,
-        service_name: &str,
-        pod_fqdn: &String,
-    ) -> Vec<String> {
-        vec![
+    /// Logs in as the prober user, whose password is read from the mounted generated Secret.
+    fn kcat_client_sasl_scram(&self, cert_directory: &str) -> Vec<String> {
+        let mut args = vec![
+            "-X".to_string(),
+            "security.protocol=SASL_SSL".to_string(),
+            "-X".to_string(),
+            format!("ssl.ca.location={cert_directory}/ca.crt"),
+            "-X".to_string(),
+            format!("sasl.mechanism={}", user::SCRAM_MECHANISM),
+            "-X".to_string(),
+            format!("sasl.username={}", user::SCRAM_PROBER_USER),
+            "-X".to_string(),
+            format!(
+                "sasl.password=\"$(cat {}/{})\"",
+                Self::STACKABLE_SCRAM_PROBER_DIR,
+                user::PASSWORD_SECRET_KEY
+            ),
+        ];
+        args.extend(self.kcat_tls_policy_settings());
+        args
+    }
+
+    /// Obtains an access token with the client credentials of the mounted prober Secret and logs in
+    /// with OAUTHBEARER. Returns `None` if no prober credentials are configured.
+    fn kcat_client_sasl_oauthbearer(&self, cert_directory: &str) -> Option<Vec<String>> {
+        let oidc = self.oidc_provider()?;
+        let oidc_options = self.resolved_authentication_classes.oidc_options();
+        oidc_options.prober_credentials_secret.as_ref()?;
+        // The endpoint has already been validated for the broker configuration
+        let issuer = oidc.endpoint_url().ok()?;
+        let token_endpoint_path = oidc_options
+            .token_endpoint_path
+            .as_deref()
+            .unwrap_or(DEFAULT_TOKEN_ENDPOINT_PATH);
+
+        let mut args = vec![
             "-X".to_string(),
             "security.protocol=SASL_SSL".to_string(),
             "-X".to_string(),
             format!("ssl.ca.location={cert_directory}/ca.crt"),
+            "-X".to_string(),
+            "sasl.mechanism=OAUTHBEARER".to_string(),
+            "-X".to_string(),
+            "sasl.oauthbearer.method=oidc".to_string(),
+            "-X".to_string(),
+            format!(
+                "sasl.oauthbearer.token.endpoint.url={}/{}",
+                issuer.as_str().trim_end_matches('/'),
+                token_endpoint_path.trim_start_matches('/')
+            ),
+            "-X".to_string(),
+            format!(
+                "sasl.oauthbearer.client.id=\"$(cat {}/clientId)\"",
+                Self::STACKABLE_OIDC_PROBER_DIR
+            ),
+            "-X".to_string(),
+            format!(
+                "sasl.oauthbearer.client.secret=\"$(cat {}/clientSecret)\"",
+                Self::STACKABLE_OIDC_PROBER_DIR
+            ),
+        ];
+        if !oidc.scopes.is_empty() {
+            args.push("-X".to_string());
+            args.push(format!(
+                "sasl.oauthbearer.scope=\"{}\"",
+                oidc.scopes.join(" ")
+            ));
+        }
+        // Otherwise, the certificate of the identity provider is verified with the system CAs
+        if oidc.tls.tls_ca_cert_secret_class().is_some() {
+            args.push("-X".to_string());
+            args.push(format!(
+                "https.ca.location={}/ca.crt",
+                Self::STACKABLE_TLS_OIDC_DIR
+            ));
+        }
+        args.extend(self.kcat_tls_policy_settings());
+        Some(args)
+    }
+
+    /// The kcat settings for Kerberos, with `SASL_SSL` if a certificate directory is given and
+    /// `SASL_PLAINTEXT` otherwise.
+    fn kcat_client_sasl_kerberos(
+        &self,
+        cert_directory: Option<&str>,
+        service_name: &str,
+        pod_fqdn: &String,
+    ) -> Vec<String> {
+        let mut args = match cert_directory {
+            Some(cert_directory) => {
+                let mut args = vec![
+                    "-X".to_string(),
+                    "security.protocol=SASL_SSL".to_string(),
+                    "-X".to_string(),
+                    format!("ssl.ca.location={cert_directory}/ca.crt"),
+                ];
+                args.extend(self.kcat_tls_policy_settings());
+                args
+            }
+            None => vec![
+                "-X".to_string(),
+                "security.protocol=SASL_PLAINTEXT".to_string(),
+            ],
+        };
+        args.extend([
             "-X".to_string(),
             "sasl.kerberos.keytab=/stackable/kerberos/keytab".to_string(),
             "-X".to_string(),
@@ -676,6 +2608,1193 @@ impl KafkaTlsSecurity {
             format!("sasl.kerberos.service.name={service_name}"),
             "-X".to_string(),
             format!("sasl.kerberos.principal={service_name}/{pod_fqdn}@$KERBEROS_REALM"),
-        ]
+        ]);
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        authentication::{
+            KafkaAuthenticationListener, KafkaKerberosOptions, KafkaKerberosPrincipalSubstitution,
+            KafkaKerberosPrincipalToLocalRule, KafkaKerberosTrustedRealm, KafkaOidcOptions,
+            KafkaPrincipalMappingRule, KafkaPrincipalNameCase, KafkaTlsAuthenticationOptions,
+        },
+        listener::get_kafka_listener_config,
+        tls::KafkaTlsCipherSuite,
+    };
+    use rstest::rstest;
+    use stackable_operator::{
+        commons::networking::DomainName, utils::cluster_info::KubernetesClusterInfo,
+    };
+
+    fn oidc_authentication_class(tls: &str) -> AuthenticationClass {
+        let input = format!(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: keycloak
+        spec:
+          provider:
+            oidc:
+              hostname: keycloak.default.svc.cluster.local
+              port: 8443
+              rootPath: /realms/kafka
+              principalClaim: preferred_username
+              scopes:
+                - openid
+              {tls}
+        "#
+        );
+        serde_yaml::from_str(&input).expect("illegal test input")
+    }
+
+    #[test]
+    fn test_oauthbearer_config_settings() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![oidc_authentication_class(
+                "tls: {verification: {server: {caCert: {secretClass: keycloak-tls}}}}",
+            )])
+            .with_oidc_options(KafkaOidcOptions {
+                expected_audience: Some("kafka".to_string()),
+                ..KafkaOidcOptions::default()
+            }),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+
+        assert_eq!(
+            config.get(KafkaTlsSecurity::OAUTHBEARER_JWKS_ENDPOINT_URL),
+            Some(
+                &"https://keycloak.default.svc.cluster.local:8443/realms/kafka/protocol/openid-connect/certs"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            config.get(KafkaTlsSecurity::OAUTHBEARER_EXPECTED_ISSUER),
+            Some(&"https://keycloak.default.svc.cluster.local:8443/realms/kafka".to_string())
+        );
+        assert_eq!(
+            config.get(KafkaTlsSecurity::OAUTHBEARER_EXPECTED_AUDIENCE),
+            Some(&"kafka".to_string())
+        );
+        assert_eq!(
+            config.get(KafkaTlsSecurity::OAUTHBEARER_SUB_CLAIM_NAME),
+            Some(&"preferred_username".to_string())
+        );
+        assert!(config
+            [&KafkaListenerName::Client.config_key(KafkaTlsSecurity::OAUTHBEARER_JAAS_CONFIG)]
+            .contains("ssl.truststore.location=\"/stackable/tls-oidc/ca.crt\""));
+    }
+
+    #[test]
+    fn test_oauthbearer_prober() {
+        let oidc_authentication_class = oidc_authentication_class(
+            "tls: {verification: {server: {caCert: {secretClass: keycloak-tls}}}}",
+        );
+
+        // Without credentials, the prober cannot log in
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![oidc_authentication_class.clone()]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let commands = kafka_security.kcat_prober_container_commands(&"localhost".to_string());
+        assert_eq!(
+            commands.last(),
+            Some(&"exec 3<>/dev/tcp/localhost/9093".to_string())
+        );
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![oidc_authentication_class]).with_oidc_options(
+                KafkaOidcOptions {
+                    prober_credentials_secret: Some("kafka-prober-client".to_string()),
+                    ..KafkaOidcOptions::default()
+                },
+            ),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let commands = kafka_security.kcat_prober_container_commands(&"localhost".to_string());
+        let command = commands.last().unwrap();
+        assert!(command.starts_with("/stackable/kcat -b localhost:9093 "));
+        assert!(command.contains("-X sasl.mechanism=OAUTHBEARER -X sasl.oauthbearer.method=oidc"));
+        assert!(command.contains("-X sasl.oauthbearer.token.endpoint.url=https://keycloak.default.svc.cluster.local:8443/realms/kafka/protocol/openid-connect/token"));
+        assert!(command.contains(
+            "-X sasl.oauthbearer.client.secret=\"$(cat /stackable/oidc-prober/clientSecret)\""
+        ));
+        assert!(command.contains("-X sasl.oauthbearer.scope=\"openid\""));
+        assert!(command.contains("-X https.ca.location=/stackable/tls-oidc/ca.crt"));
+        assert!(command.ends_with(" -L"));
+    }
+
+    #[test]
+    fn test_oidc_requires_tls_verification() {
+        let resolved_authentication_classes =
+            ResolvedAuthenticationClasses::new(vec![oidc_authentication_class(
+                "tls: {verification: {none: {}}}",
+            )]);
+
+        assert!(resolved_authentication_classes.validate().is_err());
+    }
+
+    fn ldap_authentication_class(tls: &str) -> AuthenticationClass {
+        let input = format!(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: openldap
+        spec:
+          provider:
+            ldap:
+              hostname: openldap.default.svc.cluster.local
+              searchBase: ou=users,dc=example,dc=org
+              searchFilter: (memberOf=cn="kafka users",ou=groups,dc=example,dc=org)
+              bindCredentials:
+                secretClass: openldap-bind-credentials
+              {tls}
+        "#
+        );
+        serde_yaml::from_str(&input).expect("illegal test input")
+    }
+
+    #[test]
+    fn test_ldap_plain_config_settings() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![ldap_authentication_class(
+                "tls: {verification: {server: {caCert: {secretClass: openldap-tls}}}}",
+            )]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+
+        assert_eq!(
+            config.get(
+                &KafkaListenerName::Client.config_key(KafkaTlsSecurity::SASL_ENABLED_MECHANISMS)
+            ),
+            Some(&"PLAIN".to_string())
+        );
+        assert_eq!(
+            config.get(
+                &KafkaListenerName::Client
+                    .config_key(KafkaTlsSecurity::PLAIN_SERVER_CALLBACK_HANDLER_CLASS)
+            ),
+            Some(&KafkaTlsSecurity::LDAP_PLAIN_SERVER_CALLBACK_HANDLER.to_string())
+        );
+        let jaas_config =
+            &config[&KafkaListenerName::Client.config_key(KafkaTlsSecurity::PLAIN_JAAS_CONFIG)];
+        assert!(jaas_config
+            .starts_with("org.apache.kafka.common.security.plain.PlainLoginModule required"));
+        assert!(jaas_config.contains("ldap.url=\"ldaps://openldap.default.svc.cluster.local:636"));
+        assert!(jaas_config.contains("ldap.search.base=\"ou=users,dc=example,dc=org\""));
+        assert!(jaas_config.contains(
+            r#"ldap.search.filter="(memberOf=cn=\"kafka users\",ou=groups,dc=example,dc=org)""#
+        ));
+        assert!(jaas_config.contains("ldap.uid.attribute=\"uid\""));
+        assert!(
+            jaas_config.contains("ldap.bind.credentials.dir=\"/stackable/ldap-bind-credentials\"")
+        );
+        assert!(jaas_config.contains("ldap.truststore.location=\"/stackable/tls-ldap/ca.crt\""));
+        assert!(jaas_config.ends_with(';'));
+
+        let cluster_info = KubernetesClusterInfo {
+            cluster_domain: DomainName::try_from("cluster.local").unwrap(),
+        };
+        let kafka: KafkaCluster = serde_yaml::from_str(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+        "#,
+        )
+        .expect("illegal test input");
+        let kafka_listeners = get_kafka_listener_config(
+            &kafka,
+            &kafka_security,
+            "simple-kafka-broker-default",
+            false,
+            &cluster_info,
+        )
+        .unwrap();
+        let commands = kafka_security
+            .kafka_container_commands(&kafka_listeners, None, false, None)
+            .join("\n");
+        assert!(commands.contains(
+            "if ! grep -rqF 'tech/stackable/kafka/security/LdapPlainServerCallbackHandler.class' libs; then"
+        ));
+    }
+
+    #[test]
+    fn test_ldap_requires_tls_verification() {
+        let resolved_authentication_classes =
+            ResolvedAuthenticationClasses::new(vec![ldap_authentication_class(
+                "tls: {verification: {none: {}}}",
+            )]);
+
+        assert!(resolved_authentication_classes.validate().is_err());
+    }
+
+    #[test]
+    fn test_multiple_authentication_classes_require_dedicated_listeners() {
+        let tls = "tls: {verification: {server: {caCert: {secretClass: tls}}}}";
+        let ldap_listener = |name: &str| {
+            Some(KafkaAuthenticationListener {
+                name: name.to_string(),
+                ..KafkaAuthenticationListener::default()
+            })
+        };
+        let resolved_authentication_classes = |listeners| {
+            ResolvedAuthenticationClasses::new(vec![
+                oidc_authentication_class(tls),
+                ldap_authentication_class(tls),
+            ])
+            .with_listeners(listeners)
+        };
+
+        assert!(
+            resolved_authentication_classes(vec![None, ldap_listener("ldap")])
+                .validate()
+                .is_ok()
+        );
+        assert!(resolved_authentication_classes(vec![None, None])
+            .validate()
+            .is_err());
+        assert!(resolved_authentication_classes(vec![
+            ldap_listener("oidc"),
+            ldap_listener("ldap")
+        ])
+        .validate()
+        .is_err());
+        assert!(
+            resolved_authentication_classes(vec![None, ldap_listener("LDAP")])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            resolved_authentication_classes(vec![None, ldap_listener("ldap-users")])
+                .validate()
+                .is_err()
+        );
+        assert!(ResolvedAuthenticationClasses::new(vec![
+            ldap_authentication_class(tls),
+            ldap_authentication_class(tls),
+        ])
+        .with_listeners(vec![None, ldap_listener("ldap")])
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_kerberos_sasl_plaintext() {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class.clone()]),
+            "tls".to_string(),
+            None,
+        );
+        assert!(kafka_security.validate_authentication_methods().is_err());
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class])
+                .with_kerberos_options(KafkaKerberosOptions {
+                    sasl_plaintext: true,
+                    ..KafkaKerberosOptions::default()
+                }),
+            "tls".to_string(),
+            None,
+        );
+        assert!(kafka_security.validate_authentication_methods().is_ok());
+
+        let config = kafka_security.config_settings().unwrap();
+        assert!(!config.contains_key(
+            &KafkaListenerName::Client.config_key(KafkaTlsSecurity::SSL_KEYSTORE_LOCATION)
+        ));
+        assert!(!config.contains_key(
+            &KafkaListenerName::Bootstrap.config_key(KafkaTlsSecurity::SSL_KEYSTORE_LOCATION)
+        ));
+        // Clients with a delegation token log in by SCRAM
+        assert_eq!(
+            config.get("sasl.enabled.mechanisms"),
+            Some(&"GSSAPI,SCRAM-SHA-256,SCRAM-SHA-512".to_string())
+        );
+
+        let prober_commands = kafka_security
+            .kcat_prober_container_commands(&"simple-kafka-broker-default-0".to_string());
+        assert!(prober_commands.contains(&"security.protocol=SASL_PLAINTEXT".to_string()));
+        assert!(!prober_commands
+            .iter()
+            .any(|arg| arg.starts_with("ssl.ca.location")));
+    }
+
+    #[test]
+    fn test_kerberos_inter_broker() {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+        let kafka: KafkaCluster = serde_yaml::from_str(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            authentication:
+              - authenticationClass: kerberos
+                kerberos:
+                  interBroker: true
+            tls:
+              serverSecretClass: tls
+            zookeeperConfigMapName: xyz
+        "#,
+        )
+        .expect("illegal test input");
+        let kerberos_options = KafkaKerberosOptions {
+            inter_broker: true,
+            ..KafkaKerberosOptions::default()
+        };
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class.clone()])
+                .with_kerberos_options(kerberos_options.clone()),
+            "".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(kafka_security.validate_authentication_methods().is_err());
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class])
+                .with_kerberos_options(kerberos_options),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(kafka_security.validate_authentication_methods().is_ok());
+
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.internal.sasl.enabled.mechanisms"),
+            Some(&"GSSAPI".to_string())
+        );
+        assert_eq!(
+            config.get("sasl.mechanism.inter.broker.protocol"),
+            Some(&"GSSAPI".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.internal.ssl.client.auth"),
+            Some(&"required".to_string())
+        );
+
+        let admin_config = kafka_security.admin_client_config_settings();
+        assert_eq!(
+            admin_config.get("security.protocol"),
+            Some(&"SASL_SSL".to_string())
+        );
+        assert_eq!(
+            admin_config.get("sasl.mechanism"),
+            Some(&"GSSAPI".to_string())
+        );
+
+        let cluster_info = KubernetesClusterInfo {
+            cluster_domain: DomainName::try_from("cluster.local").unwrap(),
+        };
+        let kafka_listeners = get_kafka_listener_config(
+            &kafka,
+            &kafka_security,
+            "simple-kafka-broker-default",
+            false,
+            &cluster_info,
+        )
+        .unwrap();
+        assert!(kafka_listeners
+            .listener_security_protocol_map()
+            .contains("INTERNAL:SASL_SSL"));
+
+        let commands = kafka_security
+            .kafka_container_commands(&kafka_listeners, None, true, None)
+            .join("\n");
+        assert!(commands.contains("--override \"listener.name.internal.gssapi.sasl.jaas.config=com.sun.security.auth.module.Krb5LoginModule required useKeyTab=true storeKey=true keyTab=\\\"/stackable/kerberos/keytab\\\" principal=\\\"kafka/$POD_NAME.simple-kafka-broker-default.default.svc.cluster.local@$KERBEROS_REALM\\\";\""));
+    }
+
+    #[test]
+    fn test_ssl_principal_mapping_rules() {
+        let tls_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: tls
+        spec:
+          provider:
+            tls:
+              clientCertSecretClass: tls
+        "#,
+        )
+        .expect("illegal test input");
+        let tls_options = KafkaTlsAuthenticationOptions {
+            principal_mapping_rules: vec![
+                KafkaPrincipalMappingRule {
+                    pattern: "^CN=(.*?),OU=users,.*$".to_string(),
+                    replacement: "$1".to_string(),
+                    case: Some(KafkaPrincipalNameCase::Lowercase),
+                },
+                KafkaPrincipalMappingRule {
+                    pattern: "^CN=(.*?)/(.*?),.*$".to_string(),
+                    replacement: "$1/$2".to_string(),
+                    case: None,
+                },
+            ],
+            ..KafkaTlsAuthenticationOptions::default()
+        };
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![tls_authentication_class.clone()])
+                .with_tls_options(tls_options)
+                .validate()
+                .unwrap(),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.client_auth.ssl.principal.mapping.rules"),
+            Some(
+                &"RULE:^CN=(.*?),OU=users,.*$/$1/L,RULE:^CN=(.*?)\\/(.*?),.*$/$1\\/$2/,DEFAULT"
+                    .to_string()
+            )
+        );
+
+        let invalid_tls_options = KafkaTlsAuthenticationOptions {
+            principal_mapping_rules: vec![KafkaPrincipalMappingRule {
+                pattern: "^CN=(.*,.*$".to_string(),
+                replacement: "$1".to_string(),
+                case: None,
+            }],
+            ..KafkaTlsAuthenticationOptions::default()
+        };
+        assert!(
+            ResolvedAuthenticationClasses::new(vec![tls_authentication_class])
+                .with_tls_options(invalid_tls_options)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_kerberos_principal_to_local_rules_and_trusted_realms() {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+        let kerberos_options: KafkaKerberosOptions = serde_yaml::from_str(
+            r#"
+        principalToLocalRules:
+          - components: 1
+            format: $1@$0
+            filter: .*@EU\.CORP\.EXAMPLE\.COM
+            substitution:
+              pattern: "@.*"
+              replacement: ""
+            case: Lowercase
+        trustedRealms:
+          - realm: EU.CORP.EXAMPLE.COM
+            kdcs:
+              - dc1.eu.corp.example.com
+            domains:
+              - eu.corp.example.com
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class.clone()])
+                .with_kerberos_options(kerberos_options)
+                .validate()
+                .unwrap(),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("sasl.kerberos.principal.to.local.rules"),
+            Some(&"RULE:[1:$1@$0](.*@EU\\.CORP\\.EXAMPLE\\.COM)s/@.*///L,DEFAULT".to_string())
+        );
+        assert_eq!(
+            kafka_security.kerberos_trusted_realms_config(),
+            Some(
+                indoc::indoc! {"
+                    [realms]
+                      EU.CORP.EXAMPLE.COM = {
+                        kdc = dc1.eu.corp.example.com
+                      }
+
+                    [domain_realm]
+                      .eu.corp.example.com = EU.CORP.EXAMPLE.COM
+                      eu.corp.example.com = EU.CORP.EXAMPLE.COM
+                "}
+                .to_string()
+            )
+        );
+        assert_eq!(
+            kafka_security.broker_krb5_config_path(),
+            STACKABLE_TRUSTED_REALMS_KRB5_PATH
+        );
+
+        let invalid_rule = KafkaKerberosOptions {
+            principal_to_local_rules: vec![KafkaKerberosPrincipalToLocalRule {
+                components: 1,
+                format: "$1@$0".to_string(),
+                filter: None,
+                substitution: Some(KafkaKerberosPrincipalSubstitution {
+                    pattern: "@.*/".to_string(),
+                    replacement: "".to_string(),
+                    global: false,
+                }),
+                case: None,
+            }],
+            ..KafkaKerberosOptions::default()
+        };
+        assert!(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class.clone()])
+                .with_kerberos_options(invalid_rule)
+                .validate()
+                .is_err()
+        );
+
+        let invalid_realm = KafkaKerberosOptions {
+            trusted_realms: vec![KafkaKerberosTrustedRealm {
+                realm: "EU.CORP.EXAMPLE.COM }".to_string(),
+                kdcs: vec![],
+                admin_server: None,
+                domains: vec![],
+            }],
+            ..KafkaKerberosOptions::default()
+        };
+        assert!(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class])
+                .with_kerberos_options(invalid_realm)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_tls_client_auth() {
+        let tls_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: tls
+        spec:
+          provider:
+            tls:
+              clientCertSecretClass: tls
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![tls_authentication_class.clone()]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.client_auth.ssl.client.auth"),
+            Some(&"required".to_string())
+        );
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![tls_authentication_class]).with_tls_options(
+                KafkaTlsAuthenticationOptions {
+                    client_auth: KafkaTlsClientAuth::Requested,
+                    ..KafkaTlsAuthenticationOptions::default()
+                },
+            ),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.client_auth.ssl.client.auth"),
+            Some(&"requested".to_string())
+        );
+        let client_listener = &kafka_security.exposed_client_listeners()[0];
+        assert_eq!(
+            kafka_security.ssl_client_auth(client_listener),
+            Some(KafkaTlsClientAuth::Requested)
+        );
+        // The prober still presents its certificate
+        assert!(kafka_security
+            .kcat_prober_container_commands(&"localhost".to_string())
+            .join(" ")
+            .contains("ssl.certificate.location"));
+    }
+
+    #[test]
+    fn test_delegation_token_config_settings() {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(kafka_security.has_delegation_tokens_enabled());
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("delegation.token.secret.key"),
+            Some(&"${dir:/stackable/delegation-token:secret.key}".to_string())
+        );
+        assert_eq!(config.get("config.providers"), Some(&"dir".to_string()));
+        assert!(config.contains_key("listener.name.client.scram-sha-512.sasl.jaas.config"));
+        assert!(config.contains_key("listener.name.bootstrap.scram-sha-512.sasl.jaas.config"));
+        assert_eq!(
+            kafka_security.delegation_token_config_settings().len(),
+            3,
+            "the controllers need the same settings"
+        );
+
+        // Tokens are not issued to clients which authenticate with certificates only
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(!kafka_security.has_delegation_tokens_enabled());
+        assert!(!kafka_security
+            .config_settings()
+            .unwrap()
+            .contains_key("delegation.token.secret.key"));
+    }
+
+    #[test]
+    fn test_tls_policies() {
+        let tls_policies: KafkaTlsPolicies = serde_yaml::from_str(
+            r#"
+        client:
+          protocols:
+            - TLSv1.3
+        internal:
+          protocols:
+            - TLSv1.2
+            - TLSv1.3
+          cipherSuites:
+            - TLS_AES_256_GCM_SHA384
+            - TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+        "#,
+        )
+        .expect("illegal test input");
+        assert!(tls_policies.validate().is_ok());
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_tls_policies(tls_policies);
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.client.ssl.enabled.protocols"),
+            Some(&"TLSv1.3".to_string())
+        );
+        assert!(!config.contains_key("listener.name.client.ssl.cipher.suites"));
+        assert_eq!(
+            config.get("listener.name.internal.ssl.enabled.protocols"),
+            Some(&"TLSv1.2,TLSv1.3".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.internal.ssl.cipher.suites"),
+            Some(&"TLS_AES_256_GCM_SHA384,TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384".to_string())
+        );
+        assert_eq!(
+            kafka_security
+                .controller_config_settings()
+                .get("listener.name.controller.ssl.cipher.suites"),
+            config.get("listener.name.internal.ssl.cipher.suites")
+        );
+        assert_eq!(
+            kafka_security
+                .admin_client_config_settings()
+                .get("ssl.enabled.protocols"),
+            Some(&"TLSv1.2,TLSv1.3".to_string())
+        );
+        // kcat cannot restrict the protocols
+        assert!(!kafka_security
+            .kcat_prober_container_commands(&"localhost".to_string())
+            .join(" ")
+            .contains("ssl.cipher.suites"));
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_tls_policies(KafkaTlsPolicies {
+            client: Some(KafkaTlsPolicy {
+                protocols: vec![],
+                cipher_suites: vec![
+                    KafkaTlsCipherSuite::TlsAes128GcmSha256,
+                    KafkaTlsCipherSuite::TlsEcdheRsaWithAes128GcmSha256,
+                    KafkaTlsCipherSuite::TlsEcdheEcdsaWithChacha20Poly1305Sha256,
+                ],
+            }),
+            ..KafkaTlsPolicies::default()
+        });
+        assert!(kafka_security
+            .kcat_prober_container_commands(&"localhost".to_string())
+            .contains(
+                &"ssl.cipher.suites=ECDHE-RSA-AES128-GCM-SHA256:ECDHE-ECDSA-CHACHA20-POLY1305"
+                    .to_string()
+            ));
+
+        // No cipher suite can be negotiated with TLSv1.3
+        let tls_policy = KafkaTlsPolicy {
+            protocols: vec![KafkaTlsProtocol::Tls13],
+            cipher_suites: vec![KafkaTlsCipherSuite::TlsEcdheRsaWithAes256GcmSha384],
+        };
+        assert!(tls_policy.validate().is_err());
+    }
+
+    #[test]
+    fn test_store_config_settings() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_store_password("simple-kafka-tls-store-password", "WtlLd6t0rPn8");
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.client.ssl.keystore.password"),
+            Some(&"${dir:/stackable/tls-store-password:password}".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.internal.ssl.truststore.password"),
+            Some(&"${dir:/stackable/tls-store-password:password}".to_string())
+        );
+        assert_eq!(config.get("config.providers"), Some(&"dir".to_string()));
+        assert!(
+            !config.values().any(|value| value.contains("WtlLd6t0rPn8")),
+            "the password must not be part of the ConfigMap"
+        );
+        let admin_config = kafka_security.admin_client_config_settings();
+        assert_eq!(
+            admin_config.get("ssl.keystore.password"),
+            Some(&"${dir:/stackable/tls-store-password:password}".to_string())
+        );
+        assert_eq!(
+            admin_config.get("config.providers"),
+            Some(&"dir".to_string())
+        );
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_store_format(KafkaTlsStoreFormat::Pem);
+        let config = kafka_security.controller_config_settings();
+        assert_eq!(
+            config.get("listener.name.controller.ssl.keystore.type"),
+            Some(&"PEM".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.controller.ssl.keystore.key"),
+            Some(&"${dir:/stackable/tls-kafka-internal:tls.key}".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.controller.ssl.truststore.certificates"),
+            Some(&"${dir:/stackable/tls-kafka-internal:ca.crt}".to_string())
+        );
+        assert!(!config.contains_key("listener.name.controller.ssl.keystore.password"));
+        assert_eq!(config.get("config.providers"), Some(&"dir".to_string()));
+    }
+
+    #[test]
+    fn test_bootstrap_secret_class() {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class.clone()]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_bootstrap_secret_class("public-tls");
+        assert!(kafka_security.validate_authentication_methods().is_ok());
+        assert_eq!(
+            kafka_security.tls_bootstrap_secret_class(),
+            Some("public-tls")
+        );
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.bootstrap.ssl.keystore.location"),
+            Some(&"/stackable/tls-kafka-bootstrap/keystore.p12".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.client.ssl.keystore.location"),
+            Some(&"/stackable/tls-kafka-server/keystore.p12".to_string())
+        );
+
+        // Without TLS, there is no keystore the SecretClass could be used for
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class])
+                .with_kerberos_options(KafkaKerberosOptions {
+                    sasl_plaintext: true,
+                    ..KafkaKerberosOptions::default()
+                }),
+            "tls".to_string(),
+            None,
+        )
+        .with_bootstrap_secret_class("public-tls");
+        assert!(matches!(
+            kafka_security.validate_authentication_methods(),
+            Err(Error::BootstrapSecretClassRequiresKerberosTls { .. })
+        ));
+
+        // Without Kerberos, there is no BOOTSTRAP listener
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_bootstrap_secret_class("public-tls");
+        assert!(matches!(
+            kafka_security.validate_authentication_methods(),
+            Err(Error::BootstrapSecretClassRequiresKerberosTls { .. })
+        ));
+    }
+
+    #[test]
+    fn test_zookeeper_config_settings() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(kafka_security.zookeeper_config_settings().is_empty());
+        assert!(kafka_security
+            .zookeeper_jvm_args(&KafkaRole::Broker)
+            .is_empty());
+
+        let kafka_security = kafka_security
+            .with_store_password("simple-kafka-tls-store-password", "secret")
+            .with_zookeeper(Some("zookeeper-client-tls"), Some("kerberos"));
+        let config = kafka_security.zookeeper_config_settings();
+        assert_eq!(
+            config.get("zookeeper.ssl.client.enable"),
+            Some(&"true".to_string())
+        );
+        assert_eq!(
+            config.get("zookeeper.clientCnxnSocket"),
+            Some(&"org.apache.zookeeper.ClientCnxnSocketNetty".to_string())
+        );
+        assert_eq!(
+            config.get("zookeeper.ssl.keystore.location"),
+            Some(&"/stackable/tls-zookeeper/keystore.p12".to_string())
+        );
+        assert_eq!(
+            config.get("zookeeper.ssl.truststore.password"),
+            Some(&"${dir:/stackable/tls-store-password:password}".to_string())
+        );
+        assert_eq!(config.get("config.providers"), Some(&"dir".to_string()));
+        assert_eq!(config.get("zookeeper.set.acl"), Some(&"true".to_string()));
+        assert_eq!(
+            kafka_security.zookeeper_jvm_args(&KafkaRole::Broker),
+            vec![
+                "-Djava.security.auth.login.config=/stackable/config/zookeeper-jaas.conf",
+                "-Djava.security.krb5.conf=/stackable/zookeeper-kerberos/krb5.conf",
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("kerberos", true)]
+    #[case("other-kerberos", false)]
+    fn test_zookeeper_kerberos_secret_class(
+        #[case] zookeeper_secret_class: &str,
+        #[case] valid: bool,
+    ) {
+        let kerberos_authentication_class: AuthenticationClass = serde_yaml::from_str(
+            r#"
+        apiVersion: authentication.stackable.tech/v1alpha1
+        kind: AuthenticationClass
+        metadata:
+          name: kerberos
+        spec:
+          provider:
+            kerberos:
+              kerberosSecretClass: kerberos
+        "#,
+        )
+        .expect("illegal test input");
+
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![kerberos_authentication_class]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_zookeeper(None, Some(zookeeper_secret_class));
+        assert_eq!(
+            kafka_security.validate_authentication_methods().is_ok(),
+            valid
+        );
+        // The brokers already use the krb5.conf of the client Kerberos SecretClass
+        assert_eq!(
+            kafka_security.zookeeper_jvm_args(&KafkaRole::Broker),
+            vec!["-Djava.security.auth.login.config=/stackable/config/zookeeper-jaas.conf"]
+        );
+        assert_eq!(
+            kafka_security
+                .zookeeper_jvm_args(&KafkaRole::Controller)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_opa_truststore() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_store_password("simple-kafka-tls-store-password", "secret")
+        .with_opa_ca_cert_secret_class("opa-tls");
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("opa.authorizer.truststore.path"),
+            Some(&"/stackable/tls-opa/truststore.p12".to_string())
+        );
+        assert_eq!(
+            config.get("opa.authorizer.truststore.password"),
+            Some(&"${dir:/stackable/tls-store-password:password}".to_string())
+        );
+        assert_eq!(
+            config.get("opa.authorizer.truststore.type"),
+            Some(&"PKCS12".to_string())
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{name: repl, port: 9200, protocol: SASL_SSL, authenticationClass: openldap}",
+        true
+    )]
+    #[case(
+        "{name: repl, port: 9200, protocol: SSL, brokerListenerClass: external-stable}",
+        true
+    )]
+    #[case(
+        "{name: sidecar, port: 9300, protocol: PLAINTEXT, loopback: true}",
+        true
+    )]
+    #[case("{name: Repl, port: 9200, protocol: SSL}", false)]
+    #[case("{name: ldap, port: 9200, protocol: SSL}", false)]
+    #[case("{name: auth, port: 9200, protocol: SSL}", false)]
+    #[case("{name: client, port: 9200, protocol: SSL}", false)]
+    #[case("{name: internal, port: 9200, protocol: SSL}", false)]
+    #[case("{name: controller, port: 9200, protocol: SSL}", false)]
+    #[case("{name: bootstrap, port: 9200, protocol: SSL}", false)]
+    #[case("{name: nodeport, port: 9200, protocol: SSL}", false)]
+    #[case("{name: tls, port: 9200, protocol: SSL}", false)]
+    #[case("{name: repl, port: 9101, protocol: SSL}", false)]
+    #[case("{name: repl, port: 9606, protocol: SSL}", false)]
+    #[case(
+        "{name: repl, port: 9200, protocol: SASL_SSL, authenticationClass: unknown}",
+        false
+    )]
+    #[case(
+        "{name: repl, port: 9200, protocol: SSL, authenticationClass: openldap}",
+        false
+    )]
+    #[case("{name: repl, port: 9200, protocol: SASL_PLAINTEXT}", false)]
+    #[case("{name: sidecar, port: 9300, protocol: SSL, loopback: true}", false)]
+    fn test_validate_additional_listeners(#[case] listener: &str, #[case] valid: bool) {
+        let tls = "tls: {verification: {server: {caCert: {secretClass: tls}}}}";
+        let listener: KafkaAdditionalListener =
+            serde_yaml::from_str(listener).expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![
+                oidc_authentication_class(tls),
+                ldap_authentication_class(tls),
+            ])
+            .with_listeners(vec![
+                None,
+                Some(KafkaAuthenticationListener {
+                    name: "ldap".to_string(),
+                    ..KafkaAuthenticationListener::default()
+                }),
+            ]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_additional_listeners(vec![listener]);
+
+        assert_eq!(kafka_security.validate_listeners().is_ok(), valid);
+    }
+
+    #[test]
+    fn test_additional_listener_requires_tls() {
+        let listener: KafkaAdditionalListener =
+            serde_yaml::from_str("{name: repl, port: 9200, protocol: SSL}")
+                .expect("illegal test input");
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "".to_string(),
+            None,
+        )
+        .with_additional_listeners(vec![listener]);
+
+        assert!(kafka_security.validate_listeners().is_err());
+    }
+
+    #[test]
+    fn test_internal_credentials() {
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        )
+        .with_internal_credentials("simple-kafka-internal-credentials");
+        let jaas_config = "org.apache.kafka.common.security.plain.PlainLoginModule required username=\"stackable-internal\" password=\"${dir:/stackable/internal-credentials:password}\"";
+
+        let config = kafka_security.config_settings().unwrap();
+        assert_eq!(
+            config.get("listener.name.internal.sasl.enabled.mechanisms"),
+            Some(&"PLAIN".to_string())
+        );
+        assert_eq!(
+            config.get("sasl.mechanism.inter.broker.protocol"),
+            Some(&"PLAIN".to_string())
+        );
+        assert_eq!(
+            config.get("listener.name.internal.plain.sasl.jaas.config"),
+            Some(&format!(
+                "{jaas_config} user_stackable-internal=\"${{dir:/stackable/internal-credentials:password}}\";"
+            ))
+        );
+        assert_eq!(config.get("config.providers"), Some(&"dir".to_string()));
+        // The certificates are still required, but not mapped to the internal super user
+        assert_eq!(
+            config.get("listener.name.internal.ssl.client.auth"),
+            Some(&"required".to_string())
+        );
+        assert!(!config
+            .keys()
+            .any(|key| key.ends_with("principal.mapping.rules")));
+
+        let controller_config = kafka_security.controller_config_settings();
+        assert_eq!(
+            controller_config.get("listener.name.controller.sasl.enabled.mechanisms"),
+            Some(&"PLAIN".to_string())
+        );
+        assert_eq!(
+            controller_config.get("sasl.mechanism.controller.protocol"),
+            Some(&"PLAIN".to_string())
+        );
+
+        let admin_config = kafka_security.admin_client_config_settings();
+        assert_eq!(
+            admin_config.get("security.protocol"),
+            Some(&"SASL_SSL".to_string())
+        );
+        assert_eq!(
+            admin_config.get("sasl.mechanism"),
+            Some(&"PLAIN".to_string())
+        );
+        assert_eq!(
+            admin_config.get("sasl.jaas.config"),
+            Some(&format!("{jaas_config};"))
+        );
+
+        // Without an authorizer, the internal listeners only authenticate the certificates
+        let kafka_security = KafkaTlsSecurity::new(
+            ResolvedAuthenticationClasses::new(vec![]),
+            "tls".to_string(),
+            Some("tls".to_string()),
+        );
+        assert!(!kafka_security
+            .config_settings()
+            .unwrap()
+            .contains_key("listener.name.internal.sasl.enabled.mechanisms"));
+        assert_eq!(
+            kafka_security
+                .admin_client_config_settings()
+                .get("security.protocol"),
+            Some(&"SSL".to_string())
+        );
     }
 }
@@ -1,7 +1,22 @@
 use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
 use stackable_operator::schemars::{self, JsonSchema};
 
 const TLS_DEFAULT_SECRET_CLASS: &str = "tls";
+/// The key of the generated Secret with the store password, which is also the name of the
+/// mounted file.
+pub const TLS_STORE_PASSWORD_SECRET_KEY: &str = "password";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display(
+        "none of the cipher suites {cipher_suites:?} can be used with the protocols {protocols:?}"
+    ))]
+    NoCipherSuiteForProtocols {
+        protocols: Vec<KafkaTlsProtocol>,
+        cipher_suites: Vec<KafkaTlsCipherSuite>,
+    },
+}
 
 #[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,6 +41,179 @@ pub struct KafkaTls {
         skip_serializing_if = "Option::is_none"
     )]
     pub server_secret_class: Option<String>,
+    /// The [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass.html) to use for
+    /// the bootstrap listener, which only exists with Kerberos authentication. The certificate is
+    /// only issued for the bootstrap address, e.g. a publicly trusted certificate of an external
+    /// load balancer, while the brokers keep the certificates of `serverSecretClass`.
+    /// It is rejected if there is no Kerberos AuthenticationClass with TLS.
+    ///
+    /// Defaults to `serverSecretClass`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_secret_class: Option<String>,
+    /// The TLS protocols and cipher suites accepted by the listeners. By default, the protocols
+    /// and cipher suites enabled by Kafka and the JVM are accepted.
+    #[serde(default)]
+    pub policies: KafkaTlsPolicies,
+    /// The format of the keystores and truststores of the brokers and KRaft controllers, either
+    /// `Pkcs12` or `Pem`. Defaults to `Pkcs12`.
+    #[serde(default)]
+    pub store_format: KafkaTlsStoreFormat,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum KafkaTlsStoreFormat {
+    /// PKCS12 stores, which are protected with a password generated by the operator.
+    #[default]
+    Pkcs12,
+    /// The PEM files of the SecretClass, which are read by Kafka directly. The private key is not
+    /// encrypted, so there is no password.
+    Pem,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaTlsPolicies {
+    /// The policy of the client listeners, which includes the listeners of additional
+    /// AuthenticationClasses and the readiness probe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<KafkaTlsPolicy>,
+    /// The policy of the bootstrap listener, which only exists with Kerberos authentication.
+    /// Defaults to the policy of the client listeners.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<KafkaTlsPolicy>,
+    /// The policy of the internal listener between the brokers, which is also used by the KRaft
+    /// controllers and the admin Jobs of the operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal: Option<KafkaTlsPolicy>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaTlsPolicy {
+    /// The accepted TLS protocols (`ssl.enabled.protocols`), e.g. `TLSv1.3`.
+    #[serde(default)]
+    pub protocols: Vec<KafkaTlsProtocol>,
+    /// The accepted cipher suites (`ssl.cipher.suites`), e.g. `TLS_AES_256_GCM_SHA384`.
+    #[serde(default)]
+    pub cipher_suites: Vec<KafkaTlsCipherSuite>,
+}
+
+#[derive(strum::Display, Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+pub enum KafkaTlsProtocol {
+    #[serde(rename = "TLSv1.2")]
+    #[strum(serialize = "TLSv1.2")]
+    Tls12,
+    #[serde(rename = "TLSv1.3")]
+    #[strum(serialize = "TLSv1.3")]
+    Tls13,
+}
+
+/// The cipher suites with forward secrecy and authenticated encryption, named as in the IANA
+/// registry and the JVM.
+#[derive(strum::Display, Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+pub enum KafkaTlsCipherSuite {
+    // TLSv1.3
+    #[serde(rename = "TLS_AES_128_GCM_SHA256")]
+    #[strum(serialize = "TLS_AES_128_GCM_SHA256")]
+    TlsAes128GcmSha256,
+    #[serde(rename = "TLS_AES_256_GCM_SHA384")]
+    #[strum(serialize = "TLS_AES_256_GCM_SHA384")]
+    TlsAes256GcmSha384,
+    #[serde(rename = "TLS_CHACHA20_POLY1305_SHA256")]
+    #[strum(serialize = "TLS_CHACHA20_POLY1305_SHA256")]
+    TlsChacha20Poly1305Sha256,
+    // TLSv1.2
+    #[serde(rename = "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256")]
+    #[strum(serialize = "TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256")]
+    TlsEcdheEcdsaWithAes128GcmSha256,
+    #[serde(rename = "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384")]
+    #[strum(serialize = "TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384")]
+    TlsEcdheEcdsaWithAes256GcmSha384,
+    #[serde(rename = "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256")]
+    #[strum(serialize = "TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256")]
+    TlsEcdheEcdsaWithChacha20Poly1305Sha256,
+    #[serde(rename = "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256")]
+    #[strum(serialize = "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256")]
+    TlsEcdheRsaWithAes128GcmSha256,
+    #[serde(rename = "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384")]
+    #[strum(serialize = "TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384")]
+    TlsEcdheRsaWithAes256GcmSha384,
+    #[serde(rename = "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256")]
+    #[strum(serialize = "TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256")]
+    TlsEcdheRsaWithChacha20Poly1305Sha256,
+    #[serde(rename = "TLS_DHE_RSA_WITH_AES_128_GCM_SHA256")]
+    #[strum(serialize = "TLS_DHE_RSA_WITH_AES_128_GCM_SHA256")]
+    TlsDheRsaWithAes128GcmSha256,
+    #[serde(rename = "TLS_DHE_RSA_WITH_AES_256_GCM_SHA384")]
+    #[strum(serialize = "TLS_DHE_RSA_WITH_AES_256_GCM_SHA384")]
+    TlsDheRsaWithAes256GcmSha384,
+}
+
+impl KafkaTlsCipherSuite {
+    /// The protocol the cipher suite is used with.
+    pub fn protocol(&self) -> KafkaTlsProtocol {
+        match self.openssl_name() {
+            Some(_) => KafkaTlsProtocol::Tls12,
+            None => KafkaTlsProtocol::Tls13,
+        }
+    }
+
+    /// The OpenSSL name of a TLSv1.2 cipher suite, as required by kcat (librdkafka). The TLSv1.3
+    /// cipher suites cannot be configured in librdkafka.
+    pub fn openssl_name(&self) -> Option<&'static str> {
+        match self {
+            Self::TlsAes128GcmSha256
+            | Self::TlsAes256GcmSha384
+            | Self::TlsChacha20Poly1305Sha256 => None,
+            Self::TlsEcdheEcdsaWithAes128GcmSha256 => Some("ECDHE-ECDSA-AES128-GCM-SHA256"),
+            Self::TlsEcdheEcdsaWithAes256GcmSha384 => Some("ECDHE-ECDSA-AES256-GCM-SHA384"),
+            Self::TlsEcdheEcdsaWithChacha20Poly1305Sha256 => Some("ECDHE-ECDSA-CHACHA20-POLY1305"),
+            Self::TlsEcdheRsaWithAes128GcmSha256 => Some("ECDHE-RSA-AES128-GCM-SHA256"),
+            Self::TlsEcdheRsaWithAes256GcmSha384 => Some("ECDHE-RSA-AES256-GCM-SHA384"),
+            Self::TlsEcdheRsaWithChacha20Poly1305Sha256 => Some("ECDHE-RSA-CHACHA20-POLY1305"),
+            Self::TlsDheRsaWithAes128GcmSha256 => Some("DHE-RSA-AES128-GCM-SHA256"),
+            Self::TlsDheRsaWithAes256GcmSha384 => Some("DHE-RSA-AES256-GCM-SHA384"),
+        }
+    }
+}
+
+impl KafkaTlsPolicies {
+    /// The policy of the bootstrap listener, which falls back to the policy of the client
+    /// listeners.
+    pub fn bootstrap(&self) -> Option<&KafkaTlsPolicy> {
+        self.bootstrap.as_ref().or(self.client.as_ref())
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        for policy in [&self.client, &self.bootstrap, &self.internal]
+            .into_iter()
+            .flatten()
+        {
+            policy.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl KafkaTlsPolicy {
+    /// Checks that a handshake is possible, i.e. that one of the cipher suites belongs to one of
+    /// the protocols. Empty lists keep the defaults of Kafka and the JVM.
+    pub fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            self.protocols.is_empty()
+                || self.cipher_suites.is_empty()
+                || self
+                    .cipher_suites
+                    .iter()
+                    .any(|cipher_suite| self.protocols.contains(&cipher_suite.protocol())),
+            NoCipherSuiteForProtocolsSnafu {
+                protocols: self.protocols.clone(),
+                cipher_suites: self.cipher_suites.clone(),
+            }
+        );
+        Ok(())
+    }
 }
 
 /// Default TLS settings.
@@ -34,6 +222,9 @@ pub fn default_kafka_tls() -> Option<KafkaTls> {
     Some(KafkaTls {
         internal_secret_class: internal_tls_default(),
         server_secret_class: server_tls_default(),
+        bootstrap_secret_class: None,
+        policies: KafkaTlsPolicies::default(),
+        store_format: KafkaTlsStoreFormat::default(),
     })
 }
 
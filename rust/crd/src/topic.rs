@@ -0,0 +1,245 @@
+//! The [`KafkaTopic`] custom resource, which declares a topic in a [`KafkaCluster`](crate::KafkaCluster).
+use std::collections::BTreeMap;
+
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use stackable_operator::{
+    kube::{CustomResource, ResourceExt},
+    schemars::{self, JsonSchema},
+};
+
+use crate::admin::{admin_tool, shell_quote, DeletionPolicy, KafkaClusterRef, SyncStatus};
+
+/// The maximum length of a topic name, see `org.apache.kafka.common.internals.Topic`.
+const MAX_TOPIC_NAME_LENGTH: usize = 249;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display(
+        "the topic name {name:?} is invalid: it must consist of at most {MAX_TOPIC_NAME_LENGTH} ASCII alphanumerics, '.', '_' or '-' and must not be '.' or '..'"
+    ))]
+    InvalidTopicName { name: String },
+}
+
+/// A topic in a Kafka cluster managed by the Stackable operator for Apache Kafka.
+/// Find more information on how to use it in the
+/// [operator documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/topics).
+#[derive(Clone, CustomResource, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[kube(
+    group = "kafka.stackable.tech",
+    version = "v1alpha1",
+    kind = "KafkaTopic",
+    plural = "kafkatopics",
+    status = "SyncStatus",
+    namespaced,
+    crates(
+        kube_core = "stackable_operator::kube::core",
+        k8s_openapi = "stackable_operator::k8s_openapi",
+        schemars = "stackable_operator::schemars"
+    )
+)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaTopicSpec {
+    /// The KafkaCluster the topic is created in.
+    pub cluster_ref: KafkaClusterRef,
+
+    /// The name of the topic in Kafka. Defaults to the name of the KafkaTopic object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic_name: Option<String>,
+
+    /// The number of partitions. Defaults to `num.partitions` of the brokers.
+    /// The number of partitions of an existing topic can only be increased.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partitions: Option<u32>,
+
+    /// The replication factor. Defaults to `default.replication.factor` of the brokers.
+    /// It is only applied when the topic is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_factor: Option<u16>,
+
+    /// Topic level configuration, e.g. `retention.ms` or `cleanup.policy`.
+    /// Settings that are not listed here are reset to the broker defaults.
+    #[serde(default)]
+    pub config: BTreeMap<String, String>,
+
+    /// Whether the topic is deleted in Kafka when the KafkaTopic is deleted. Defaults to `Retain`.
+    #[serde(default)]
+    pub deletion_policy: DeletionPolicy,
+}
+
+impl KafkaTopic {
+    /// The name of the topic in Kafka, which is validated as Kafka does not accept all names that
+    /// are valid for Kubernetes objects (and vice versa).
+    pub fn topic_name(&self) -> Result<String, Error> {
+        let name = self
+            .spec
+            .topic_name
+            .clone()
+            .unwrap_or_else(|| self.name_any());
+        ensure!(
+            !name.is_empty()
+                && name.len() <= MAX_TOPIC_NAME_LENGTH
+                && name != "."
+                && name != ".."
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')),
+            InvalidTopicNameSnafu { name }
+        );
+        Ok(name)
+    }
+
+    /// Returns the commands that create the topic if it does not exist yet and update the
+    /// partitions and config of an existing topic. They are meant to be wrapped with
+    /// [`admin_tool_commands`](crate::admin::admin_tool_commands).
+    pub fn apply_commands(&self) -> Result<String, Error> {
+        let topic_name = self.topic_name()?;
+        let kafka_topics = admin_tool("kafka-topics.sh");
+        let kafka_configs = format!(
+            "{} --entity-type topics --entity-name {topic_name}",
+            admin_tool("kafka-configs.sh")
+        );
+
+        let mut create_args = String::new();
+        if let Some(partitions) = self.spec.partitions {
+            create_args.push_str(&format!(" --partitions {partitions}"));
+        }
+        if let Some(replication_factor) = self.spec.replication_factor {
+            create_args.push_str(&format!(" --replication-factor {replication_factor}"));
+        }
+
+        let mut commands = vec![format!(
+            "{kafka_topics} --create --if-not-exists --topic {topic_name}{create_args}"
+        )];
+
+        if let Some(partitions) = self.spec.partitions {
+            commands.push(formatdoc! {"
+                PARTITIONS=$({kafka_topics} --describe --topic {topic_name} | grep -oP 'PartitionCount:\\s*\\K[0-9]+')
+                if [ \"$PARTITIONS\" -lt {partitions} ]; then
+                  {kafka_topics} --alter --topic {topic_name} --partitions {partitions}
+                elif [ \"$PARTITIONS\" -gt {partitions} ]; then
+                  echo \"The number of partitions of topic {topic_name} cannot be decreased from $PARTITIONS to {partitions}\" >&2
+                  exit 1
+                fi"
+            });
+        }
+
+        if !self.spec.config.is_empty() {
+            let config = self
+                .spec
+                .config
+                .iter()
+                .map(|(key, value)| {
+                    // Values containing commas have to be put in square brackets
+                    if value.contains(',') {
+                        format!("{key}=[{value}]")
+                    } else {
+                        format!("{key}={value}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            commands.push(format!(
+                "{kafka_configs} --alter --add-config {}",
+                shell_quote(&config)
+            ));
+        }
+
+        // Reset all dynamic topic configs that are not part of the spec
+        let keep = format!(
+            " {} ",
+            self.spec
+                .config
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        commands.push(formatdoc! {"
+            KEEP={keep}
+            for KEY in $({kafka_configs} --describe | grep -oP '^\\s+\\K[^=\\s]+(?==)'); do
+              if [[ \"$KEEP\" != *\" $KEY \"* ]]; then
+                {kafka_configs} --alter --delete-config \"$KEY\"
+              fi
+            done",
+            keep = shell_quote(&keep),
+        });
+
+        Ok(commands.join("\n"))
+    }
+
+    /// Returns the commands that delete the topic, which succeed if the topic does not exist.
+    pub fn delete_commands(&self) -> Result<String, Error> {
+        Ok(format!(
+            "{} --delete --if-exists --topic {}",
+            admin_tool("kafka-topics.sh"),
+            self.topic_name()?
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(None, Some("orders"))]
+    #[case(Some("orders.v1_eu-west"), Some("orders.v1_eu-west"))]
+    #[case(Some(".."), None)]
+    #[case(Some("orders/v1"), None)]
+    #[case(Some(""), None)]
+    fn test_topic_name(#[case] topic_name: Option<&str>, #[case] expected: Option<&str>) {
+        let input = format!(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaTopic
+        metadata:
+          name: orders
+          namespace: default
+        spec:
+          clusterRef:
+            name: simple-kafka
+          {topic_name}
+        "#,
+            topic_name = topic_name
+                .map(|name| format!("topicName: \"{name}\""))
+                .unwrap_or_default()
+        );
+        let topic: KafkaTopic = serde_yaml::from_str(&input).expect("illegal test input");
+
+        assert_eq!(topic.topic_name().ok().as_deref(), expected);
+        assert_eq!(topic.spec.deletion_policy, DeletionPolicy::Retain);
+        assert!(topic.spec.config.is_empty());
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaTopic
+        metadata:
+          name: orders
+          namespace: default
+        spec:
+          clusterRef:
+            name: simple-kafka
+          partitions: 3
+          config:
+            cleanup.policy: compact,delete
+            retention.ms: "604800000"
+        "#;
+        let topic: KafkaTopic = serde_yaml::from_str(input).expect("illegal test input");
+        let commands = topic.apply_commands().unwrap();
+
+        assert!(commands.contains("--create --if-not-exists --topic orders --partitions 3\n"));
+        assert!(commands.contains("--alter --topic orders --partitions 3"));
+        assert!(commands.contains(
+            "--alter --add-config 'cleanup.policy=[compact,delete],retention.ms=604800000'"
+        ));
+        assert!(commands.contains("KEEP=' cleanup.policy retention.ms '"));
+        assert!(!commands.contains("--replication-factor"));
+    }
+}
@@ -0,0 +1,248 @@
+//! The [`KafkaUser`] custom resource, which declares SCRAM credentials in a [`KafkaCluster`](crate::KafkaCluster).
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, Snafu};
+use stackable_operator::{
+    kube::{CustomResource, ResourceExt},
+    schemars::{self, JsonSchema},
+};
+
+use crate::admin::{admin_tool, KafkaClusterRef, SyncStatus};
+
+/// The SASL mechanism of the provisioned credentials.
+pub const SCRAM_MECHANISM: &str = "SCRAM-SHA-512";
+/// The SASL mechanisms of the CLIENT listener if a Static AuthenticationClass is used.
+pub const SCRAM_MECHANISMS: [&str; 2] = ["SCRAM-SHA-256", "SCRAM-SHA-512"];
+const SCRAM_ITERATIONS: u32 = 4096;
+// keys of the generated Secret
+pub const USERNAME_SECRET_KEY: &str = "username";
+pub const PASSWORD_SECRET_KEY: &str = "password";
+pub const SASL_MECHANISM_SECRET_KEY: &str = "sasl.mechanism";
+pub const SASL_JAAS_CONFIG_SECRET_KEY: &str = "sasl.jaas.config";
+pub const BOOTSTRAP_SERVERS_SECRET_KEY: &str = "bootstrap.servers";
+/// The password is passed to the admin Job in this env var, so that it is not part of the Job spec.
+pub const PASSWORD_ENV: &str = "SCRAM_PASSWORD";
+/// The user of a Static AuthenticationClass the readiness probe of the brokers logs in with. Its
+/// password is generated by the operator, so an entry for it in the user credentials Secret is
+/// ignored.
+pub const SCRAM_PROBER_USER: &str = "stackable-prober";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display(
+        "the user name {name:?} is invalid: it must consist of ASCII alphanumerics, '.', '_', '-' or '@'"
+    ))]
+    InvalidUserName { name: String },
+
+    #[snafu(display(
+        "the password of the user {user_name:?} is invalid: it must not contain ',', '[' or ']'"
+    ))]
+    InvalidPassword { user_name: String },
+}
+
+/// A user in a Kafka cluster managed by the Stackable operator for Apache Kafka.
+/// The operator provisions SCRAM credentials for the user and writes them to a Secret.
+/// Find more information on how to use it in the
+/// [operator documentation](DOCS_BASE_URL_PLACEHOLDER/kafka/usage-guide/users).
+#[derive(Clone, CustomResource, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[kube(
+    group = "kafka.stackable.tech",
+    version = "v1alpha1",
+    kind = "KafkaUser",
+    plural = "kafkausers",
+    status = "KafkaUserStatus",
+    namespaced,
+    crates(
+        kube_core = "stackable_operator::kube::core",
+        k8s_openapi = "stackable_operator::k8s_openapi",
+        schemars = "stackable_operator::schemars"
+    )
+)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaUserSpec {
+    /// The KafkaCluster the credentials are provisioned in.
+    pub cluster_ref: KafkaClusterRef,
+
+    /// The name of the user in Kafka. Defaults to the name of the KafkaUser object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+
+    /// The name of the Secret the credentials are written to. Defaults to the name of the
+    /// KafkaUser object. The Secret is created by the operator, existing Secrets which are not
+    /// owned by the KafkaUser are refused. A changed password in the Secret is provisioned again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaUserStatus {
+    #[serde(flatten)]
+    pub sync: SyncStatus,
+
+    /// A hash of the provisioned password, so that a password which is changed in the Secret is
+    /// provisioned again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+}
+
+impl KafkaUser {
+    /// The name of the user in Kafka. It is restricted to characters that need no escaping in
+    /// SCRAM messages and JAAS configurations.
+    pub fn user_name(&self) -> Result<String, Error> {
+        let name = self
+            .spec
+            .user_name
+            .clone()
+            .unwrap_or_else(|| self.name_any());
+        validate_user_name(&name)?;
+        Ok(name)
+    }
+
+    pub fn secret_name(&self) -> String {
+        self.spec
+            .secret_name
+            .clone()
+            .unwrap_or_else(|| self.name_any())
+    }
+
+    /// Returns the commands that create or update the SCRAM credentials of the user. The password
+    /// is read from the [`PASSWORD_ENV`] env var.
+    pub fn apply_commands(&self) -> Result<String, Error> {
+        Ok(format!(
+            "{kafka_configs} --alter --add-config \"{SCRAM_MECHANISM}=[iterations={SCRAM_ITERATIONS},password=${PASSWORD_ENV}]\"",
+            kafka_configs = self.kafka_configs()?,
+        ))
+    }
+
+    /// Returns the commands that delete the SCRAM credentials, which succeed if there are none.
+    pub fn delete_commands(&self) -> Result<String, Error> {
+        let kafka_configs = self.kafka_configs()?;
+        Ok(formatdoc! {"
+            if {kafka_configs} --describe | grep -q {SCRAM_MECHANISM}; then
+              {kafka_configs} --alter --delete-config {SCRAM_MECHANISM}
+            fi"
+        })
+    }
+
+    fn kafka_configs(&self) -> Result<String, Error> {
+        Ok(format!(
+            "{} --entity-type users --entity-name {}",
+            admin_tool("kafka-configs.sh"),
+            self.user_name()?
+        ))
+    }
+}
+
+/// Checks a user of a Static AuthenticationClass. The password is passed to the admin tools in a
+/// SCRAM config, in which `,`, `[` and `]` are separators.
+pub fn validate_static_user(user_name: &str, password: &str) -> Result<(), Error> {
+    validate_user_name(user_name)?;
+    ensure!(
+        !password.contains([',', '[', ']']),
+        InvalidPasswordSnafu { user_name }
+    );
+    Ok(())
+}
+
+/// Returns the commands that bootstrap the users of a Static AuthenticationClass with credentials
+/// for all [`SCRAM_MECHANISMS`]. The password of the n-th user is read from the
+/// `<PASSWORD_ENV>_<n>` env var.
+pub fn static_user_commands(user_names: &[String]) -> String {
+    // The passwords must not end up in the log of the Job
+    let mut commands = vec!["set +x".to_string()];
+    for (index, user_name) in user_names.iter().enumerate() {
+        let credentials = SCRAM_MECHANISMS
+            .iter()
+            .map(|mechanism| {
+                format!(
+                    "{mechanism}=[iterations={SCRAM_ITERATIONS},password=${PASSWORD_ENV}_{index}]"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        commands.push(format!(
+            "echo \"Bootstrapping the credentials of {user_name}\"\n{kafka_configs} --entity-type users --entity-name {user_name} --alter --add-config \"{credentials}\"",
+            kafka_configs = admin_tool("kafka-configs.sh"),
+        ));
+    }
+    commands.join("\n")
+}
+
+fn validate_user_name(name: &str) -> Result<(), Error> {
+    ensure!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@')),
+        InvalidUserNameSnafu { name }
+    );
+    Ok(())
+}
+
+/// The JAAS configuration (`sasl.jaas.config`) Kafka clients use to log in with the credentials.
+pub fn jaas_config(user_name: &str, password: &str) -> String {
+    format!("org.apache.kafka.common.security.scram.ScramLoginModule required username=\"{user_name}\" password=\"{password}\";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(None, Some("alice"))]
+    #[case(
+        Some("svc.orders-app_1@example.com"),
+        Some("svc.orders-app_1@example.com")
+    )]
+    #[case(Some("alice,bob"), None)]
+    #[case(Some("alice=admin"), None)]
+    #[case(Some(""), None)]
+    fn test_user_name(#[case] user_name: Option<&str>, #[case] expected: Option<&str>) {
+        let input = format!(
+            r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaUser
+        metadata:
+          name: alice
+          namespace: default
+        spec:
+          clusterRef:
+            name: simple-kafka
+          {user_name}
+        "#,
+            user_name = user_name
+                .map(|name| format!("userName: \"{name}\""))
+                .unwrap_or_default()
+        );
+        let user: KafkaUser = serde_yaml::from_str(&input).expect("illegal test input");
+
+        assert_eq!(user.user_name().ok().as_deref(), expected);
+        assert_eq!(user.secret_name(), "alice");
+    }
+
+    #[rstest]
+    #[case("alice", "s3cr3t=!", true)]
+    #[case("alice", "a,b", false)]
+    #[case("alice", "[admin]", false)]
+    #[case("alice bob", "s3cr3t", false)]
+    fn test_validate_static_user(
+        #[case] user_name: &str,
+        #[case] password: &str,
+        #[case] valid: bool,
+    ) {
+        assert_eq!(validate_static_user(user_name, password).is_ok(), valid);
+    }
+
+    #[test]
+    fn test_static_user_commands() {
+        let commands = static_user_commands(&["alice".to_string(), "bob".to_string()]);
+
+        assert!(commands.starts_with("set +x\n"));
+        assert!(commands.contains(
+            "--entity-name bob --alter --add-config \"SCRAM-SHA-256=[iterations=4096,password=$SCRAM_PASSWORD_1],SCRAM-SHA-512=[iterations=4096,password=$SCRAM_PASSWORD_1]\""
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+//! The connection of ZooKeeper based clusters to ZooKeeper, which is optionally secured with TLS
+//! and Kerberos.
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, Snafu};
+use stackable_operator::{
+    kube::ResourceExt,
+    schemars::{self, JsonSchema},
+    utils::cluster_info::KubernetesClusterInfo,
+};
+
+use crate::{KafkaCluster, KafkaRole, STACKABLE_ZOOKEEPER_KERBEROS_DIR};
+
+const ZOOKEEPER_CLIENT_CERT_DEFAULT_SECRET_CLASS: &str = "tls";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+}
+
+#[derive(Clone, Default, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaZookeeperConfig {
+    /// Connect to ZooKeeper with TLS. This must be set if the ZooKeeper discovery ConfigMap points
+    /// to a client port of ZooKeeper which serves TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<KafkaZookeeperTls>,
+    /// Authenticate to ZooKeeper with Kerberos and protect the znodes of Kafka with ACLs, so that
+    /// other clients of ZooKeeper can read but not modify the metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kerberos: Option<KafkaZookeeperKerberos>,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaZookeeperTls {
+    /// The [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass.html) which issues
+    /// the client certificate of the brokers. ZooKeeper must trust its CA, and its CA must have
+    /// issued the server certificate of ZooKeeper.
+    ///
+    /// Defaults to `tls`.
+    #[serde(default = "zookeeper_client_cert_default")]
+    pub client_cert_secret_class: String,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaZookeeperKerberos {
+    /// The [SecretClass](DOCS_BASE_URL_PLACEHOLDER/secret-operator/secretclass.html) which
+    /// provides the keytab of the brokers. If clients authenticate with Kerberos, this must be the
+    /// `kerberosSecretClass` of their AuthenticationClass.
+    pub secret_class: String,
+}
+
+pub fn zookeeper_client_cert_default() -> String {
+    ZOOKEEPER_CLIENT_CERT_DEFAULT_SECRET_CLASS.to_string()
+}
+
+/// The name of the service scope of the keytab the brokers and controllers log in to ZooKeeper
+/// with. All of them share the principal, as the znode ACLs only grant access to the principal
+/// which created the znode. The Service itself does not exist.
+pub fn zookeeper_kerberos_service(kafka: &KafkaCluster) -> String {
+    format!("{}-zookeeper", kafka.name_any())
+}
+
+/// The JAAS configuration with the `Client` section, which the ZooKeeper client logs in with.
+/// The principal has no realm, so the default realm of the `krb5.conf` is used.
+pub fn zookeeper_jaas_config(
+    kafka: &KafkaCluster,
+    cluster_info: &KubernetesClusterInfo,
+) -> Result<String, Error> {
+    let principal_host = format!(
+        "{service}.{namespace}.svc.{cluster_domain}",
+        service = zookeeper_kerberos_service(kafka),
+        namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?,
+        cluster_domain = cluster_info.cluster_domain,
+    );
+    Ok(formatdoc! {"
+        Client {{
+          com.sun.security.auth.module.Krb5LoginModule required
+          useKeyTab=true
+          storeKey=true
+          keyTab=\"{STACKABLE_ZOOKEEPER_KERBEROS_DIR}/keytab\"
+          principal=\"{service_name}/{principal_host}\";
+        }};
+        ",
+        service_name = KafkaRole::Broker.kerberos_service_name(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+    use stackable_operator::commons::networking::DomainName;
+
+    #[rstest]
+    #[case("{}", None)]
+    #[case("{tls: {}}", Some("tls"))]
+    #[case(
+        "{tls: {clientCertSecretClass: zookeeper-client-tls}}",
+        Some("zookeeper-client-tls")
+    )]
+    fn test_zookeeper_tls(#[case] zookeeper: &str, #[case] client_cert_secret_class: Option<&str>) {
+        let zookeeper: KafkaZookeeperConfig =
+            serde_yaml::from_str(zookeeper).expect("illegal test input");
+        assert_eq!(
+            zookeeper
+                .tls
+                .as_ref()
+                .map(|tls| tls.client_cert_secret_class.as_str()),
+            client_cert_secret_class
+        );
+    }
+
+    #[test]
+    fn test_zookeeper_jaas_config() {
+        let input = r#"
+        apiVersion: kafka.stackable.tech/v1alpha1
+        kind: KafkaCluster
+        metadata:
+          name: simple-kafka
+          namespace: default
+        spec:
+          image:
+            productVersion: 3.7.1
+          clusterConfig:
+            zookeeperConfigMapName: xyz
+            zookeeper:
+              kerberos:
+                secretClass: kerberos
+        "#;
+        let kafka: KafkaCluster = serde_yaml::from_str(input).expect("illegal test input");
+        assert_eq!(kafka.spec.cluster_config.zookeeper.tls, None);
+        let cluster_info = KubernetesClusterInfo {
+            cluster_domain: DomainName::try_from("cluster.local").unwrap(),
+        };
+
+        assert_eq!(
+            zookeeper_jaas_config(&kafka, &cluster_info).unwrap(),
+            "Client {\n  com.sun.security.auth.module.Krb5LoginModule required\n  useKeyTab=true\n  storeKey=true\n  keyTab=\"/stackable/zookeeper-kerberos/keytab\"\n  principal=\"kafka/simple-kafka-zookeeper.default.svc.cluster.local\";\n};\n"
+        );
+    }
+}
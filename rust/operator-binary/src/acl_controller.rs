@@ -0,0 +1,361 @@
+//! Keeps the ACLs declared by a [`KafkaAcl`] in sync with the referenced [`KafkaCluster`]
+//!
+//! Besides applying every generation of the spec, the ACLs are checked for drift periodically:
+//! Once the admin Job of the last sync or check is removed (see the TTL of the admin Jobs), another
+//! check is started.
+use std::sync::Arc;
+
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{
+    acl::{self, AclDrift, KafkaAcl, KafkaAclStatus},
+    admin::{SyncState, SyncStatus},
+    security::KafkaTlsSecurity,
+    KafkaCluster, OPERATOR_NAME,
+};
+use stackable_operator::{
+    k8s_openapi::{api::batch::v1::Job, apimachinery::pkg::apis::meta::v1::Time, chrono::Utc},
+    kube::{
+        api::DynamicObject,
+        core::{error_boundary, DeserializeGuard},
+        runtime::{
+            controller::Action,
+            finalizer::{self, finalizer},
+            reflector::ObjectRef,
+        },
+        ResourceExt,
+    },
+    logging::controller::ReconcilerError,
+    time::Duration,
+};
+use strum::{EnumDiscriminants, IntoStaticStr};
+
+use crate::admin::{
+    self, admin_job_name, admin_job_output, admin_job_state, build_admin_job, AdminJobState,
+};
+
+pub const ACL_CONTROLLER_NAME: &str = "kafkaacl";
+const ACL_FINALIZER: &str = "kafka.stackable.tech/acl";
+
+pub struct Ctx {
+    pub client: stackable_operator::client::Client,
+}
+
+#[derive(Snafu, Debug, EnumDiscriminants)]
+#[strum_discriminants(derive(IntoStaticStr))]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("KafkaAcl object is invalid"))]
+    InvalidKafkaAcl {
+        source: error_boundary::InvalidObject,
+    },
+
+    #[snafu(display("invalid ACL binding"))]
+    InvalidAcl { source: acl::Error },
+
+    #[snafu(display("failed to retrieve {kafka}"))]
+    RetrieveKafkaCluster {
+        source: stackable_operator::client::Error,
+        kafka: ObjectRef<KafkaCluster>,
+    },
+
+    #[snafu(display("{kafka} not found"))]
+    KafkaClusterNotFound { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display("failed to initialize security context"))]
+    FailedToInitializeSecurityContext {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to retrieve admin Job [{job_name}]"))]
+    RetrieveAdminJob {
+        source: stackable_operator::client::Error,
+        job_name: String,
+    },
+
+    #[snafu(display("failed to build admin Job"))]
+    BuildAdminJob { source: admin::Error },
+
+    #[snafu(display("failed to apply admin Job"))]
+    ApplyAdminJob {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("failed to list the Pods of admin Job [{job_name}]"))]
+    ListAdminJobPods {
+        source: stackable_operator::kube::Error,
+        job_name: String,
+    },
+
+    #[snafu(display("the ACLs are not deleted yet, waiting for Job [{job_name}]"))]
+    DeletionPending { job_name: String },
+
+    #[snafu(display("failed to update status"))]
+    ApplyStatus {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("error managing finalizer"))]
+    Finalizer {
+        source: Box<finalizer::Error<Error>>,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl ReconcilerError for Error {
+    fn category(&self) -> &'static str {
+        ErrorDiscriminants::from(self).into()
+    }
+
+    fn secondary_object(&self) -> Option<ObjectRef<DynamicObject>> {
+        match self {
+            Error::ObjectHasNoNamespace => None,
+            Error::InvalidKafkaAcl { .. } => None,
+            Error::InvalidAcl { .. } => None,
+            Error::RetrieveKafkaCluster { kafka, .. } => Some(kafka.clone().erase()),
+            Error::KafkaClusterNotFound { kafka } => Some(kafka.clone().erase()),
+            Error::FailedToInitializeSecurityContext { .. } => None,
+            Error::RetrieveAdminJob { .. } => None,
+            Error::BuildAdminJob { .. } => None,
+            Error::ApplyAdminJob { .. } => None,
+            Error::ListAdminJobPods { .. } => None,
+            Error::DeletionPending { .. } => None,
+            Error::ApplyStatus { .. } => None,
+            Error::Finalizer { .. } => None,
+        }
+    }
+}
+
+pub async fn reconcile_acl(acl: Arc<DeserializeGuard<KafkaAcl>>, ctx: Arc<Ctx>) -> Result<Action> {
+    tracing::info!("Starting reconcile");
+
+    let acl = acl
+        .0
+        .as_ref()
+        .map_err(error_boundary::InvalidObject::clone)
+        .context(InvalidKafkaAclSnafu)?;
+    let namespace = acl.namespace().context(ObjectHasNoNamespaceSnafu)?;
+
+    finalizer(
+        &ctx.client.get_api::<KafkaAcl>(&namespace),
+        ACL_FINALIZER,
+        Arc::new(acl.clone()),
+        |event| async move {
+            match event {
+                finalizer::Event::Apply(acl) => apply_acl(&acl, &ctx).await,
+                finalizer::Event::Cleanup(acl) => cleanup_acl(&acl, &ctx).await,
+            }
+        },
+    )
+    .await
+    .map_err(|source| Error::Finalizer {
+        source: Box::new(source),
+    })
+}
+
+/// Runs an admin Job which syncs the ACLs with the current generation of the spec or, once they
+/// are synced, checks them for drift. The outcome is reported in the status.
+async fn apply_acl(acl: &KafkaAcl, ctx: &Ctx) -> Result<Action> {
+    let namespace = acl.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let generation = acl.metadata.generation.unwrap_or_default().to_string();
+    let sync_job_name = admin_job_name(&acl.name_any(), "acl", &[&generation]);
+    let synced = acl.status.as_ref().is_some_and(|status| {
+        status.sync.sync_state == SyncState::Synced
+            && status.sync.observed_generation == acl.metadata.generation
+    });
+
+    let job_name = if synced {
+        // Wait for the TTL of the sync Job before the first check
+        if ctx
+            .client
+            .get_opt::<Job>(&sync_job_name, &namespace)
+            .await
+            .with_context(|_| RetrieveAdminJobSnafu {
+                job_name: &sync_job_name,
+            })?
+            .is_some()
+        {
+            return Ok(Action::await_change());
+        }
+        admin_job_name(&acl.name_any(), "acl", &[&generation, "check"])
+    } else {
+        sync_job_name
+    };
+
+    let result = match acl.apply_commands().context(InvalidAclSnafu) {
+        Ok(commands) => run_admin_job(acl, ctx, &job_name, &commands).await,
+        Err(err) => Err(err),
+    };
+
+    let previous = acl.status.clone().unwrap_or_default();
+    let (sync_state, message, scopes, drift) = match &result {
+        // The ACLs stay synced while they are checked
+        Ok((AdminJobState::Running, _)) if synced => {
+            (SyncState::Synced, None, previous.scopes, previous.drift)
+        }
+        Ok((AdminJobState::Running, _)) => (
+            SyncState::Pending,
+            None,
+            acl.managed_scopes().into_iter().collect(),
+            previous.drift,
+        ),
+        // Only the checks report drift, the sync Jobs are expected to change the ACLs
+        Ok((AdminJobState::Succeeded, drift)) => (
+            SyncState::Synced,
+            None,
+            acl.spec_scopes().into_iter().collect(),
+            match drift {
+                Some(drift) if synced && !drift.entries.is_empty() => Some(drift.clone()),
+                _ => previous.drift,
+            },
+        ),
+        Ok((AdminJobState::Failed, _)) => (
+            SyncState::Failed,
+            Some(format!(
+                "the admin Job [{job_name}] failed, check its logs for details"
+            )),
+            acl.managed_scopes().into_iter().collect(),
+            previous.drift,
+        ),
+        Err(err) => (
+            SyncState::Failed,
+            Some(err.to_string()),
+            previous.scopes,
+            previous.drift,
+        ),
+    };
+    let status = KafkaAclStatus {
+        sync: SyncStatus::new(
+            sync_state,
+            acl.metadata.generation,
+            message,
+            acl.status.as_ref().map(|status| &status.sync),
+        ),
+        scopes,
+        drift,
+    };
+    update_status(acl, ctx, &status).await?;
+
+    match result {
+        // Retrying does not help, the spec has to be changed
+        Err(Error::InvalidAcl { .. }) => Ok(Action::await_change()),
+        result => result.map(|_| Action::await_change()),
+    }
+}
+
+/// Deletes all managed ACLs in Kafka. The finalizer is only removed once the admin Job succeeded
+/// or if there is nothing to delete.
+async fn cleanup_acl(acl: &KafkaAcl, ctx: &Ctx) -> Result<Action> {
+    let job_name = admin_job_name(&acl.name_any(), "acl", &["delete"]);
+    match run_admin_job(acl, ctx, &job_name, &acl.delete_commands()).await {
+        Ok((AdminJobState::Succeeded, _)) => Ok(Action::await_change()),
+        Ok((state, _)) => {
+            let mut status = acl.status.clone().unwrap_or_default();
+            status.sync = SyncStatus::new(
+                SyncState::Deleting,
+                acl.metadata.generation,
+                (state == AdminJobState::Failed).then(|| {
+                    format!("the admin Job [{job_name}] failed, check its logs for details")
+                }),
+                acl.status.as_ref().map(|status| &status.sync),
+            );
+            update_status(acl, ctx, &status).await?;
+            DeletionPendingSnafu { job_name }.fail()
+        }
+        // There is nothing to delete if the cluster does not exist anymore
+        Err(Error::KafkaClusterNotFound { .. }) => Ok(Action::await_change()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Runs an admin Job against the cluster of the ACLs, unless a Job with this name already exists.
+/// As the commands depend on the status, an existing Job is not updated.
+///
+/// Returns the state of the Job and, once it succeeded, the drift it reported.
+async fn run_admin_job(
+    acl: &KafkaAcl,
+    ctx: &Ctx,
+    job_name: &str,
+    commands: &str,
+) -> Result<(AdminJobState, Option<AclDrift>)> {
+    let client = &ctx.client;
+    let namespace = acl.namespace().context(ObjectHasNoNamespaceSnafu)?;
+
+    let job = match client
+        .get_opt::<Job>(job_name, &namespace)
+        .await
+        .context(RetrieveAdminJobSnafu { job_name })?
+    {
+        Some(job) => job,
+        None => {
+            let kafka_ref =
+                ObjectRef::<KafkaCluster>::new(&acl.spec.cluster_ref.name).within(&namespace);
+            let kafka = client
+                .get_opt::<KafkaCluster>(&acl.spec.cluster_ref.name, &namespace)
+                .await
+                .with_context(|_| RetrieveKafkaClusterSnafu {
+                    kafka: kafka_ref.clone(),
+                })?
+                .with_context(|| KafkaClusterNotFoundSnafu { kafka: kafka_ref })?;
+
+            let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(client, &kafka)
+                .await
+                .context(FailedToInitializeSecurityContextSnafu)?;
+
+            let job = build_admin_job(
+                &kafka,
+                acl,
+                ACL_CONTROLLER_NAME,
+                job_name,
+                commands,
+                vec![],
+                &kafka_security,
+                &client.kubernetes_cluster_info,
+            )
+            .context(BuildAdminJobSnafu)?;
+            client
+                .apply_patch(OPERATOR_NAME, &job, &job)
+                .await
+                .context(ApplyAdminJobSnafu)?
+        }
+    };
+
+    match admin_job_state(&job) {
+        AdminJobState::Succeeded => {
+            let drift = admin_job_output(&ctx.client, &namespace, job_name)
+                .await
+                .context(ListAdminJobPodsSnafu { job_name })?
+                .map(|entries| AclDrift {
+                    // The completion time keeps the status stable while the Job exists
+                    detected_time: job
+                        .status
+                        .and_then(|status| status.completion_time)
+                        .unwrap_or_else(|| Time(Utc::now())),
+                    entries,
+                });
+            Ok((AdminJobState::Succeeded, drift))
+        }
+        state => Ok((state, None)),
+    }
+}
+
+async fn update_status(acl: &KafkaAcl, ctx: &Ctx, status: &KafkaAclStatus) -> Result<()> {
+    ctx.client
+        .apply_patch_status(OPERATOR_NAME, acl, status)
+        .await
+        .context(ApplyStatusSnafu)?;
+
+    Ok(())
+}
+
+pub fn error_policy(
+    _obj: Arc<DeserializeGuard<KafkaAcl>>,
+    error: &Error,
+    _ctx: Arc<Ctx>,
+) -> Action {
+    match error {
+        Error::InvalidKafkaAcl { .. } => Action::await_change(),
+        _ => Action::requeue(*Duration::from_secs(5)),
+    }
+}
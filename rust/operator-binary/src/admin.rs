@@ -0,0 +1,266 @@
+//! Builds the [`Job`]s that run the Kafka admin tools against a [`KafkaCluster`], which is how the
+//! resources managed inside of a cluster (e.g. topics) are applied.
+use snafu::{ResultExt, Snafu};
+use stackable_kafka_crd::{
+    admin::admin_tool_commands, security::KafkaTlsSecurity, Container, KafkaCluster, APP_NAME,
+    DOCKER_IMAGE_BASE_NAME, OPERATOR_NAME,
+};
+use stackable_operator::{
+    builder::{
+        meta::ObjectMetaBuilder,
+        pod::{
+            container::ContainerBuilder, resources::ResourceRequirementsBuilder,
+            security::PodSecurityContextBuilder, PodBuilder,
+        },
+    },
+    k8s_openapi::api::{
+        batch::v1::{Job, JobSpec},
+        core::v1::{EnvVar, Pod, PodSpec},
+    },
+    kube::{api::ListParams, Resource},
+    kvp::ObjectLabels,
+    time::Duration,
+    utils::cluster_info::KubernetesClusterInfo,
+};
+
+use crate::{
+    kafka_controller::KAFKA_UID,
+    kerberos::{self, add_kerberos_admin_job_config},
+};
+
+/// The admin jobs are short-lived, so the default certificate lifetime of the brokers suffices.
+const ADMIN_JOB_SECRET_LIFETIME: Duration = Duration::from_days_unchecked(1);
+/// Finished jobs are removed after this time. If the job failed, it is retried afterwards.
+const ADMIN_JOB_TTL_SECONDS: i32 = 300;
+/// The Job name is used as the value of the `job-name` label of its Pods, so it must be a valid
+/// label value.
+const MAX_ADMIN_JOB_NAME_LENGTH: usize = 63;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("invalid container name [{name}]"))]
+    InvalidContainerName {
+        name: String,
+        source: stackable_operator::builder::pod::container::Error,
+    },
+
+    #[snafu(display("failed to add Secret Volumes and VolumeMounts"))]
+    AddVolumesAndVolumeMounts {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to add the Kerberos configuration"))]
+    AddKerberosConfig { source: kerberos::Error },
+
+    #[snafu(display("failed to build the admin tool commands"))]
+    BuildAdminToolCommands {
+        source: stackable_kafka_crd::admin::Error,
+    },
+
+    #[snafu(display("object is missing metadata to build owner reference"))]
+    ObjectMissingMetadataForOwnerRef {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to build Metadata"))]
+    MetadataBuild {
+        source: stackable_operator::builder::meta::Error,
+    },
+}
+
+/// The state of an admin [`Job`], derived from its status.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdminJobState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Returns the name of an admin [`Job`] in the form `{owner_name}-{kind}-{suffixes}`, e.g.
+/// `orders-topic-3`. The kind prevents clashes between the Jobs of different resource kinds with
+/// the same name.
+///
+/// Names which exceed the maximum length are truncated and made unique again with a hash of the
+/// full name.
+pub fn admin_job_name(owner_name: &str, kind: &str, suffixes: &[&str]) -> String {
+    let name = [owner_name, kind]
+        .into_iter()
+        .chain(suffixes.iter().copied())
+        .collect::<Vec<_>>()
+        .join("-");
+    if name.len() <= MAX_ADMIN_JOB_NAME_LENGTH {
+        return name;
+    }
+
+    let hash = stable_hash(&name);
+    // Object names only consist of ASCII characters, so the name can be cut at any byte
+    let prefix = name[..MAX_ADMIN_JOB_NAME_LENGTH - hash.len() - 1].trim_end_matches(['-', '.']);
+    format!("{prefix}-{hash}")
+}
+
+/// Returns a hex encoded hash of the given value, which is the same across operator versions.
+pub fn stable_hash(value: &str) -> String {
+    // FNV-1a, as the hashers from std are not guaranteed to be stable across Rust releases
+    let hash = value.bytes().fold(0x811c9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    });
+    format!("{hash:08x}")
+}
+
+/// Builds a [`Job`] owned by `owner`, which runs the given admin tool commands against the
+/// INTERNAL listener of the brokers.
+#[allow(clippy::too_many_arguments)]
+pub fn build_admin_job<T: Clone + Resource<DynamicType = ()>>(
+    kafka: &KafkaCluster,
+    owner: &T,
+    controller_name: &str,
+    job_name: &str,
+    commands: &str,
+    env: Vec<EnvVar>,
+    kafka_security: &KafkaTlsSecurity,
+    cluster_info: &KubernetesClusterInfo,
+) -> Result<Job, Error> {
+    let resolved_product_image = kafka
+        .spec
+        .image
+        .resolve(DOCKER_IMAGE_BASE_NAME, crate::built_info::PKG_VERSION);
+    let recommended_labels = ObjectLabels {
+        owner,
+        app_name: APP_NAME,
+        app_version: &resolved_product_image.app_version_label,
+        operator_name: OPERATOR_NAME,
+        controller_name,
+        role: "admin",
+        role_group: "default",
+    };
+
+    let kafka_container_name = Container::Kafka.to_string();
+    let mut cb_kafka =
+        ContainerBuilder::new(&kafka_container_name).context(InvalidContainerNameSnafu {
+            name: kafka_container_name.clone(),
+        })?;
+
+    let mut pod_builder = PodBuilder::new();
+
+    kafka_security
+        .add_internal_volume_and_volume_mounts(
+            &mut pod_builder,
+            &mut cb_kafka,
+            &ADMIN_JOB_SECRET_LIFETIME,
+        )
+        .context(AddVolumesAndVolumeMountsSnafu)?;
+    add_kerberos_admin_job_config(kafka_security, kafka, &mut cb_kafka, &mut pod_builder)
+        .context(AddKerberosConfigSnafu)?;
+
+    cb_kafka
+        .image_from_product_image(&resolved_product_image)
+        .command(vec![
+            "/bin/bash".to_string(),
+            "-x".to_string(),
+            "-euo".to_string(),
+            "pipefail".to_string(),
+            "-c".to_string(),
+        ])
+        .args(vec![admin_tool_commands(
+            kafka,
+            kafka_security,
+            cluster_info,
+            commands,
+        )
+        .context(BuildAdminToolCommandsSnafu)?])
+        .add_env_vars(env)
+        .resources(
+            ResourceRequirementsBuilder::new()
+                .with_cpu_request("100m")
+                .with_cpu_limit("400m")
+                .with_memory_request("512Mi")
+                .with_memory_limit("512Mi")
+                .build(),
+        );
+
+    pod_builder
+        .metadata(
+            ObjectMetaBuilder::new()
+                .with_recommended_labels(recommended_labels.clone())
+                .context(MetadataBuildSnafu)?
+                .build(),
+        )
+        .image_pull_secrets_from_product_image(&resolved_product_image)
+        .add_container(cb_kafka.build())
+        .security_context(
+            PodSecurityContextBuilder::new()
+                .run_as_user(KAFKA_UID)
+                .run_as_group(0)
+                .fs_group(1000)
+                .build(),
+        );
+
+    let mut pod_template = pod_builder.build_template();
+    let pod_template_spec = pod_template.spec.get_or_insert_with(PodSpec::default);
+    pod_template_spec.restart_policy = Some("Never".to_string());
+
+    Ok(Job {
+        metadata: ObjectMetaBuilder::new()
+            .name_and_namespace(owner)
+            .name(job_name)
+            .ownerreference_from_resource(owner, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .with_recommended_labels(recommended_labels)
+            .context(MetadataBuildSnafu)?
+            .build(),
+        spec: Some(JobSpec {
+            template: pod_template,
+            ttl_seconds_after_finished: Some(ADMIN_JOB_TTL_SECONDS),
+            ..JobSpec::default()
+        }),
+        status: None,
+    })
+}
+
+/// Derives the [`AdminJobState`] from the status of the [`Job`].
+pub fn admin_job_state(job: &Job) -> AdminJobState {
+    let Some(status) = &job.status else {
+        return AdminJobState::Running;
+    };
+
+    if status.succeeded.unwrap_or_default() > 0 {
+        AdminJobState::Succeeded
+    } else if status
+        .conditions
+        .iter()
+        .flatten()
+        .any(|condition| condition.type_ == "Failed" && condition.status == "True")
+    {
+        AdminJobState::Failed
+    } else {
+        AdminJobState::Running
+    }
+}
+
+/// Returns the lines of the termination message of the succeeded admin Job Pod, if it still
+/// exists.
+pub async fn admin_job_output(
+    client: &stackable_operator::client::Client,
+    namespace: &str,
+    job_name: &str,
+) -> Result<Option<Vec<String>>, stackable_operator::kube::Error> {
+    let pods = client
+        .get_api::<Pod>(namespace)
+        .list(&ListParams::default().labels(&format!("job-name={job_name}")))
+        .await?;
+
+    Ok(pods
+        .into_iter()
+        .filter_map(|pod| pod.status)
+        .filter(|status| status.phase.as_deref() == Some("Succeeded"))
+        .flat_map(|status| status.container_statuses.unwrap_or_default())
+        .find_map(|container_status| container_status.state?.terminated)
+        .map(|terminated| {
+            terminated
+                .message
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }))
+}
@@ -0,0 +1,69 @@
+//! The key the brokers and controllers sign delegation tokens with. It is generated once per
+//! cluster and kept in a Secret, as all tokens become invalid if it changes.
+use snafu::{ResultExt, Snafu};
+use stackable_kafka_crd::{
+    delegation_token::DELEGATION_TOKEN_SECRET_KEY, KafkaCluster, STACKABLE_DELEGATION_TOKEN_DIR,
+};
+use stackable_operator::{
+    builder::{
+        self,
+        pod::{container::ContainerBuilder, volume::VolumeBuilder, PodBuilder},
+    },
+    commons::product_image_selection::ResolvedProductImage,
+};
+
+use crate::generated_secret::{self, create_generated_secret};
+
+const DELEGATION_TOKEN_VOLUME_NAME: &str = "delegation-token";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("failed to create the Secret with the delegation token secret key"))]
+    CreateSecret { source: generated_secret::Error },
+
+    #[snafu(display("failed to add needed volume"))]
+    AddVolume { source: builder::pod::Error },
+
+    #[snafu(display("failed to add needed volumeMount"))]
+    AddVolumeMount {
+        source: builder::pod::container::Error,
+    },
+}
+
+/// Creates the Secret with the delegation token secret key, unless it already exists. An existing
+/// key is never replaced.
+pub async fn create_secret_key_secret(
+    kafka: &KafkaCluster,
+    client: &stackable_operator::client::Client,
+    resolved_product_image: &ResolvedProductImage,
+) -> Result<(), Error> {
+    create_generated_secret(
+        kafka,
+        client,
+        resolved_product_image,
+        &kafka.delegation_token_secret_name(),
+        DELEGATION_TOKEN_SECRET_KEY,
+        "delegation-token",
+    )
+    .await
+    .context(CreateSecretSnafu)
+}
+
+/// Mounts the delegation token secret key, which is read by the `dir` config provider.
+pub fn add_delegation_token_pod_config(
+    kafka: &KafkaCluster,
+    cb_kafka: &mut ContainerBuilder,
+    pb: &mut PodBuilder,
+) -> Result<(), Error> {
+    pb.add_volume(
+        VolumeBuilder::new(DELEGATION_TOKEN_VOLUME_NAME)
+            .with_secret(kafka.delegation_token_secret_name(), false)
+            .build(),
+    )
+    .context(AddVolumeSnafu)?;
+    cb_kafka
+        .add_volume_mount(DELEGATION_TOKEN_VOLUME_NAME, STACKABLE_DELEGATION_TOKEN_DIR)
+        .context(AddVolumeMountSnafu)?;
+
+    Ok(())
+}
@@ -0,0 +1,491 @@
+//! Issues the delegation token declared by a [`KafkaDelegationToken`] in the referenced
+//! [`KafkaCluster`] and writes it to a [`Secret`]
+//!
+//! A token is issued by an admin Job, which reports it in its termination message. A new token is
+//! issued after half of the lifetime of the current one, the previous token stays valid until it
+//! expires.
+use std::{collections::BTreeMap, sync::Arc};
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{
+    admin::{SyncState, SyncStatus},
+    delegation_token::{
+        self, DelegationToken, KafkaDelegationToken, KafkaDelegationTokenStatus, HMAC_SECRET_KEY,
+        TOKEN_ID_SECRET_KEY,
+    },
+    listener::KafkaClientAuthentication,
+    security::KafkaTlsSecurity,
+    user::{
+        BOOTSTRAP_SERVERS_SECRET_KEY, SASL_JAAS_CONFIG_SECRET_KEY, SASL_MECHANISM_SECRET_KEY,
+        SCRAM_MECHANISM,
+    },
+    KafkaCluster, APP_NAME, DOCKER_IMAGE_BASE_NAME, OPERATOR_NAME,
+};
+use stackable_operator::{
+    builder::meta::ObjectMetaBuilder,
+    k8s_openapi::{
+        api::{
+            batch::v1::Job,
+            core::v1::{ConfigMap, Secret},
+        },
+        apimachinery::pkg::apis::meta::v1::Time,
+        chrono::{self, DateTime, Utc},
+        ByteString,
+    },
+    kube::{
+        api::DynamicObject,
+        core::{error_boundary, DeserializeGuard},
+        runtime::{controller::Action, reflector::ObjectRef},
+        ResourceExt,
+    },
+    kvp::ObjectLabels,
+    logging::controller::ReconcilerError,
+    time::Duration,
+};
+use strum::{EnumDiscriminants, IntoStaticStr};
+
+use crate::{
+    admin::{
+        self, admin_job_name, admin_job_output, admin_job_state, build_admin_job, AdminJobState,
+    },
+    utils::is_owned_by,
+};
+
+pub const DELEGATION_TOKEN_CONTROLLER_NAME: &str = "kafkadelegationtoken";
+
+pub struct Ctx {
+    pub client: stackable_operator::client::Client,
+}
+
+#[derive(Snafu, Debug, EnumDiscriminants)]
+#[strum_discriminants(derive(IntoStaticStr))]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("KafkaDelegationToken object is invalid"))]
+    InvalidKafkaDelegationToken {
+        source: error_boundary::InvalidObject,
+    },
+
+    #[snafu(display("invalid delegation token"))]
+    InvalidDelegationToken { source: delegation_token::Error },
+
+    #[snafu(display("the owner of the token is refused by {kafka}"))]
+    OwnerRefused {
+        source: delegation_token::Error,
+        kafka: ObjectRef<KafkaCluster>,
+    },
+
+    #[snafu(display("failed to retrieve {kafka}"))]
+    RetrieveKafkaCluster {
+        source: stackable_operator::client::Error,
+        kafka: ObjectRef<KafkaCluster>,
+    },
+
+    #[snafu(display("{kafka} not found"))]
+    KafkaClusterNotFound { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display(
+        "{kafka} issues no delegation tokens, which requires a Kerberos or Static AuthenticationClass"
+    ))]
+    DelegationTokensDisabled { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display(
+        "{kafka} does not authenticate the admin Jobs, which requires internal TLS or Kerberos between the brokers"
+    ))]
+    AdminJobNotAuthenticated { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display("failed to retrieve discovery {config_map}"))]
+    RetrieveDiscoveryConfigMap {
+        source: stackable_operator::client::Error,
+        config_map: ObjectRef<ConfigMap>,
+    },
+
+    #[snafu(display("failed to retrieve {secret}"))]
+    RetrieveSecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display(
+        "{secret} already exists and is not owned by the KafkaDelegationToken, choose another secretName"
+    ))]
+    SecretNotOwned { secret: ObjectRef<Secret> },
+
+    #[snafu(display("object is missing metadata to build owner reference"))]
+    ObjectMissingMetadataForOwnerRef {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to build Metadata"))]
+    MetadataBuild {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to apply {secret}"))]
+    ApplySecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("failed to initialize security context"))]
+    FailedToInitializeSecurityContext {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to build admin Job"))]
+    BuildAdminJob { source: admin::Error },
+
+    #[snafu(display("failed to apply admin Job"))]
+    ApplyAdminJob {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("failed to list the Pods of admin Job [{job_name}]"))]
+    ListAdminJobPods {
+        source: stackable_operator::kube::Error,
+        job_name: String,
+    },
+
+    #[snafu(display("failed to update status"))]
+    ApplyStatus {
+        source: stackable_operator::client::Error,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl ReconcilerError for Error {
+    fn category(&self) -> &'static str {
+        ErrorDiscriminants::from(self).into()
+    }
+
+    fn secondary_object(&self) -> Option<ObjectRef<DynamicObject>> {
+        match self {
+            Error::ObjectHasNoNamespace => None,
+            Error::InvalidKafkaDelegationToken { .. } => None,
+            Error::InvalidDelegationToken { .. } => None,
+            Error::OwnerRefused { kafka, .. } => Some(kafka.clone().erase()),
+            Error::RetrieveKafkaCluster { kafka, .. } => Some(kafka.clone().erase()),
+            Error::KafkaClusterNotFound { kafka } => Some(kafka.clone().erase()),
+            Error::DelegationTokensDisabled { kafka } => Some(kafka.clone().erase()),
+            Error::AdminJobNotAuthenticated { kafka } => Some(kafka.clone().erase()),
+            Error::RetrieveDiscoveryConfigMap { config_map, .. } => {
+                Some(config_map.clone().erase())
+            }
+            Error::RetrieveSecret { secret, .. } => Some(secret.clone().erase()),
+            Error::SecretNotOwned { secret } => Some(secret.clone().erase()),
+            Error::ObjectMissingMetadataForOwnerRef { .. } => None,
+            Error::MetadataBuild { .. } => None,
+            Error::ApplySecret { secret, .. } => Some(secret.clone().erase()),
+            Error::FailedToInitializeSecurityContext { .. } => None,
+            Error::BuildAdminJob { .. } => None,
+            Error::ApplyAdminJob { .. } => None,
+            Error::ListAdminJobPods { .. } => None,
+            Error::ApplyStatus { .. } => None,
+        }
+    }
+}
+
+/// The outcome of a sync of a [`KafkaDelegationToken`].
+enum TokenSync {
+    /// The token in the Secret is current, a new one is issued at the given time.
+    Valid { reissue_time: DateTime<Utc> },
+    /// A new token was issued and written to the Secret.
+    Issued { token_id: String },
+    /// The admin Job which issues a new token is still running.
+    Pending,
+    /// The admin Job failed, it is retried once it is removed after its TTL.
+    Failed { job_name: String },
+}
+
+pub async fn reconcile_delegation_token(
+    token: Arc<DeserializeGuard<KafkaDelegationToken>>,
+    ctx: Arc<Ctx>,
+) -> Result<Action> {
+    tracing::info!("Starting reconcile");
+
+    let token = token
+        .0
+        .as_ref()
+        .map_err(error_boundary::InvalidObject::clone)
+        .context(InvalidKafkaDelegationTokenSnafu)?;
+
+    let result = sync_token(token, &ctx).await;
+
+    let mut status = token.status.clone().unwrap_or_default();
+    let (sync_state, message) = match &result {
+        Ok(TokenSync::Valid { .. }) => (SyncState::Synced, None),
+        Ok(TokenSync::Issued { token_id }) => {
+            status.token_id = Some(token_id.clone());
+            status.issued_time = Some(Time(Utc::now()));
+            status.issued_tokens += 1;
+            (SyncState::Synced, None)
+        }
+        Ok(TokenSync::Pending) => (SyncState::Pending, None),
+        Ok(TokenSync::Failed { job_name }) => (
+            SyncState::Failed,
+            Some(format!(
+                "the admin Job [{job_name}] failed, check its logs for details"
+            )),
+        ),
+        Err(err) => (SyncState::Failed, Some(err.to_string())),
+    };
+    status.sync = SyncStatus::new(
+        sync_state,
+        token.metadata.generation,
+        message,
+        token.status.as_ref().map(|status| &status.sync),
+    );
+    ctx.client
+        .apply_patch_status(OPERATOR_NAME, token, &status)
+        .await
+        .context(ApplyStatusSnafu)?;
+
+    match result {
+        // The status was updated, which triggers the next reconcile
+        Ok(TokenSync::Issued { .. }) => Ok(Action::await_change()),
+        Ok(TokenSync::Valid { reissue_time }) => Ok(Action::requeue(
+            (reissue_time - Utc::now()).to_std().unwrap_or_default(),
+        )),
+        Ok(TokenSync::Pending | TokenSync::Failed { .. }) => Ok(Action::await_change()),
+        // Retrying does not help, the spec has to be changed
+        Err(Error::InvalidDelegationToken { .. }) => Ok(Action::await_change()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Issues a new token if there is no valid one for the current generation of the spec in the
+/// Secret or if half of its lifetime has passed.
+async fn sync_token(token: &KafkaDelegationToken, ctx: &Ctx) -> Result<TokenSync> {
+    let client = &ctx.client;
+    let namespace = token.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let lifetime = token.lifetime().context(InvalidDelegationTokenSnafu)?;
+    let commands = token
+        .create_commands()
+        .context(InvalidDelegationTokenSnafu)?;
+
+    let secret_name = token.secret_name();
+    let secret_ref = ObjectRef::<Secret>::new(&secret_name).within(&namespace);
+    let existing_secret = client
+        .get_opt::<Secret>(&secret_name, &namespace)
+        .await
+        .with_context(|_| RetrieveSecretSnafu {
+            secret: secret_ref.clone(),
+        })?;
+    // Otherwise a token could be written into the Secret of another application
+    if let Some(existing_secret) = &existing_secret {
+        ensure!(
+            is_owned_by(existing_secret, token),
+            SecretNotOwnedSnafu {
+                secret: secret_ref.clone(),
+            }
+        );
+    }
+    let secret_token_id = existing_secret
+        .and_then(|secret| secret.data)
+        .and_then(|mut data| data.remove(TOKEN_ID_SECRET_KEY))
+        .and_then(|token_id| String::from_utf8(token_id.0).ok());
+
+    if let Some(status) = token.status.as_ref().filter(|status| {
+        status.sync.sync_state == SyncState::Synced
+            && status.sync.observed_generation == token.metadata.generation
+            && status.token_id.is_some()
+            && status.token_id == secret_token_id
+    }) {
+        if let Some(reissue_time) = reissue_time(status, lifetime) {
+            if reissue_time > Utc::now() {
+                return Ok(TokenSync::Valid { reissue_time });
+            }
+        }
+    }
+
+    let kafka = get_kafka_cluster(token, ctx).await?;
+    let kafka_ref = ObjectRef::from_obj(&kafka);
+    // Not an invalid spec, as the owner can be allowed in the KafkaCluster later on
+    token
+        .validate_owner(&kafka)
+        .with_context(|_| OwnerRefusedSnafu {
+            kafka: kafka_ref.clone(),
+        })?;
+    let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(client, &kafka)
+        .await
+        .context(FailedToInitializeSecurityContextSnafu)?;
+    ensure!(
+        kafka_security.has_delegation_tokens_enabled(),
+        DelegationTokensDisabledSnafu {
+            kafka: kafka_ref.clone()
+        }
+    );
+    // Kafka refuses to issue tokens on connections without client authentication
+    ensure!(
+        kafka_security.tls_internal_secret_class().is_some()
+            || kafka_security.has_kerberos_inter_broker_enabled(),
+        AdminJobNotAuthenticatedSnafu { kafka: kafka_ref }
+    );
+
+    // Every issued token gets a new Job, the Job of the previous one may still exist.
+    let job_name = admin_job_name(
+        &token.name_any(),
+        "delegation-token",
+        &[
+            &token.metadata.generation.unwrap_or_default().to_string(),
+            &token
+                .status
+                .as_ref()
+                .map(|status| status.issued_tokens)
+                .unwrap_or_default()
+                .to_string(),
+        ],
+    );
+    let job = build_admin_job(
+        &kafka,
+        token,
+        DELEGATION_TOKEN_CONTROLLER_NAME,
+        &job_name,
+        &commands,
+        vec![],
+        &kafka_security,
+        &client.kubernetes_cluster_info,
+    )
+    .context(BuildAdminJobSnafu)?;
+    let job: Job = client
+        .apply_patch(OPERATOR_NAME, &job, &job)
+        .await
+        .context(ApplyAdminJobSnafu)?;
+
+    match admin_job_state(&job) {
+        AdminJobState::Succeeded => {}
+        AdminJobState::Running => return Ok(TokenSync::Pending),
+        AdminJobState::Failed => return Ok(TokenSync::Failed { job_name }),
+    }
+    // The output is gone if the Job Pod was removed, the Job is then recreated after its TTL
+    let Some(issued) = admin_job_output(client, &namespace, &job_name)
+        .await
+        .with_context(|_| ListAdminJobPodsSnafu {
+            job_name: &job_name,
+        })?
+        .as_deref()
+        .and_then(DelegationToken::from_output)
+    else {
+        return Ok(TokenSync::Pending);
+    };
+
+    let mut data = BTreeMap::from([
+        (TOKEN_ID_SECRET_KEY.to_string(), issued.token_id.clone()),
+        (HMAC_SECRET_KEY.to_string(), issued.hmac.clone()),
+        (
+            SASL_MECHANISM_SECRET_KEY.to_string(),
+            SCRAM_MECHANISM.to_string(),
+        ),
+        (
+            SASL_JAAS_CONFIG_SECRET_KEY.to_string(),
+            issued.jaas_config(),
+        ),
+    ]);
+    if let Some(bootstrap_servers) = bootstrap_servers(&kafka, &kafka_security, ctx).await? {
+        data.insert(BOOTSTRAP_SERVERS_SECRET_KEY.to_string(), bootstrap_servers);
+    }
+
+    let resolved_product_image = kafka
+        .spec
+        .image
+        .resolve(DOCKER_IMAGE_BASE_NAME, crate::built_info::PKG_VERSION);
+    let secret = Secret {
+        metadata: ObjectMetaBuilder::new()
+            .name_and_namespace(token)
+            .name(&secret_name)
+            .ownerreference_from_resource(token, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .with_recommended_labels(ObjectLabels {
+                owner: token,
+                app_name: APP_NAME,
+                app_version: &resolved_product_image.app_version_label,
+                operator_name: OPERATOR_NAME,
+                controller_name: DELEGATION_TOKEN_CONTROLLER_NAME,
+                role: "delegation-token",
+                role_group: "default",
+            })
+            .context(MetadataBuildSnafu)?
+            .build(),
+        data: Some(
+            data.into_iter()
+                .map(|(key, value)| (key, ByteString(value.into_bytes())))
+                .collect(),
+        ),
+        ..Secret::default()
+    };
+    client
+        .apply_patch(OPERATOR_NAME, &secret, &secret)
+        .await
+        .context(ApplySecretSnafu { secret: secret_ref })?;
+
+    Ok(TokenSync::Issued {
+        token_id: issued.token_id,
+    })
+}
+
+/// A new token is issued after half of the lifetime of the current one, so that clients have
+/// enough time to pick it up.
+fn reissue_time(status: &KafkaDelegationTokenStatus, lifetime: Duration) -> Option<DateTime<Utc>> {
+    let issued_time = status.issued_time.as_ref()?;
+    Some(issued_time.0 + chrono::Duration::from_std(*lifetime / 2).ok()?)
+}
+
+async fn get_kafka_cluster(token: &KafkaDelegationToken, ctx: &Ctx) -> Result<KafkaCluster> {
+    let namespace = token.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let kafka_ref = ObjectRef::<KafkaCluster>::new(&token.spec.cluster_ref.name).within(&namespace);
+
+    ctx.client
+        .get_opt::<KafkaCluster>(&token.spec.cluster_ref.name, &namespace)
+        .await
+        .with_context(|_| RetrieveKafkaClusterSnafu {
+            kafka: kafka_ref.clone(),
+        })?
+        .with_context(|| KafkaClusterNotFoundSnafu { kafka: kafka_ref })
+}
+
+/// The bootstrap servers from the discovery ConfigMap of the listener which serves SCRAM or,
+/// if there is none, Kerberos. Both accept logins with delegation tokens.
+async fn bootstrap_servers(
+    kafka: &KafkaCluster,
+    kafka_security: &KafkaTlsSecurity,
+    ctx: &Ctx,
+) -> Result<Option<String>> {
+    let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let client_listeners = kafka_security.client_listeners();
+    let config_map_name = [
+        KafkaClientAuthentication::Scram,
+        KafkaClientAuthentication::Kerberos,
+    ]
+    .into_iter()
+    .find_map(|authentication| {
+        client_listeners
+            .iter()
+            .find(|client_listener| client_listener.authentication == Some(authentication))
+    })
+    .map(|client_listener| client_listener.discovery_config_map_name(&kafka.name_any()))
+    .unwrap_or_else(|| kafka.name_any());
+    let config_map_ref = ObjectRef::<ConfigMap>::new(&config_map_name).within(&namespace);
+
+    Ok(ctx
+        .client
+        .get_opt::<ConfigMap>(&config_map_name, &namespace)
+        .await
+        .context(RetrieveDiscoveryConfigMapSnafu {
+            config_map: config_map_ref,
+        })?
+        .and_then(|config_map| config_map.data)
+        .and_then(|mut data| data.remove("KAFKA")))
+}
+
+pub fn error_policy(
+    _obj: Arc<DeserializeGuard<KafkaDelegationToken>>,
+    error: &Error,
+    _ctx: Arc<Ctx>,
+) -> Action {
+    match error {
+        Error::InvalidKafkaDelegationToken { .. } => Action::await_change(),
+        _ => Action::requeue(*Duration::from_secs(5)),
+    }
+}
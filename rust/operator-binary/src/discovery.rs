@@ -2,14 +2,18 @@ use crate::utils::build_recommended_labels;
 use crate::KAFKA_CONTROLLER_NAME;
 
 use snafu::{OptionExt, ResultExt, Snafu};
-use stackable_kafka_crd::{security::KafkaTlsSecurity, KafkaCluster, KafkaRole};
+use stackable_kafka_crd::{
+    listener::{KafkaClientListener, KafkaListenerName},
+    security::KafkaTlsSecurity,
+    KafkaCluster, KafkaRole,
+};
 use stackable_operator::{
     builder::{configmap::ConfigMapBuilder, meta::ObjectMetaBuilder},
     commons::{listener::Listener, product_image_selection::ResolvedProductImage},
     k8s_openapi::api::core::v1::{ConfigMap, Service},
     kube::{runtime::reflector::ObjectRef, Resource, ResourceExt},
 };
-use std::num::TryFromIntError;
+use std::{collections::BTreeMap, num::TryFromIntError};
 
 #[derive(Snafu, Debug)]
 pub enum Error {
@@ -52,38 +56,51 @@ pub enum Error {
 }
 
 /// Builds discovery [`ConfigMap`]s for connecting to a [`KafkaCluster`] for all expected scenarios
+///
+/// Every exposed client listener gets its own discovery [`ConfigMap`], `listeners` contains the
+/// bootstrap [`Listener`]s of all broker role groups per client listener. Besides the bootstrap
+/// servers, it contains the security protocol of the listener and, with TLS authentication,
+/// whether clients may connect without a certificate.
 pub async fn build_discovery_configmaps(
     kafka: &KafkaCluster,
     owner: &impl Resource<DynamicType = ()>,
     resolved_product_image: &ResolvedProductImage,
     kafka_security: &KafkaTlsSecurity,
-    listeners: &[Listener],
+    listeners: &BTreeMap<KafkaListenerName, Vec<Listener>>,
 ) -> Result<Vec<ConfigMap>, Error> {
     let name = owner.name_unchecked();
-    let port_name = if kafka_security.has_kerberos_enabled() {
-        kafka_security.bootstrap_port_name()
-    } else {
-        kafka_security.client_port_name()
-    };
-    Ok(vec![
-        build_discovery_configmap(
+    let mut discovery_configmaps = Vec::new();
+    for client_listener in kafka_security.exposed_client_listeners() {
+        let bootstrap_listeners = listeners
+            .get(&client_listener.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let port_name = client_listener.bootstrap_port_name();
+        discovery_configmaps.push(build_discovery_configmap(
             kafka,
             owner,
             resolved_product_image,
-            &name,
-            listener_hosts(listeners, port_name)?,
-        )?,
-        // backwards compat: nodeport service is now the same as the main service, access type
-        // is determined by the listenerclass.
-        // do we want to deprecate/remove this?
-        build_discovery_configmap(
-            kafka,
-            owner,
-            resolved_product_image,
-            &format!("{name}-nodeport"),
-            listener_hosts(listeners, port_name)?,
-        )?,
-    ])
+            &client_listener.discovery_config_map_name(&name),
+            listener_hosts(bootstrap_listeners, port_name)?,
+            kafka_security,
+            &client_listener,
+        )?);
+        if client_listener.dedicated.is_none() {
+            // backwards compat: nodeport service is now the same as the main service, access type
+            // is determined by the listenerclass.
+            // do we want to deprecate/remove this?
+            discovery_configmaps.push(build_discovery_configmap(
+                kafka,
+                owner,
+                resolved_product_image,
+                &format!("{name}-nodeport"),
+                listener_hosts(bootstrap_listeners, port_name)?,
+                kafka_security,
+                &client_listener,
+            )?);
+        }
+    }
+    Ok(discovery_configmaps)
 }
 
 /// Build a discovery [`ConfigMap`] containing information about how to connect to a certain [`KafkaCluster`]
@@ -95,6 +112,8 @@ fn build_discovery_configmap(
     resolved_product_image: &ResolvedProductImage,
     name: &str,
     hosts: impl IntoIterator<Item = (impl Into<String>, u16)>,
+    kafka_security: &KafkaTlsSecurity,
+    client_listener: &KafkaClientListener,
 ) -> Result<ConfigMap, Error> {
     // Write a list of bootstrap servers in the format that Kafka clients:
     // "{host1}:{port1},{host2:port2},..."
@@ -103,7 +122,8 @@ fn build_discovery_configmap(
         .map(|(host, port)| format!("{}:{}", host.into(), port))
         .collect::<Vec<_>>()
         .join(",");
-    ConfigMapBuilder::new()
+    let mut cm_builder = ConfigMapBuilder::new();
+    cm_builder
         .metadata(
             ObjectMetaBuilder::new()
                 .name_and_namespace(kafka)
@@ -123,8 +143,15 @@ fn build_discovery_configmap(
                 .build(),
         )
         .add_data("KAFKA", bootstrap_servers)
-        .build()
-        .context(BuildConfigMapSnafu)
+        .add_data(
+            "KAFKA_SECURITY_PROTOCOL",
+            client_listener.protocol.to_string(),
+        );
+    // With `Requested`, clients without a certificate can connect to the same bootstrap servers
+    if let Some(client_auth) = kafka_security.ssl_client_auth(client_listener) {
+        cm_builder.add_data("KAFKA_SSL_CLIENT_AUTH", client_auth.to_string());
+    }
+    cm_builder.build().context(BuildConfigMapSnafu)
 }
 
 fn listener_hosts(
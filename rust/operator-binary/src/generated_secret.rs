@@ -0,0 +1,110 @@
+//! Secrets with a value generated by the operator, e.g. a key or a password. The value is generated
+//! once per cluster, as the brokers and clients depend on it staying the same.
+use std::collections::BTreeMap;
+
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{KafkaCluster, OPERATOR_NAME};
+use stackable_operator::{
+    builder::meta::ObjectMetaBuilder,
+    commons::product_image_selection::ResolvedProductImage,
+    k8s_openapi::{api::core::v1::Secret, ByteString},
+    kube::{runtime::reflector::ObjectRef, ResourceExt},
+};
+
+use crate::{
+    kafka_controller::KAFKA_CONTROLLER_NAME,
+    utils::{build_recommended_labels, generate_password},
+};
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("failed to retrieve {secret}"))]
+    RetrieveSecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("failed to apply {secret}"))]
+    ApplySecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("failed to generate the value of {secret}"))]
+    GenerateValue {
+        source: std::io::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("object is missing metadata to build owner reference"))]
+    ObjectMissingMetadataForOwnerRef {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to build Metadata"))]
+    MetadataBuild {
+        source: stackable_operator::builder::meta::Error,
+    },
+}
+
+/// Creates the Secret with a generated value in `key`, unless it already exists. An existing
+/// value is never replaced.
+///
+/// The Secret is not a cluster resource, so that the value is kept if the feature which requires it
+/// is disabled temporarily. It is deleted together with the cluster.
+pub async fn create_generated_secret(
+    kafka: &KafkaCluster,
+    client: &stackable_operator::client::Client,
+    resolved_product_image: &ResolvedProductImage,
+    secret_name: &str,
+    key: &str,
+    role: &str,
+) -> Result<(), Error> {
+    let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let secret_ref = ObjectRef::<Secret>::new(secret_name).within(&namespace);
+
+    if client
+        .get_opt::<Secret>(secret_name, &namespace)
+        .await
+        .with_context(|_| RetrieveSecretSnafu {
+            secret: secret_ref.clone(),
+        })?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let value = generate_password().with_context(|_| GenerateValueSnafu {
+        secret: secret_ref.clone(),
+    })?;
+    let secret = Secret {
+        metadata: ObjectMetaBuilder::new()
+            .name_and_namespace(kafka)
+            .name(secret_name)
+            .ownerreference_from_resource(kafka, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .with_recommended_labels(build_recommended_labels(
+                kafka,
+                KAFKA_CONTROLLER_NAME,
+                &resolved_product_image.app_version_label,
+                role,
+                "global",
+            ))
+            .context(MetadataBuildSnafu)?
+            .build(),
+        data: Some(BTreeMap::from([(
+            key.to_string(),
+            ByteString(value.into_bytes()),
+        )])),
+        ..Secret::default()
+    };
+    client
+        .apply_patch(OPERATOR_NAME, &secret, &secret)
+        .await
+        .context(ApplySecretSnafu { secret: secret_ref })?;
+
+    Ok(())
+}
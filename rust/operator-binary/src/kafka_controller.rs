@@ -10,16 +10,25 @@ use product_config::{
     writer::{to_java_properties_string, PropertiesWriterError},
     ProductConfigManager,
 };
-use snafu::{OptionExt, ResultExt, Snafu};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use stackable_kafka_crd::{
-    listener::{get_kafka_listener_config, pod_fqdn, KafkaListenerError},
+    acl::{KafkaAcl, KafkaAclSpec},
+    admin::KafkaClusterRef,
+    kraft::{self, ZkMigrationState},
+    listener::{
+        get_kafka_controller_listener_config, get_kafka_listener_config, pod_fqdn,
+        KafkaClientAuthentication, KafkaClientListener, KafkaListenerError, KafkaListenerName,
+    },
+    rack_awareness::{self, RACK_VOLUME_NAME, STACKABLE_RACK_DIR},
     security::KafkaTlsSecurity,
+    tls::{KafkaTlsStoreFormat, TLS_STORE_PASSWORD_SECRET_KEY},
+    user::{self, PASSWORD_ENV, PASSWORD_SECRET_KEY},
+    zookeeper::{self, zookeeper_jaas_config},
     Container, KafkaCluster, KafkaClusterStatus, KafkaConfig, KafkaRole, APP_NAME,
     DOCKER_IMAGE_BASE_NAME, JVM_SECURITY_PROPERTIES_FILE, KAFKA_HEAP_OPTS,
-    LISTENER_BOOTSTRAP_VOLUME_NAME, LISTENER_BROKER_VOLUME_NAME, LOG_DIRS_VOLUME_NAME,
-    METRICS_PORT, METRICS_PORT_NAME, OPERATOR_NAME, SERVER_PROPERTIES_FILE, STACKABLE_CONFIG_DIR,
-    STACKABLE_DATA_DIR, STACKABLE_LISTENER_BOOTSTRAP_DIR, STACKABLE_LISTENER_BROKER_DIR,
-    STACKABLE_LOG_CONFIG_DIR, STACKABLE_LOG_DIR,
+    KRB5_TRUSTED_REALMS_FILE, LISTENER_BOOTSTRAP_VOLUME_NAME, LOG_DIRS_VOLUME_NAME, METRICS_PORT,
+    METRICS_PORT_NAME, OPERATOR_NAME, SERVER_PROPERTIES_FILE, STACKABLE_CONFIG_DIR,
+    STACKABLE_DATA_DIR, STACKABLE_LOG_CONFIG_DIR, STACKABLE_LOG_DIR, ZOOKEEPER_JAAS_CONFIG_FILE,
 };
 use stackable_operator::{
     builder::{
@@ -47,12 +56,13 @@ use stackable_operator::{
             apps::v1::{StatefulSet, StatefulSetSpec},
             core::v1::{
                 ConfigMap, ConfigMapKeySelector, ConfigMapVolumeSource, ContainerPort, EnvVar,
-                EnvVarSource, ExecAction, ObjectFieldSelector, PodSpec, Probe, Service,
-                ServiceAccount, ServiceSpec, Volume,
+                EnvVarSource, ExecAction, ObjectFieldSelector, PersistentVolumeClaim, PodSpec,
+                PodTemplateSpec, Probe, Secret, SecretKeySelector, Service, ServiceAccount,
+                ServiceSpec, TCPSocketAction, Volume,
             },
         },
-        apimachinery::pkg::apis::meta::v1::LabelSelector,
-        DeepMerge,
+        apimachinery::pkg::{apis::meta::v1::LabelSelector, util::intstr::IntOrString},
+        http, DeepMerge,
     },
     kube::{
         api::DynamicObject,
@@ -80,11 +90,15 @@ use stackable_operator::{
     time::Duration,
     utils::cluster_info::KubernetesClusterInfo,
 };
-use strum::{EnumDiscriminants, IntoStaticStr};
+use strum::{EnumDiscriminants, IntoEnumIterator, IntoStaticStr};
 
 use crate::{
+    admin::{self, admin_job_name, admin_job_state, build_admin_job, AdminJobState},
+    delegation_token::{self, add_delegation_token_pod_config, create_secret_key_secret},
     discovery::{self, build_discovery_configmaps},
+    generated_secret::{self, create_generated_secret},
     kerberos::{self, add_kerberos_pod_config},
+    ldap::{self, add_ldap_pod_config},
     operations::{
         graceful_shutdown::{add_graceful_shutdown_config, graceful_shutdown_config_properties},
         pdb::add_pdbs,
@@ -93,6 +107,7 @@ use crate::{
         extend_role_group_config_map, resolve_vector_aggregator_address, LOG4J_CONFIG_FILE,
         MAX_KAFKA_LOG_FILES_SIZE,
     },
+    rack_awareness::annotate_broker_pods,
     utils::build_recommended_labels,
 };
 
@@ -122,6 +137,57 @@ pub enum Error {
     #[snafu(display("object defines no broker role"))]
     NoBrokerRole,
 
+    #[snafu(display("invalid metadata quorum configuration"))]
+    InvalidMetadataQuorum { source: stackable_kafka_crd::Error },
+
+    #[snafu(display("failed to build the KRaft configuration"))]
+    KraftConfig { source: kraft::Error },
+
+    #[snafu(display("failed to list the KRaft controller Pods"))]
+    ListControllerPods { source: stackable_kafka_crd::Error },
+
+    #[snafu(display("failed to build the request for the metrics of the KRaft controllers"))]
+    BuildControllerMetricsRequest { source: http::Error },
+
+    #[snafu(display("failed to build the ZooKeeper JAAS configuration"))]
+    ZookeeperJaasConfig { source: zookeeper::Error },
+
+    #[snafu(display("invalid authorization configuration"))]
+    InvalidAuthorization { source: stackable_kafka_crd::Error },
+
+    #[snafu(display("failed to apply the KafkaAcl of the cluster"))]
+    ApplyClusterAcl {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("failed to delete the KafkaAcl of the cluster"))]
+    DeleteClusterAcl {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("failed to retrieve the user credentials Secret {secret}"))]
+    GetScramCredentials {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("invalid user in the user credentials Secret {secret}"))]
+    InvalidScramUser {
+        source: user::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("failed to create the password of the SCRAM prober user"))]
+    CreateScramProberPassword { source: generated_secret::Error },
+
+    #[snafu(display("failed to build the Job bootstrapping the SCRAM credentials"))]
+    BuildScramCredentialsJob { source: admin::Error },
+
+    #[snafu(display("failed to apply the Job bootstrapping the SCRAM credentials"))]
+    ApplyScramCredentialsJob {
+        source: stackable_operator::cluster_resources::Error,
+    },
+
     #[snafu(display("failed to apply role Service"))]
     ApplyRoleService {
         source: stackable_operator::cluster_resources::Error,
@@ -336,11 +402,46 @@ pub enum Error {
     #[snafu(display("failed to add kerberos config"))]
     AddKerberosConfig { source: kerberos::Error },
 
+    #[snafu(display("failed to add LDAP config"))]
+    AddLdapConfig { source: ldap::Error },
+
+    #[snafu(display("failed to create the delegation token secret key"))]
+    CreateDelegationTokenSecretKey { source: delegation_token::Error },
+
+    #[snafu(display("failed to add the delegation token config"))]
+    AddDelegationTokenConfig { source: delegation_token::Error },
+
+    #[snafu(display("failed to create the TLS store password"))]
+    CreateTlsStorePassword { source: generated_secret::Error },
+
+    #[snafu(display("failed to create the password of the internal super user"))]
+    CreateInternalCredentials { source: generated_secret::Error },
+
     #[snafu(display("failed to validate authentication method"))]
     FailedToValidateAuthenticationMethod {
         source: stackable_kafka_crd::security::Error,
     },
 
+    #[snafu(display("failed to validate the listeners"))]
+    FailedToValidateListeners {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to annotate the broker Pods with their rack"))]
+    AnnotateRack {
+        source: crate::rack_awareness::Error,
+    },
+
+    #[snafu(display(
+        "failed to annotate {count} broker Pod(s) with their rack, see the operator log for details"
+    ))]
+    AnnotateRackPods { count: usize },
+
+    #[snafu(display("failed to build the security settings"))]
+    BuildSecurityConfig {
+        source: stackable_kafka_crd::security::Error,
+    },
+
     #[snafu(display("failed to add needed volume"))]
     AddVolume { source: builder::pod::Error },
 
@@ -370,6 +471,19 @@ impl ReconcilerError for Error {
             Error::ObjectHasNoName => None,
             Error::ObjectHasNoNamespace => None,
             Error::NoBrokerRole => None,
+            Error::InvalidMetadataQuorum { .. } => None,
+            Error::KraftConfig { .. } => None,
+            Error::ListControllerPods { .. } => None,
+            Error::BuildControllerMetricsRequest { .. } => None,
+            Error::ZookeeperJaasConfig { .. } => None,
+            Error::InvalidAuthorization { .. } => None,
+            Error::ApplyClusterAcl { .. } => None,
+            Error::DeleteClusterAcl { .. } => None,
+            Error::GetScramCredentials { secret, .. } => Some(secret.clone().erase()),
+            Error::InvalidScramUser { secret, .. } => Some(secret.clone().erase()),
+            Error::CreateScramProberPassword { .. } => None,
+            Error::BuildScramCredentialsJob { .. } => None,
+            Error::ApplyScramCredentialsJob { .. } => None,
             Error::ApplyRoleService { .. } => None,
             Error::ApplyRoleServiceAccount { .. } => None,
             Error::ApplyRoleRoleBinding { .. } => None,
@@ -398,6 +512,7 @@ impl ReconcilerError for Error {
             Error::InvalidContainerName { .. } => None,
             Error::DeleteOrphans { .. } => None,
             Error::FailedToInitializeSecurityContext { .. } => None,
+            Error::BuildSecurityConfig { .. } => None,
             Error::InvalidHeapConfig { .. } => None,
             Error::CreateClusterResources { .. } => None,
             Error::FailedToResolveConfig { .. } => None,
@@ -420,7 +535,15 @@ impl ReconcilerError for Error {
             Error::AddVolumeMount { .. } => None,
             Error::ResolveNamespace { .. } => None,
             Error::AddKerberosConfig { .. } => None,
+            Error::AddLdapConfig { .. } => None,
+            Error::CreateDelegationTokenSecretKey { .. } => None,
+            Error::AddDelegationTokenConfig { .. } => None,
+            Error::CreateTlsStorePassword { .. } => None,
+            Error::CreateInternalCredentials { .. } => None,
             Error::FailedToValidateAuthenticationMethod { .. } => None,
+            Error::FailedToValidateListeners { .. } => None,
+            Error::AnnotateRack { .. } => None,
+            Error::AnnotateRackPods { .. } => None,
             Error::InvalidKafkaCluster { .. } => None,
         }
     }
@@ -439,7 +562,18 @@ pub async fn reconcile_kafka(
         .context(InvalidKafkaClusterSnafu)?;
 
     let client = &ctx.client;
-    let kafka_role = KafkaRole::Broker;
+
+    kafka
+        .validate_metadata_quorum()
+        .context(InvalidMetadataQuorumSnafu)?;
+    kafka
+        .validate_authorization()
+        .context(InvalidAuthorizationSnafu)?;
+    let kraft_cluster_id = if kafka.metadata_mode(&KafkaRole::Broker).is_kraft() {
+        Some(kraft::cluster_id(kafka).context(KraftConfigSnafu)?)
+    } else {
+        None
+    };
 
     let resolved_product_image = kafka
         .spec
@@ -455,33 +589,47 @@ pub async fn reconcile_kafka(
     )
     .context(CreateClusterResourcesSnafu)?;
 
+    let config_property_kinds = vec![
+        PropertyNameKind::File(SERVER_PROPERTIES_FILE.to_string()),
+        PropertyNameKind::File(JVM_SECURITY_PROPERTIES_FILE.to_string()),
+        PropertyNameKind::Env,
+    ];
+    let mut roles = HashMap::from([(
+        KafkaRole::Broker.to_string(),
+        (
+            config_property_kinds.clone(),
+            kafka.spec.brokers.clone().context(NoBrokerRoleSnafu)?,
+        ),
+    )]);
+    if let Some(controllers) = kafka.spec.controllers.clone() {
+        roles.insert(
+            KafkaRole::Controller.to_string(),
+            (config_property_kinds, controllers),
+        );
+    }
+
     let validated_config = validate_all_roles_and_groups_config(
         &resolved_product_image.product_version,
-        &transform_all_roles_to_config(
-            kafka,
-            [(
-                KafkaRole::Broker.to_string(),
-                (
-                    vec![
-                        PropertyNameKind::File(SERVER_PROPERTIES_FILE.to_string()),
-                        PropertyNameKind::File(JVM_SECURITY_PROPERTIES_FILE.to_string()),
-                        PropertyNameKind::Env,
-                    ],
-                    kafka.spec.brokers.clone().context(NoBrokerRoleSnafu)?,
-                ),
-            )]
-            .into(),
-        )
-        .context(GenerateProductConfigSnafu)?,
+        &transform_all_roles_to_config(kafka, roles).context(GenerateProductConfigSnafu)?,
         &ctx.product_config,
         false,
         false,
     )
     .context(InvalidProductConfigSnafu)?;
-    let role_broker_config = validated_config
-        .get(&KafkaRole::Broker.to_string())
-        .map(Cow::Borrowed)
-        .unwrap_or_default();
+
+    // The password is read when the security settings are resolved
+    if kafka.tls_store_format() == KafkaTlsStoreFormat::Pkcs12 {
+        create_generated_secret(
+            kafka,
+            client,
+            &resolved_product_image,
+            &kafka.tls_store_password_secret_name(),
+            TLS_STORE_PASSWORD_SECRET_KEY,
+            "tls-store-password",
+        )
+        .await
+        .context(CreateTlsStorePasswordSnafu)?;
+    }
 
     let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(client, kafka)
         .await
@@ -490,6 +638,10 @@ pub async fn reconcile_kafka(
     tracing::debug!(
         kerberos_enabled = kafka_security.has_kerberos_enabled(),
         kerberos_secret_class = ?kafka_security.kerberos_secret_class(),
+        scram_user_credentials_secret = ?kafka_security.scram_user_credentials_secret(),
+        oidc_enabled = kafka_security.has_oidc_enabled(),
+        ldap_enabled = kafka_security.has_ldap_enabled(),
+        delegation_tokens_enabled = kafka_security.has_delegation_tokens_enabled(),
         tls_enabled = kafka_security.tls_enabled(),
         tls_client_authentication_class = ?kafka_security.tls_client_authentication_class(),
         "The following security settings are used"
@@ -498,12 +650,37 @@ pub async fn reconcile_kafka(
     kafka_security
         .validate_authentication_methods()
         .context(FailedToValidateAuthenticationMethodSnafu)?;
+    kafka_security
+        .validate_listeners()
+        .context(FailedToValidateListenersSnafu)?;
+
+    // The brokers, controllers and admin Jobs log in to the internal listeners with this password
+    if kafka_security.has_internal_credentials_enabled() {
+        create_generated_secret(
+            kafka,
+            client,
+            &resolved_product_image,
+            &kafka.internal_credentials_secret_name(),
+            PASSWORD_SECRET_KEY,
+            "internal-credentials",
+        )
+        .await
+        .context(CreateInternalCredentialsSnafu)?;
+    }
+
+    // All issued tokens become invalid if the key changes
+    if kafka_security.has_delegation_tokens_enabled() {
+        create_secret_key_secret(kafka, client, &resolved_product_image)
+            .await
+            .context(CreateDelegationTokenSecretKeySnafu)?;
+    }
 
     // Assemble the OPA connection string from the discovery and the given path if provided
     // Will be passed as --override parameter in the cli in the state ful set
     let opa_connect = if let Some(opa_spec) = &kafka.spec.cluster_config.authorization.opa {
         Some(
             opa_spec
+                .config
                 .full_document_url_from_config_map(client, kafka, Some("allow"), OpaApiVersion::V1)
                 .await
                 .context(InvalidOpaConfigSnafu)?,
@@ -536,83 +713,130 @@ pub async fn reconcile_kafka(
         .await
         .context(ApplyRoleBindingSnafu)?;
 
-    let mut bootstrap_listeners = Vec::<Listener>::new();
+    let mut bootstrap_listeners = BTreeMap::<KafkaListenerName, Vec<Listener>>::new();
+    // Gates the next phase of a KRaft migration
+    let mut rolled_out = true;
 
-    for (rolegroup_name, rolegroup_config) in role_broker_config.iter() {
-        let rolegroup_ref = kafka.broker_rolegroup_ref(rolegroup_name);
+    for kafka_role in KafkaRole::iter() {
+        let validated_role_config = validated_config
+            .get(&kafka_role.to_string())
+            .map(Cow::Borrowed)
+            .unwrap_or_default();
 
-        let merged_config = kafka
-            .merged_config(&KafkaRole::Broker, &rolegroup_ref)
-            .context(FailedToResolveConfigSnafu)?;
+        for (rolegroup_name, rolegroup_config) in validated_role_config.iter() {
+            let rolegroup_ref = kafka_role.rolegroup_ref(kafka, rolegroup_name);
 
-        let rg_service =
-            build_broker_rolegroup_service(kafka, &resolved_product_image, &rolegroup_ref)?;
-        let rg_configmap = build_broker_rolegroup_config_map(
-            kafka,
-            &resolved_product_image,
-            &kafka_security,
-            &rolegroup_ref,
-            rolegroup_config,
-            &merged_config,
-            vector_aggregator_address.as_deref(),
-        )?;
-        let rg_statefulset = build_broker_rolegroup_statefulset(
-            kafka,
-            &kafka_role,
-            &resolved_product_image,
-            &rolegroup_ref,
-            rolegroup_config,
-            opa_connect.as_deref(),
-            &kafka_security,
-            &merged_config,
-            &rbac_sa,
-            &client.kubernetes_cluster_info,
-        )?;
-        let rg_bootstrap_listener = build_broker_rolegroup_bootstrap_listener(
-            kafka,
-            &resolved_product_image,
-            &kafka_security,
-            &rolegroup_ref,
-            &merged_config,
-        )?;
+            let merged_config = kafka
+                .merged_config(&kafka_role, &rolegroup_ref)
+                .context(FailedToResolveConfigSnafu)?;
+
+            let rg_service =
+                build_rolegroup_service(kafka, &resolved_product_image, &rolegroup_ref)?;
+            let rg_configmap = build_rolegroup_config_map(
+                kafka,
+                &kafka_role,
+                &resolved_product_image,
+                &kafka_security,
+                &rolegroup_ref,
+                rolegroup_config,
+                &merged_config,
+                vector_aggregator_address.as_deref(),
+                &client.kubernetes_cluster_info,
+            )?;
+            let rg_statefulset = match kafka_role {
+                KafkaRole::Broker => build_broker_rolegroup_statefulset(
+                    kafka,
+                    &kafka_role,
+                    &resolved_product_image,
+                    &rolegroup_ref,
+                    rolegroup_config,
+                    opa_connect.as_deref(),
+                    &kafka_security,
+                    &merged_config,
+                    &rbac_sa,
+                    &client.kubernetes_cluster_info,
+                    kraft_cluster_id.as_deref(),
+                )?,
+                KafkaRole::Controller => build_controller_rolegroup_statefulset(
+                    kafka,
+                    &kafka_role,
+                    &resolved_product_image,
+                    &rolegroup_ref,
+                    rolegroup_config,
+                    &kafka_security,
+                    &merged_config,
+                    &rbac_sa,
+                )?,
+            };
+
+            if kafka_role == KafkaRole::Broker {
+                for client_listener in kafka_security.exposed_client_listeners() {
+                    let rg_bootstrap_listener = build_broker_rolegroup_bootstrap_listener(
+                        kafka,
+                        &resolved_product_image,
+                        &client_listener,
+                        &rolegroup_ref,
+                        &merged_config,
+                    )?;
+                    bootstrap_listeners
+                        .entry(client_listener.name)
+                        .or_default()
+                        .push(
+                            cluster_resources
+                                .add(client, rg_bootstrap_listener)
+                                .await
+                                .context(ApplyRoleServiceSnafu)?,
+                        );
+                }
+            }
 
-        bootstrap_listeners.push(
             cluster_resources
-                .add(client, rg_bootstrap_listener)
+                .add(client, rg_service)
                 .await
-                .context(ApplyRoleServiceSnafu)?,
-        );
-        cluster_resources
-            .add(client, rg_service)
-            .await
-            .with_context(|_| ApplyRoleGroupServiceSnafu {
-                rolegroup: rolegroup_ref.clone(),
-            })?;
-        cluster_resources
-            .add(client, rg_configmap)
-            .await
-            .with_context(|_| ApplyRoleGroupConfigSnafu {
-                rolegroup: rolegroup_ref.clone(),
-            })?;
-
-        ss_cond_builder.add(
+                .with_context(|_| ApplyRoleGroupServiceSnafu {
+                    rolegroup: rolegroup_ref.clone(),
+                })?;
             cluster_resources
+                .add(client, rg_configmap)
+                .await
+                .with_context(|_| ApplyRoleGroupConfigSnafu {
+                    rolegroup: rolegroup_ref.clone(),
+                })?;
+
+            let rg_statefulset = cluster_resources
                 .add(client, rg_statefulset)
                 .await
                 .with_context(|_| ApplyRoleGroupStatefulSetSnafu {
                     rolegroup: rolegroup_ref.clone(),
-                })?,
-        );
+                })?;
+            rolled_out &= is_rolled_out(&rg_statefulset);
+            ss_cond_builder.add(rg_statefulset);
+        }
+
+        let role_config = kafka.role_config(&kafka_role);
+        if let Some(GenericRoleConfig {
+            pod_disruption_budget: pdb,
+        }) = role_config
+        {
+            add_pdbs(pdb, kafka, &kafka_role, client, &mut cluster_resources)
+                .await
+                .context(FailedToCreatePdbSnafu)?;
+        }
     }
 
-    let role_config = kafka.role_config(&kafka_role);
-    if let Some(GenericRoleConfig {
-        pod_disruption_budget: pdb,
-    }) = role_config
-    {
-        add_pdbs(pdb, kafka, &kafka_role, client, &mut cluster_resources)
+    // The brokers wait for the annotation with their rack before they start. Pods which cannot be
+    // annotated do not block the rest of the reconciliation, the error is reported at its end.
+    let rack_errors = match &kafka.spec.cluster_config.rack_awareness {
+        Some(rack_awareness) => annotate_broker_pods(client, kafka, rack_awareness)
             .await
-            .context(FailedToCreatePdbSnafu)?;
+            .context(AnnotateRackSnafu)?,
+        None => Vec::new(),
+    };
+    for error in &rack_errors {
+        tracing::warn!(
+            error = %error,
+            "Failed to annotate broker Pod with its rack"
+        );
     }
 
     for discovery_cm in build_discovery_configmaps(
@@ -631,11 +855,59 @@ pub async fn reconcile_kafka(
             .context(ApplyDiscoveryConfigSnafu)?;
     }
 
+    // The KafkaAcl is not a cluster resource, so it is not deleted as an orphan
+    match build_cluster_acl(kafka, &resolved_product_image)? {
+        Some(cluster_acl) => {
+            client
+                .apply_patch(OPERATOR_NAME, &cluster_acl, &cluster_acl)
+                .await
+                .context(ApplyClusterAclSnafu)?;
+        }
+        None => {
+            let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+            if let Some(cluster_acl) = client
+                .get_opt::<KafkaAcl>(&kafka.cluster_acl_name(), &namespace)
+                .await
+                .context(DeleteClusterAclSnafu)?
+            {
+                client
+                    .delete(&cluster_acl)
+                    .await
+                    .context(DeleteClusterAclSnafu)?;
+            }
+        }
+    }
+
+    let scram_credentials_revision = bootstrap_scram_credentials(
+        kafka,
+        client,
+        &resolved_product_image,
+        &kafka_security,
+        &mut cluster_resources,
+    )
+    .await?;
+
     let cluster_operation_cond_builder =
         ClusterOperationsConditionBuilder::new(&kafka.spec.cluster_operation);
 
+    // The controllers only report the state of the migration once they are rolled out
+    let controller_migration_states = if kafka.kraft_migration_phase().is_some() && rolled_out {
+        controller_migration_states(client, kafka).await?
+    } else {
+        Vec::new()
+    };
+
     let status = KafkaClusterStatus {
         conditions: compute_conditions(kafka, &[&ss_cond_builder, &cluster_operation_cond_builder]),
+        kraft_migration: kraft::next_migration_status(
+            kafka,
+            rolled_out,
+            &controller_migration_states,
+        ),
+        scram_credentials_secret: kafka_security
+            .scram_user_credentials_secret()
+            .map(str::to_string),
+        scram_credentials_revision,
     };
 
     cluster_resources
@@ -648,22 +920,248 @@ pub async fn reconcile_kafka(
         .await
         .context(ApplyStatusSnafu)?;
 
+    ensure!(
+        rack_errors.is_empty(),
+        AnnotateRackPodsSnafu {
+            count: rack_errors.len()
+        }
+    );
+
+    // The migration state of the controllers is not watched, so it is polled until the
+    // current phase of a migration is completed
+    if status
+        .kraft_migration
+        .as_ref()
+        .is_some_and(|migration| migration.is_pending())
+    {
+        return Ok(Action::requeue(*Duration::from_secs(30)));
+    }
+
     Ok(Action::await_change())
 }
 
+/// Reads the [`ZkMigrationState`] from the metrics of the KRaft controllers, through the proxy of
+/// the Kubernetes API server.
+///
+/// Only the active controller reports the state. Controllers whose metrics cannot be read are
+/// skipped, the migration then waits for the next reconciliation.
+async fn controller_migration_states(
+    client: &stackable_operator::client::Client,
+    kafka: &KafkaCluster,
+) -> Result<Vec<ZkMigrationState>> {
+    let mut states = Vec::new();
+    for pod in kafka.controller_pods().context(ListControllerPodsSnafu)? {
+        let request = http::Request::get(format!(
+            "/api/v1/namespaces/{namespace}/pods/{pod_name}:{METRICS_PORT}/proxy/metrics",
+            namespace = pod.namespace,
+            pod_name = pod.pod_name,
+        ))
+        .body(Vec::new())
+        .context(BuildControllerMetricsRequestSnafu)?;
+        match client.as_kube_client().request_text(request).await {
+            Ok(metrics) => states.extend(ZkMigrationState::from_metrics(&metrics)),
+            Err(error) => tracing::warn!(
+                pod = %pod.pod_name,
+                error = %error,
+                "Failed to read the migration state from the metrics of the KRaft controller"
+            ),
+        }
+    }
+    Ok(states)
+}
+
+/// Bootstraps the users of a Static AuthenticationClass as SCRAM credentials and returns the
+/// `resourceVersion` of the user credentials Secret once they are applied.
+///
+/// The admin Job is named after the `resourceVersion`, so that it runs again whenever the Secret
+/// changes. It is a cluster resource, which is only added until it succeeded, so that it is
+/// removed afterwards. A failed Job is retried once it is removed after its TTL.
+/// Users which are removed from the Secret are kept in Kafka.
+///
+/// The prober user is bootstrapped as well, with a password generated by the operator.
+async fn bootstrap_scram_credentials(
+    kafka: &KafkaCluster,
+    client: &stackable_operator::client::Client,
+    resolved_product_image: &ResolvedProductImage,
+    kafka_security: &KafkaTlsSecurity,
+    cluster_resources: &mut ClusterResources,
+) -> Result<Option<String>> {
+    let Some(secret_name) = kafka_security.scram_user_credentials_secret() else {
+        return Ok(None);
+    };
+    let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let secret_ref = ObjectRef::<Secret>::new(secret_name).within(&namespace);
+
+    // The Secret is mounted into the broker Pods, so it must exist even if the users were already
+    // bootstrapped
+    let prober_secret_name = kafka.scram_prober_secret_name();
+    create_generated_secret(
+        kafka,
+        client,
+        resolved_product_image,
+        &prober_secret_name,
+        PASSWORD_SECRET_KEY,
+        "scram-prober",
+    )
+    .await
+    .context(CreateScramProberPasswordSnafu)?;
+
+    let secret = client
+        .get::<Secret>(secret_name, &namespace)
+        .await
+        .with_context(|_| GetScramCredentialsSnafu {
+            secret: secret_ref.clone(),
+        })?;
+    let revision = secret.metadata.resource_version.clone();
+    let bootstrapped_revision = kafka
+        .status
+        .as_ref()
+        .and_then(|status| status.scram_credentials_revision.clone());
+    if revision == bootstrapped_revision {
+        return Ok(bootstrapped_revision);
+    }
+
+    let mut users = secret.data.unwrap_or_default();
+    if users.remove(user::SCRAM_PROBER_USER).is_some() {
+        tracing::warn!(
+            secret = %secret_ref,
+            "Ignoring the user {:?} of the user credentials Secret, its password is generated by the operator",
+            user::SCRAM_PROBER_USER
+        );
+    }
+    for (user_name, password) in &users {
+        user::validate_static_user(user_name, &String::from_utf8_lossy(&password.0)).with_context(
+            |_| InvalidScramUserSnafu {
+                secret: secret_ref.clone(),
+            },
+        )?;
+    }
+
+    // The readiness probe of the brokers logs in with the prober user
+    let password_refs = users
+        .into_keys()
+        .map(|user_name| (user_name.clone(), secret_name.to_string(), user_name))
+        .chain(std::iter::once((
+            user::SCRAM_PROBER_USER.to_string(),
+            prober_secret_name,
+            PASSWORD_SECRET_KEY.to_string(),
+        )))
+        .collect::<Vec<_>>();
+    let user_names = password_refs
+        .iter()
+        .map(|(user_name, _, _)| user_name.clone())
+        .collect::<Vec<_>>();
+    // The passwords are passed to the Job from the Secrets, so that they are not part of the Job
+    // spec
+    let password_envs = password_refs
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, secret_name, key))| EnvVar {
+            name: format!("{PASSWORD_ENV}_{index}"),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    name: secret_name,
+                    key,
+                    ..SecretKeySelector::default()
+                }),
+                ..EnvVarSource::default()
+            }),
+            ..EnvVar::default()
+        })
+        .collect();
+    let job = build_admin_job(
+        kafka,
+        kafka,
+        KAFKA_CONTROLLER_NAME,
+        &admin_job_name(
+            &kafka.name_any(),
+            "scram",
+            &[revision.as_deref().unwrap_or_default()],
+        ),
+        &user::static_user_commands(&user_names),
+        password_envs,
+        kafka_security,
+        &client.kubernetes_cluster_info,
+    )
+    .context(BuildScramCredentialsJobSnafu)?;
+    let job = cluster_resources
+        .add(client, job)
+        .await
+        .context(ApplyScramCredentialsJobSnafu)?;
+
+    Ok(match admin_job_state(&job) {
+        AdminJobState::Succeeded => revision,
+        AdminJobState::Running => bootstrapped_revision,
+        AdminJobState::Failed => {
+            tracing::warn!(
+                job = job.name_any(),
+                "Failed to bootstrap the SCRAM credentials, retrying after the Job is removed"
+            );
+            bootstrapped_revision
+        }
+    })
+}
+
+/// The ACL bindings declared in the cluster spec are applied by the ACL controller, so they are put
+/// into a [`KafkaAcl`] owned by the cluster.
+fn build_cluster_acl(
+    kafka: &KafkaCluster,
+    resolved_product_image: &ResolvedProductImage,
+) -> Result<Option<KafkaAcl>> {
+    let Some(acls) = &kafka.spec.cluster_config.authorization.acls else {
+        return Ok(None);
+    };
+    if acls.bindings.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(KafkaAcl {
+        metadata: ObjectMetaBuilder::new()
+            .name_and_namespace(kafka)
+            .name(kafka.cluster_acl_name())
+            .ownerreference_from_resource(kafka, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .with_recommended_labels(build_recommended_labels(
+                kafka,
+                KAFKA_CONTROLLER_NAME,
+                &resolved_product_image.app_version_label,
+                "acl",
+                "global",
+            ))
+            .context(MetadataBuildSnafu)?
+            .build(),
+        spec: KafkaAclSpec {
+            cluster_ref: KafkaClusterRef {
+                name: kafka.name_any(),
+            },
+            bindings: acls.bindings.clone(),
+        },
+        status: None,
+    }))
+}
+
 /// Kafka clients will use the load-balanced bootstrap listener to get a list of broker addresses and will use those to
 /// transmit data to the correct broker.
+///
+/// Every client listener gets its own bootstrap listener, dedicated listeners of additional
+/// AuthenticationClasses may override the ListenerClass.
 pub fn build_broker_rolegroup_bootstrap_listener(
     kafka: &KafkaCluster,
     resolved_product_image: &ResolvedProductImage,
-    kafka_security: &KafkaTlsSecurity,
+    client_listener: &KafkaClientListener,
     rolegroup: &RoleGroupRef<KafkaCluster>,
     merged_config: &KafkaConfig,
 ) -> Result<Listener> {
+    let class_name = client_listener
+        .dedicated
+        .as_ref()
+        .and_then(|dedicated| dedicated.bootstrap_listener_class.clone())
+        .unwrap_or_else(|| merged_config.bootstrap_listener_class.clone());
+
     Ok(Listener {
         metadata: ObjectMetaBuilder::new()
             .name_and_namespace(kafka)
-            .name(kafka.bootstrap_service_name(rolegroup))
+            .name(client_listener.bootstrap_listener_name(kafka, rolegroup))
             .ownerreference_from_resource(kafka, None, Some(true))
             .context(ObjectMissingMetadataForOwnerRefSnafu)?
             .with_recommended_labels(build_recommended_labels(
@@ -676,8 +1174,8 @@ pub fn build_broker_rolegroup_bootstrap_listener(
             .context(MetadataBuildSnafu)?
             .build(),
         spec: ListenerSpec {
-            class_name: Some(merged_config.bootstrap_listener_class.clone()),
-            ports: Some(listener_ports(kafka_security)),
+            class_name: Some(class_name),
+            ports: Some(listener_ports(client_listener)),
             ..ListenerSpec::default()
         },
         status: None,
@@ -685,29 +1183,69 @@ pub fn build_broker_rolegroup_bootstrap_listener(
 }
 
 /// The rolegroup [`ConfigMap`] configures the rolegroup based on the configuration given by the administrator
-fn build_broker_rolegroup_config_map(
+#[allow(clippy::too_many_arguments)]
+fn build_rolegroup_config_map(
     kafka: &KafkaCluster,
+    kafka_role: &KafkaRole,
     resolved_product_image: &ResolvedProductImage,
     kafka_security: &KafkaTlsSecurity,
     rolegroup: &RoleGroupRef<KafkaCluster>,
-    broker_config: &HashMap<PropertyNameKind, BTreeMap<String, String>>,
+    rolegroup_config: &HashMap<PropertyNameKind, BTreeMap<String, String>>,
     merged_config: &KafkaConfig,
     vector_aggregator_address: Option<&str>,
+    cluster_info: &KubernetesClusterInfo,
 ) -> Result<ConfigMap> {
-    let mut server_cfg = broker_config
+    let mut server_cfg = rolegroup_config
         .get(&PropertyNameKind::File(SERVER_PROPERTIES_FILE.to_string()))
         .cloned()
         .unwrap_or_default();
 
-    server_cfg.extend(kafka_security.config_settings());
-    server_cfg.extend(graceful_shutdown_config_properties());
+    match kafka_role {
+        KafkaRole::Broker => {
+            server_cfg.extend(
+                kafka_security
+                    .config_settings()
+                    .context(BuildSecurityConfigSnafu)?,
+            );
+            server_cfg.extend(graceful_shutdown_config_properties());
+            if kafka.spec.cluster_config.rack_awareness.is_some() {
+                server_cfg.extend(rack_awareness::config_settings());
+            }
+        }
+        KafkaRole::Controller => {
+            // During a migration from ZooKeeper the controllers connect to the brokers
+            if kafka.metadata_mode(kafka_role).is_migrating() {
+                server_cfg.extend(kafka_security.inter_broker_config_settings());
+            }
+            server_cfg.extend(kafka_security.delegation_token_config_settings());
+        }
+    }
+
+    let uses_zookeeper = kafka.metadata_mode(kafka_role).uses_zookeeper();
+    if uses_zookeeper {
+        server_cfg.extend(kafka_security.zookeeper_config_settings());
+    }
+
+    if kafka.metadata_mode(kafka_role).uses_controllers() {
+        server_cfg.extend(kafka_security.controller_config_settings());
+        server_cfg.extend(
+            kraft::config_settings(
+                kafka,
+                kafka_role,
+                &rolegroup.role_group,
+                kafka_security,
+                cluster_info,
+            )
+            .context(KraftConfigSnafu)?,
+        );
+    }
 
     let server_cfg = server_cfg
         .into_iter()
         .map(|(k, v)| (k, Some(v)))
         .collect::<Vec<_>>();
 
-    let jvm_sec_props: BTreeMap<String, Option<String>> = broker_config
+    let jvm_sec_props: BTreeMap<String, Option<String>> = rolegroup_config
         .get(&PropertyNameKind::File(
             JVM_SECURITY_PROPERTIES_FILE.to_string(),
         ))
@@ -752,6 +1290,19 @@ fn build_broker_rolegroup_config_map(
             })?,
         );
 
+    if let (KafkaRole::Broker, Some(trusted_realms_config)) =
+        (kafka_role, kafka_security.kerberos_trusted_realms_config())
+    {
+        cm_builder.add_data(KRB5_TRUSTED_REALMS_FILE, trusted_realms_config);
+    }
+
+    if uses_zookeeper && kafka_security.zookeeper_kerberos_secret_class().is_some() {
+        cm_builder.add_data(
+            ZOOKEEPER_JAAS_CONFIG_FILE,
+            zookeeper_jaas_config(kafka, cluster_info).context(ZookeeperJaasConfigSnafu)?,
+        );
+    }
+
     tracing::debug!(?server_cfg, "Applied server config");
     tracing::debug!(?jvm_sec_props, "Applied JVM config");
 
@@ -775,7 +1326,7 @@ fn build_broker_rolegroup_config_map(
 /// The rolegroup [`Service`] is a headless service that allows direct access to the instances of a certain rolegroup
 ///
 /// This is mostly useful for internal communication between peers, or for clients that perform client-side load balancing.
-fn build_broker_rolegroup_service(
+fn build_rolegroup_service(
     kafka: &KafkaCluster,
     resolved_product_image: &ResolvedProductImage,
     rolegroup: &RoleGroupRef<KafkaCluster>,
@@ -817,7 +1368,7 @@ fn build_broker_rolegroup_service(
 
 /// The rolegroup [`StatefulSet`] runs the rolegroup, as configured by the administrator.
 ///
-/// The [`Pod`](`stackable_operator::k8s_openapi::api::core::v1::Pod`)s are accessible through the corresponding [`Service`] (from [`build_broker_rolegroup_service`]).
+/// The [`Pod`](`stackable_operator::k8s_openapi::api::core::v1::Pod`)s are accessible through the corresponding [`Service`] (from [`build_rolegroup_service`]).
 #[allow(clippy::too_many_arguments)]
 fn build_broker_rolegroup_statefulset(
     kafka: &KafkaCluster,
@@ -830,20 +1381,16 @@ fn build_broker_rolegroup_statefulset(
     merged_config: &KafkaConfig,
     service_account: &ServiceAccount,
     cluster_info: &KubernetesClusterInfo,
+    kraft_cluster_id: Option<&str>,
 ) -> Result<StatefulSet> {
-    let role = kafka.role(kafka_role).context(InternalOperatorSnafu)?;
-    let rolegroup = kafka
-        .rolegroup(rolegroup_ref)
-        .context(InternalOperatorSnafu)?;
-    let recommended_object_labels = build_recommended_labels(
+    let recommended_labels = Labels::recommended(build_recommended_labels(
         kafka,
         KAFKA_CONTROLLER_NAME,
         &resolved_product_image.app_version_label,
         &rolegroup_ref.role,
         &rolegroup_ref.role_group,
-    );
-    let recommended_labels =
-        Labels::recommended(recommended_object_labels.clone()).context(LabelBuildSnafu)?;
+    ))
+    .context(LabelBuildSnafu)?;
     // Used for PVC templates that cannot be modified once they are deployed
     let unversioned_recommended_labels = Labels::recommended(build_recommended_labels(
         kafka,
@@ -906,118 +1453,87 @@ fn build_broker_rolegroup_statefulset(
         .context(AddKerberosConfigSnafu)?;
     }
 
-    let mut env = broker_config
-        .get(&PropertyNameKind::Env)
-        .into_iter()
-        .flatten()
-        .map(|(k, v)| EnvVar {
-            name: k.clone(),
-            value: Some(v.clone()),
-            ..EnvVar::default()
-        })
-        .collect::<Vec<_>>();
+    if kafka_security.has_ldap_enabled() {
+        add_ldap_pod_config(kafka_security, &mut cb_kafka, &mut pod_builder)
+            .context(AddLdapConfigSnafu)?;
+    }
 
-    if let Some(memory_limit) = merged_config.resources.memory.limit.as_ref() {
-        let heap_size = MemoryQuantity::try_from(memory_limit)
-            .context(InvalidHeapConfigSnafu)?
-            .scale_to(BinaryMultiple::Mebi)
-            * JAVA_HEAP_RATIO;
+    if kafka_security.has_delegation_tokens_enabled() {
+        add_delegation_token_pod_config(kafka, &mut cb_kafka, &mut pod_builder)
+            .context(AddDelegationTokenConfigSnafu)?;
+    }
 
-        env.push(EnvVar {
-            name: KAFKA_HEAP_OPTS.to_string(),
-            value: Some(format!(
-                "-Xmx{heap}",
-                heap = heap_size
-                    .format_for_java()
-                    .context(InvalidHeapConfigSnafu)?
-            )),
-            ..EnvVar::default()
-        });
+    if kafka.metadata_mode(kafka_role).uses_zookeeper() {
+        kafka_security
+            .add_zookeeper_volume_and_volume_mounts(
+                kafka,
+                &mut pod_builder,
+                &mut cb_kafka,
+                &requested_secret_lifetime,
+            )
+            .context(AddVolumesAndVolumeMountsSnafu)?;
     }
 
-    env.push(EnvVar {
-        name: "ZOOKEEPER".to_string(),
-        value_from: Some(EnvVarSource {
-            config_map_key_ref: Some(ConfigMapKeySelector {
-                name: kafka.spec.cluster_config.zookeeper_config_map_name.clone(),
-                key: "ZOOKEEPER".to_string(),
-                ..ConfigMapKeySelector::default()
-            }),
-            ..EnvVarSource::default()
-        }),
-        ..EnvVar::default()
-    });
-
-    env.push(EnvVar {
-        name: "POD_NAME".to_string(),
-        value_from: Some(EnvVarSource {
-            field_ref: Some(ObjectFieldSelector {
-                api_version: Some("v1".to_string()),
-                field_path: "metadata.name".to_string(),
-            }),
-            ..EnvVarSource::default()
-        }),
-        ..EnvVar::default()
-    });
-
-    // Needed for the `containerdebug` process to log it's tracing information to.
-    env.push(EnvVar {
-        name: "CONTAINERDEBUG_LOG_DIRECTORY".to_string(),
-        value: Some(format!("{STACKABLE_LOG_DIR}/containerdebug")),
-        value_from: None,
-    });
-
-    let jvm_args = format!(
-        "-Djava.security.properties={STACKABLE_CONFIG_DIR}/{JVM_SECURITY_PROPERTIES_FILE} -javaagent:/stackable/jmx/jmx_prometheus_javaagent.jar={METRICS_PORT}:/stackable/jmx/broker.yaml",
-    );
+    if kafka.spec.cluster_config.rack_awareness.is_some() {
+        pod_builder
+            .add_volume(rack_awareness::rack_volume())
+            .context(AddVolumeSnafu)?;
+        cb_kafka
+            .add_volume_mount(RACK_VOLUME_NAME, STACKABLE_RACK_DIR)
+            .context(AddVolumeMountSnafu)?;
+    }
 
     let kafka_listeners = get_kafka_listener_config(
         kafka,
         kafka_security,
         &rolegroup_ref.object_name(),
+        merged_config.combined_controller,
         cluster_info,
     )
     .context(InvalidKafkaListenersSnafu)?;
 
-    cb_kafka
-        .image_from_product_image(resolved_product_image)
-        .command(vec![
-            "/bin/bash".to_string(),
-            "-x".to_string(),
-            "-euo".to_string(),
-            "pipefail".to_string(),
-            "-c".to_string(),
-        ])
-        .args(vec![kafka_security
-            .kafka_container_commands(
-                &kafka_listeners,
-                opa_connect_string,
-                kafka_security.has_kerberos_enabled(),
+    let mut kafka_commands = Vec::new();
+    if kafka.spec.cluster_config.rack_awareness.is_some() {
+        kafka_commands.push(rack_awareness::wait_for_rack_command());
+    }
+    kafka_commands.extend(kafka_security.kafka_container_commands(
+        &kafka_listeners,
+        opa_connect_string,
+        kafka_security.has_kerberos_enabled(),
+        kraft_cluster_id,
+    ));
+
+    let mut ports = container_ports(&kafka_security.exposed_client_listeners());
+    if merged_config.combined_controller {
+        ports.push(controller_container_port(kafka_security));
+    }
+
+    configure_kafka_container(
+        &mut cb_kafka,
+        kafka,
+        kafka_role,
+        resolved_product_image,
+        rolegroup_ref,
+        broker_config,
+        kafka_security,
+        merged_config,
+        kafka_commands,
+    )?;
+    cb_kafka.add_container_ports(ports);
+
+    for client_listener in kafka_security.exposed_client_listeners() {
+        cb_kafka
+            .add_volume_mount(
+                client_listener.bootstrap_listener_volume_name(),
+                client_listener.bootstrap_listener_dir(),
             )
-            .join("\n")])
-        .add_env_var("EXTRA_ARGS", jvm_args)
-        .add_env_var(
-            "KAFKA_LOG4J_OPTS",
-            format!("-Dlog4j.configuration=file:{STACKABLE_LOG_CONFIG_DIR}/{LOG4J_CONFIG_FILE}"),
-        )
-        .add_env_vars(env)
-        .add_container_ports(container_ports(kafka_security))
-        .add_volume_mount(LOG_DIRS_VOLUME_NAME, STACKABLE_DATA_DIR)
-        .context(AddVolumeMountSnafu)?
-        .add_volume_mount("config", STACKABLE_CONFIG_DIR)
-        .context(AddVolumeMountSnafu)?
-        .add_volume_mount(
-            LISTENER_BOOTSTRAP_VOLUME_NAME,
-            STACKABLE_LISTENER_BOOTSTRAP_DIR,
-        )
-        .context(AddVolumeMountSnafu)?
-        .add_volume_mount(LISTENER_BROKER_VOLUME_NAME, STACKABLE_LISTENER_BROKER_DIR)
-        .context(AddVolumeMountSnafu)?
-        .add_volume_mount("log-config", STACKABLE_LOG_CONFIG_DIR)
-        .context(AddVolumeMountSnafu)?
-        .add_volume_mount("log", STACKABLE_LOG_DIR)
-        .context(AddVolumeMountSnafu)?
-        .resources(merged_config.resources.clone().into());
+            .context(AddVolumeMountSnafu)?
+            .add_volume_mount(
+                client_listener.broker_listener_volume_name(),
+                client_listener.broker_listener_dir(),
+            )
+            .context(AddVolumeMountSnafu)?;
+    }
 
     let pod_fqdn = pod_fqdn(kafka, &rolegroup_ref.object_name(), cluster_info)
         .context(ResolveNamespaceSnafu)?;
@@ -1057,6 +1573,287 @@ fn build_broker_rolegroup_statefulset(
             ..Probe::default()
         });
 
+    for client_listener in kafka_security.exposed_client_listeners() {
+        let broker_listener_class = client_listener
+            .dedicated
+            .as_ref()
+            .and_then(|dedicated| dedicated.broker_listener_class.as_deref())
+            .unwrap_or(&merged_config.broker_listener_class);
+        pod_builder
+            .add_listener_volume_by_listener_class(
+                &client_listener.broker_listener_volume_name(),
+                broker_listener_class,
+                &recommended_labels,
+            )
+            .context(AddListenerVolumeSnafu)?;
+
+        // The bootstrap volume of the default client listener is a persistent volume template
+        // instead, to keep addresses persistent. As volume claim templates cannot be changed,
+        // dedicated and user-defined listeners, which come and go with the spec, use ephemeral
+        // volumes referencing their bootstrap Listener.
+        if client_listener.dedicated.is_some() {
+            pod_builder
+                .add_listener_volume_by_listener_name(
+                    &client_listener.bootstrap_listener_volume_name(),
+                    &client_listener.bootstrap_listener_name(kafka, rolegroup_ref),
+                    &recommended_labels,
+                )
+                .context(AddListenerVolumeSnafu)?;
+        }
+    }
+
+    pod_builder
+        .add_container(cb_kafka.build())
+        .add_container(cb_kcat_prober.build());
+
+    let mut pod_template = build_rolegroup_pod_template(
+        pod_builder,
+        kafka,
+        resolved_product_image,
+        rolegroup_ref,
+        merged_config,
+        service_account,
+    )?;
+
+    let pod_template_spec = pod_template.spec.get_or_insert_with(PodSpec::default);
+    // Don't run kcat pod as PID 1, to ensure that default signal handlers apply
+    pod_template_spec.share_process_namespace = Some(true);
+
+    build_rolegroup_statefulset(
+        kafka,
+        kafka_role,
+        resolved_product_image,
+        rolegroup_ref,
+        pod_template,
+        pvcs,
+    )
+}
+
+/// The rolegroup [`StatefulSet`] of the KRaft controllers, which manage the cluster metadata.
+///
+/// Controllers are not reachable by clients, so they neither get listener volumes nor a kcat prober.
+#[allow(clippy::too_many_arguments)]
+fn build_controller_rolegroup_statefulset(
+    kafka: &KafkaCluster,
+    kafka_role: &KafkaRole,
+    resolved_product_image: &ResolvedProductImage,
+    rolegroup_ref: &RoleGroupRef<KafkaCluster>,
+    controller_config: &HashMap<PropertyNameKind, BTreeMap<String, String>>,
+    kafka_security: &KafkaTlsSecurity,
+    merged_config: &KafkaConfig,
+    service_account: &ServiceAccount,
+) -> Result<StatefulSet> {
+    let kraft_cluster_id = kraft::cluster_id(kafka).context(KraftConfigSnafu)?;
+
+    let kafka_container_name = Container::Kafka.to_string();
+    let mut cb_kafka =
+        ContainerBuilder::new(&kafka_container_name).context(InvalidContainerNameSnafu {
+            name: kafka_container_name.clone(),
+        })?;
+
+    let mut pod_builder = PodBuilder::new();
+
+    // Add TLS related volumes and volume mounts
+    let requested_secret_lifetime = merged_config
+        .requested_secret_lifetime
+        .context(MissingSecretLifetimeSnafu)?;
+    kafka_security
+        .add_internal_volume_and_volume_mounts(
+            &mut pod_builder,
+            &mut cb_kafka,
+            &requested_secret_lifetime,
+        )
+        .context(AddVolumesAndVolumeMountsSnafu)?;
+
+    // The controllers issue the delegation tokens in KRaft mode
+    if kafka_security.has_delegation_tokens_enabled() {
+        add_delegation_token_pod_config(kafka, &mut cb_kafka, &mut pod_builder)
+            .context(AddDelegationTokenConfigSnafu)?;
+    }
+
+    // Only needed during a migration from ZooKeeper
+    if kafka.metadata_mode(kafka_role).uses_zookeeper() {
+        kafka_security
+            .add_zookeeper_volume_and_volume_mounts(
+                kafka,
+                &mut pod_builder,
+                &mut cb_kafka,
+                &requested_secret_lifetime,
+            )
+            .context(AddVolumesAndVolumeMountsSnafu)?;
+    }
+
+    let kafka_listeners = get_kafka_controller_listener_config(kafka, kafka_security);
+
+    configure_kafka_container(
+        &mut cb_kafka,
+        kafka,
+        kafka_role,
+        resolved_product_image,
+        rolegroup_ref,
+        controller_config,
+        kafka_security,
+        merged_config,
+        kafka_security.kafka_container_commands(
+            &kafka_listeners,
+            None,
+            false,
+            Some(&kraft_cluster_id),
+        ),
+    )?;
+    cb_kafka
+        .add_container_ports(controller_container_ports(kafka_security))
+        .readiness_probe(Probe {
+            tcp_socket: Some(TCPSocketAction {
+                port: IntOrString::String(KafkaTlsSecurity::CONTROLLER_PORT_NAME.to_string()),
+                ..TCPSocketAction::default()
+            }),
+            timeout_seconds: Some(5),
+            period_seconds: Some(2),
+            ..Probe::default()
+        });
+
+    pod_builder.add_container(cb_kafka.build());
+
+    let pod_template = build_rolegroup_pod_template(
+        pod_builder,
+        kafka,
+        resolved_product_image,
+        rolegroup_ref,
+        merged_config,
+        service_account,
+    )?;
+
+    build_rolegroup_statefulset(
+        kafka,
+        kafka_role,
+        resolved_product_image,
+        rolegroup_ref,
+        pod_template,
+        merged_config.resources.storage.build_pvcs(),
+    )
+}
+
+/// Configures the `kafka` container as brokers and controllers share it, apart from the ports,
+/// probes and role specific volume mounts.
+#[allow(clippy::too_many_arguments)]
+fn configure_kafka_container(
+    cb_kafka: &mut ContainerBuilder,
+    kafka: &KafkaCluster,
+    kafka_role: &KafkaRole,
+    resolved_product_image: &ResolvedProductImage,
+    rolegroup_ref: &RoleGroupRef<KafkaCluster>,
+    role_config: &HashMap<PropertyNameKind, BTreeMap<String, String>>,
+    kafka_security: &KafkaTlsSecurity,
+    merged_config: &KafkaConfig,
+    kafka_commands: Vec<String>,
+) -> Result<()> {
+    let metadata_mode = kafka.metadata_mode(kafka_role);
+
+    let mut env = role_config
+        .get(&PropertyNameKind::Env)
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| EnvVar {
+            name: k.clone(),
+            value: Some(v.clone()),
+            ..EnvVar::default()
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(memory_limit) = merged_config.resources.memory.limit.as_ref() {
+        let heap_size = MemoryQuantity::try_from(memory_limit)
+            .context(InvalidHeapConfigSnafu)?
+            .scale_to(BinaryMultiple::Mebi)
+            * JAVA_HEAP_RATIO;
+
+        env.push(EnvVar {
+            name: KAFKA_HEAP_OPTS.to_string(),
+            value: Some(format!(
+                "-Xmx{heap}",
+                heap = heap_size
+                    .format_for_java()
+                    .context(InvalidHeapConfigSnafu)?
+            )),
+            ..EnvVar::default()
+        });
+    }
+
+    if metadata_mode.uses_zookeeper() {
+        env.extend(zookeeper_env_var(kafka));
+    }
+    if metadata_mode.is_kraft() {
+        env.push(node_id_offset_env_var(rolegroup_ref));
+    }
+
+    env.push(EnvVar {
+        name: "POD_NAME".to_string(),
+        value_from: Some(EnvVarSource {
+            field_ref: Some(ObjectFieldSelector {
+                api_version: Some("v1".to_string()),
+                field_path: "metadata.name".to_string(),
+            }),
+            ..EnvVarSource::default()
+        }),
+        ..EnvVar::default()
+    });
+
+    // Needed for the `containerdebug` process to log it's tracing information to.
+    env.push(EnvVar {
+        name: "CONTAINERDEBUG_LOG_DIRECTORY".to_string(),
+        value: Some(format!("{STACKABLE_LOG_DIR}/containerdebug")),
+        value_from: None,
+    });
+
+    let mut jvm_args = vec![format!(
+        "-Djava.security.properties={STACKABLE_CONFIG_DIR}/{JVM_SECURITY_PROPERTIES_FILE} -javaagent:/stackable/jmx/jmx_prometheus_javaagent.jar={METRICS_PORT}:/stackable/jmx/broker.yaml",
+    )];
+    if metadata_mode.uses_zookeeper() {
+        jvm_args.extend(kafka_security.zookeeper_jvm_args(kafka_role));
+    }
+
+    cb_kafka
+        .image_from_product_image(resolved_product_image)
+        .command(vec![
+            "/bin/bash".to_string(),
+            "-x".to_string(),
+            "-euo".to_string(),
+            "pipefail".to_string(),
+            "-c".to_string(),
+        ])
+        .args(vec![kafka_commands.join("\n")])
+        .add_env_var("EXTRA_ARGS", jvm_args.join(" "))
+        .add_env_var(
+            "KAFKA_LOG4J_OPTS",
+            format!("-Dlog4j.configuration=file:{STACKABLE_LOG_CONFIG_DIR}/{LOG4J_CONFIG_FILE}"),
+        )
+        .add_env_vars(env)
+        .add_volume_mount(LOG_DIRS_VOLUME_NAME, STACKABLE_DATA_DIR)
+        .context(AddVolumeMountSnafu)?
+        .add_volume_mount("config", STACKABLE_CONFIG_DIR)
+        .context(AddVolumeMountSnafu)?
+        .add_volume_mount("log-config", STACKABLE_LOG_CONFIG_DIR)
+        .context(AddVolumeMountSnafu)?
+        .add_volume_mount("log", STACKABLE_LOG_DIR)
+        .context(AddVolumeMountSnafu)?
+        .resources(merged_config.resources.clone().into());
+
+    Ok(())
+}
+
+/// Completes the Pod template of brokers and controllers with the config and log volumes, the
+/// Vector agent and the Pod level settings.
+///
+/// The role specific containers must already have been added to the `pod_builder`, so that the
+/// Vector container comes last and `kubectl` keeps defaulting to the `kafka` container.
+fn build_rolegroup_pod_template(
+    mut pod_builder: PodBuilder,
+    kafka: &KafkaCluster,
+    resolved_product_image: &ResolvedProductImage,
+    rolegroup_ref: &RoleGroupRef<KafkaCluster>,
+    merged_config: &KafkaConfig,
+    service_account: &ServiceAccount,
+) -> Result<PodTemplateSpec> {
     if let Some(ContainerLogConfig {
         choice:
             Some(ContainerLogConfigChoice::Custom(CustomContainerLogConfig {
@@ -1082,15 +1879,19 @@ fn build_broker_rolegroup_statefulset(
     }
 
     let metadata = ObjectMetaBuilder::new()
-        .with_recommended_labels(recommended_object_labels)
+        .with_recommended_labels(build_recommended_labels(
+            kafka,
+            KAFKA_CONTROLLER_NAME,
+            &resolved_product_image.app_version_label,
+            &rolegroup_ref.role,
+            &rolegroup_ref.role_group,
+        ))
         .context(MetadataBuildSnafu)?
         .build();
 
     pod_builder
         .metadata(metadata)
         .image_pull_secrets_from_product_image(resolved_product_image)
-        .add_container(cb_kafka.build())
-        .add_container(cb_kcat_prober.build())
         .affinity(&merged_config.affinity)
         .add_volume(Volume {
             name: "config".to_string(),
@@ -1101,13 +1902,6 @@ fn build_broker_rolegroup_statefulset(
             ..Volume::default()
         })
         .context(AddVolumeSnafu)?
-        // bootstrap volume is a persistent volume template instead, to keep addresses persistent
-        .add_listener_volume_by_listener_class(
-            LISTENER_BROKER_VOLUME_NAME,
-            &merged_config.broker_listener_class,
-            &recommended_labels,
-        )
-        .context(AddListenerVolumeSnafu)?
         .add_empty_dir_volume(
             "log",
             Some(product_logging::framework::calculate_log_volume_size_limit(
@@ -1145,11 +1939,23 @@ fn build_broker_rolegroup_statefulset(
 
     add_graceful_shutdown_config(merged_config, &mut pod_builder).context(GracefulShutdownSnafu)?;
 
-    let mut pod_template = pod_builder.build_template();
+    Ok(pod_builder.build_template())
+}
 
-    let pod_template_spec = pod_template.spec.get_or_insert_with(PodSpec::default);
-    // Don't run kcat pod as PID 1, to ensure that default signal handlers apply
-    pod_template_spec.share_process_namespace = Some(true);
+/// Wraps the Pod template into the rolegroup [`StatefulSet`], after applying the Pod overrides
+/// of the role and the rolegroup.
+fn build_rolegroup_statefulset(
+    kafka: &KafkaCluster,
+    kafka_role: &KafkaRole,
+    resolved_product_image: &ResolvedProductImage,
+    rolegroup_ref: &RoleGroupRef<KafkaCluster>,
+    mut pod_template: PodTemplateSpec,
+    volume_claim_templates: Vec<PersistentVolumeClaim>,
+) -> Result<StatefulSet> {
+    let role = kafka.role(kafka_role).context(InternalOperatorSnafu)?;
+    let rolegroup = kafka
+        .rolegroup(rolegroup_ref)
+        .context(InternalOperatorSnafu)?;
 
     pod_template.merge_from(role.config.pod_overrides.clone());
     pod_template.merge_from(rolegroup.config.pod_overrides.clone());
@@ -1187,13 +1993,60 @@ fn build_broker_rolegroup_statefulset(
             },
             service_name: rolegroup_ref.object_name(),
             template: pod_template,
-            volume_claim_templates: Some(pvcs),
+            volume_claim_templates: Some(volume_claim_templates),
             ..StatefulSetSpec::default()
         }),
         status: None,
     })
 }
 
+/// The ZooKeeper connection string from the ZooKeeper discovery ConfigMap, if configured.
+fn zookeeper_env_var(kafka: &KafkaCluster) -> Option<EnvVar> {
+    let zookeeper_config_map_name = kafka
+        .spec
+        .cluster_config
+        .zookeeper_config_map_name
+        .clone()?;
+    Some(EnvVar {
+        name: "ZOOKEEPER".to_string(),
+        value_from: Some(EnvVarSource {
+            config_map_key_ref: Some(ConfigMapKeySelector {
+                name: zookeeper_config_map_name,
+                key: "ZOOKEEPER".to_string(),
+                ..ConfigMapKeySelector::default()
+            }),
+            ..EnvVarSource::default()
+        }),
+        ..EnvVar::default()
+    })
+}
+
+/// Whether all replicas of the [`StatefulSet`] run the current revision and are ready.
+fn is_rolled_out(statefulset: &StatefulSet) -> bool {
+    let Some(status) = &statefulset.status else {
+        return false;
+    };
+    let replicas = statefulset
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.replicas)
+        .unwrap_or(1);
+
+    status.observed_generation == statefulset.metadata.generation
+        && status.current_revision == status.update_revision
+        && status.updated_replicas.unwrap_or_default() == replicas
+        && status.ready_replicas.unwrap_or_default() == replicas
+}
+
+/// The offset used by the container command to derive the `node.id` from the Pod ordinal.
+fn node_id_offset_env_var(rolegroup_ref: &RoleGroupRef<KafkaCluster>) -> EnvVar {
+    EnvVar {
+        name: "NODE_ID_OFFSET".to_string(),
+        value: Some(kraft::node_id_offset(rolegroup_ref).to_string()),
+        ..EnvVar::default()
+    }
+}
+
 pub fn error_policy(
     _obj: Arc<DeserializeGuard<KafkaCluster>>,
     error: &Error,
@@ -1206,23 +2059,25 @@ pub fn error_policy(
 }
 
 /// We only expose client HTTP / HTTPS and Metrics ports.
-fn listener_ports(kafka_security: &KafkaTlsSecurity) -> Vec<ListenerPort> {
-    let mut ports = vec![
-        ListenerPort {
+/// The Metrics port is only exposed by the default client listener.
+fn listener_ports(client_listener: &KafkaClientListener) -> Vec<ListenerPort> {
+    let mut ports = Vec::new();
+    if client_listener.dedicated.is_none() {
+        ports.push(ListenerPort {
             name: METRICS_PORT_NAME.to_string(),
             port: METRICS_PORT.into(),
             protocol: Some("TCP".to_string()),
-        },
-        ListenerPort {
-            name: kafka_security.client_port_name().to_string(),
-            port: kafka_security.client_port().into(),
-            protocol: Some("TCP".to_string()),
-        },
-    ];
-    if kafka_security.has_kerberos_enabled() {
+        });
+    }
+    ports.push(ListenerPort {
+        name: client_listener.port_name.clone(),
+        port: client_listener.port.into(),
+        protocol: Some("TCP".to_string()),
+    });
+    if client_listener.authentication == Some(KafkaClientAuthentication::Kerberos) {
         ports.push(ListenerPort {
-            name: kafka_security.bootstrap_port_name().to_string(),
-            port: kafka_security.bootstrap_port().into(),
+            name: KafkaTlsSecurity::BOOTSTRAP_PORT_NAME.to_string(),
+            port: client_listener.bootstrap_port().into(),
             protocol: Some("TCP".to_string()),
         });
     }
@@ -1230,28 +2085,51 @@ fn listener_ports(kafka_security: &KafkaTlsSecurity) -> Vec<ListenerPort> {
 }
 
 /// We only expose client HTTP / HTTPS and Metrics ports.
-fn container_ports(kafka_security: &KafkaTlsSecurity) -> Vec<ContainerPort> {
-    let mut ports = vec![
-        ContainerPort {
-            name: Some(METRICS_PORT_NAME.to_string()),
-            container_port: METRICS_PORT.into(),
+fn container_ports(client_listeners: &[KafkaClientListener]) -> Vec<ContainerPort> {
+    let mut ports = vec![ContainerPort {
+        name: Some(METRICS_PORT_NAME.to_string()),
+        container_port: METRICS_PORT.into(),
+        protocol: Some("TCP".to_string()),
+        ..ContainerPort::default()
+    }];
+    for client_listener in client_listeners {
+        ports.push(ContainerPort {
+            name: Some(client_listener.port_name.clone()),
+            container_port: client_listener.port.into(),
             protocol: Some("TCP".to_string()),
             ..ContainerPort::default()
-        },
+        });
+        if client_listener.authentication == Some(KafkaClientAuthentication::Kerberos) {
+            ports.push(ContainerPort {
+                name: Some(KafkaTlsSecurity::BOOTSTRAP_PORT_NAME.to_string()),
+                container_port: client_listener.bootstrap_port().into(),
+                protocol: Some("TCP".to_string()),
+                ..ContainerPort::default()
+            });
+        }
+    }
+    ports
+}
+
+/// KRaft controllers only expose the controller and Metrics ports.
+fn controller_container_ports(kafka_security: &KafkaTlsSecurity) -> Vec<ContainerPort> {
+    vec![
         ContainerPort {
-            name: Some(kafka_security.client_port_name().to_string()),
-            container_port: kafka_security.client_port().into(),
+            name: Some(METRICS_PORT_NAME.to_string()),
+            container_port: METRICS_PORT.into(),
             protocol: Some("TCP".to_string()),
             ..ContainerPort::default()
         },
-    ];
-    if kafka_security.has_kerberos_enabled() {
-        ports.push(ContainerPort {
-            name: Some(kafka_security.bootstrap_port_name().to_string()),
-            container_port: kafka_security.bootstrap_port().into(),
-            protocol: Some("TCP".to_string()),
-            ..ContainerPort::default()
-        });
+        controller_container_port(kafka_security),
+    ]
+}
+
+/// The port of the KRaft controller listener, which is also exposed by brokers in combined mode.
+fn controller_container_port(kafka_security: &KafkaTlsSecurity) -> ContainerPort {
+    ContainerPort {
+        name: Some(KafkaTlsSecurity::CONTROLLER_PORT_NAME.to_string()),
+        container_port: kafka_security.controller_port().into(),
+        protocol: Some("TCP".to_string()),
+        ..ContainerPort::default()
     }
-    ports
 }
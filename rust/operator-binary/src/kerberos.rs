@@ -1,9 +1,6 @@
 use snafu::{ResultExt, Snafu};
-use stackable_kafka_crd::{security::KafkaTlsSecurity, KafkaRole};
-use stackable_kafka_crd::{
-    LISTENER_BOOTSTRAP_VOLUME_NAME, LISTENER_BROKER_VOLUME_NAME, STACKABLE_KERBEROS_DIR,
-    STACKABLE_KERBEROS_KRB5_PATH,
-};
+use stackable_kafka_crd::{admin::admin_kerberos_service, security::KafkaTlsSecurity, KafkaRole};
+use stackable_kafka_crd::{KafkaCluster, STACKABLE_KERBEROS_DIR, STACKABLE_KERBEROS_KRB5_PATH};
 use stackable_operator::builder::{
     self,
     pod::{
@@ -39,15 +36,24 @@ pub fn add_kerberos_pod_config(
     cb_kafka: &mut ContainerBuilder,
     pb: &mut PodBuilder,
 ) -> Result<(), Error> {
-    if let Some(kerberos_secret_class) = kafka_security.kerberos_secret_class() {
-        // Mount keytab
-        let kerberos_secret_operator_volume =
-            SecretOperatorVolumeSourceBuilder::new(kerberos_secret_class)
-                .with_listener_volume_scope(LISTENER_BROKER_VOLUME_NAME)
-                .with_listener_volume_scope(LISTENER_BOOTSTRAP_VOLUME_NAME)
-                .with_kerberos_service_name(role.kerberos_service_name())
-                .build()
-                .context(KerberosSecretVolumeSnafu)?;
+    if let (Some(kerberos_secret_class), Some(kerberos_listener)) = (
+        kafka_security.kerberos_secret_class(),
+        kafka_security.kerberos_client_listener(),
+    ) {
+        // Mount keytab with the principals of the listener which serves Kerberos
+        let mut kerberos_secret_operator_volume_builder =
+            SecretOperatorVolumeSourceBuilder::new(kerberos_secret_class);
+        kerberos_secret_operator_volume_builder
+            .with_listener_volume_scope(&kerberos_listener.broker_listener_volume_name())
+            .with_listener_volume_scope(&kerberos_listener.bootstrap_listener_volume_name())
+            .with_kerberos_service_name(role.kerberos_service_name());
+        // ... and the principal of the Pod FQDN the brokers connect to each other with
+        if kafka_security.has_kerberos_inter_broker_enabled() {
+            kerberos_secret_operator_volume_builder.with_pod_scope();
+        }
+        let kerberos_secret_operator_volume = kerberos_secret_operator_volume_builder
+            .build()
+            .context(KerberosSecretVolumeSnafu)?;
         pb.add_volume(
             VolumeBuilder::new("kerberos")
                 .ephemeral(kerberos_secret_operator_volume)
@@ -55,16 +61,59 @@ pub fn add_kerberos_pod_config(
         )
         .context(AddVolumeSnafu)?;
 
-        for cb in [cb_kafka, cb_kcat_prober] {
+        // The broker extends the krb5.conf by the trusted realms at startup, which the prober
+        // does not need to connect to the broker.
+        for (cb, krb5_config_path) in [
+            (cb_kafka, kafka_security.broker_krb5_config_path()),
+            (cb_kcat_prober, STACKABLE_KERBEROS_KRB5_PATH),
+        ] {
             cb.add_volume_mount("kerberos", STACKABLE_KERBEROS_DIR)
                 .context(AddVolumeMountSnafu)?;
-            cb.add_env_var("KRB5_CONFIG", STACKABLE_KERBEROS_KRB5_PATH);
+            cb.add_env_var("KRB5_CONFIG", krb5_config_path);
             cb.add_env_var(
                 "KAFKA_OPTS",
-                format!("-Djava.security.krb5.conf={STACKABLE_KERBEROS_KRB5_PATH}",),
+                format!("-Djava.security.krb5.conf={krb5_config_path}",),
             );
         }
     }
 
     Ok(())
 }
+
+/// Mounts a keytab into admin Jobs if the brokers authenticate to each other with Kerberos, as the
+/// admin tools connect to the INTERNAL listener of the brokers.
+pub fn add_kerberos_admin_job_config(
+    kafka_security: &KafkaTlsSecurity,
+    kafka: &KafkaCluster,
+    cb_kafka: &mut ContainerBuilder,
+    pb: &mut PodBuilder,
+) -> Result<(), Error> {
+    if let (Some(kerberos_secret_class), true) = (
+        kafka_security.kerberos_secret_class(),
+        kafka_security.has_kerberos_inter_broker_enabled(),
+    ) {
+        let kerberos_secret_operator_volume =
+            SecretOperatorVolumeSourceBuilder::new(kerberos_secret_class)
+                .with_service_scope(admin_kerberos_service(kafka))
+                .with_kerberos_service_name(KafkaRole::Broker.kerberos_service_name())
+                .build()
+                .context(KerberosSecretVolumeSnafu)?;
+        pb.add_volume(
+            VolumeBuilder::new("kerberos")
+                .ephemeral(kerberos_secret_operator_volume)
+                .build(),
+        )
+        .context(AddVolumeSnafu)?;
+
+        cb_kafka
+            .add_volume_mount("kerberos", STACKABLE_KERBEROS_DIR)
+            .context(AddVolumeMountSnafu)?;
+        cb_kafka.add_env_var("KRB5_CONFIG", STACKABLE_KERBEROS_KRB5_PATH);
+        cb_kafka.add_env_var(
+            "KAFKA_OPTS",
+            format!("-Djava.security.krb5.conf={STACKABLE_KERBEROS_KRB5_PATH}",),
+        );
+    }
+
+    Ok(())
+}
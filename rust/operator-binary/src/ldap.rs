@@ -0,0 +1,78 @@
+use snafu::{ResultExt, Snafu};
+use stackable_kafka_crd::{security::KafkaTlsSecurity, STACKABLE_LDAP_BIND_CREDENTIALS_DIR};
+use stackable_operator::builder::{
+    self,
+    pod::{
+        container::ContainerBuilder,
+        volume::{
+            SecretOperatorVolumeSourceBuilder, SecretOperatorVolumeSourceBuilderError,
+            VolumeBuilder,
+        },
+        PodBuilder,
+    },
+};
+
+const LDAP_BIND_CREDENTIALS_VOLUME_NAME: &str = "ldap-bind-credentials";
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("failed to add LDAP bind credentials secret volume"))]
+    LdapBindCredentialsSecretVolume {
+        source: SecretOperatorVolumeSourceBuilderError,
+    },
+
+    #[snafu(display("failed to add needed volume"))]
+    AddVolume { source: builder::pod::Error },
+
+    #[snafu(display("failed to add needed volumeMount"))]
+    AddVolumeMount {
+        source: builder::pod::container::Error,
+    },
+}
+
+pub fn add_ldap_pod_config(
+    kafka_security: &KafkaTlsSecurity,
+    cb_kafka: &mut ContainerBuilder,
+    pb: &mut PodBuilder,
+) -> Result<(), Error> {
+    if let Some(bind_credentials) = kafka_security
+        .ldap_provider()
+        .and_then(|ldap| ldap.bind_credentials.as_ref())
+    {
+        // Mount bind credentials (the files `user` and `password`)
+        let mut bind_credentials_builder =
+            SecretOperatorVolumeSourceBuilder::new(&bind_credentials.secret_class);
+        if let Some(scope) = &bind_credentials.scope {
+            if scope.pod {
+                bind_credentials_builder.with_pod_scope();
+            }
+            if scope.node {
+                bind_credentials_builder.with_node_scope();
+            }
+            for service in &scope.services {
+                bind_credentials_builder.with_service_scope(service);
+            }
+            for listener_volume in &scope.listener_volumes {
+                bind_credentials_builder.with_listener_volume_scope(listener_volume);
+            }
+        }
+        let bind_credentials_secret_operator_volume = bind_credentials_builder
+            .build()
+            .context(LdapBindCredentialsSecretVolumeSnafu)?;
+        pb.add_volume(
+            VolumeBuilder::new(LDAP_BIND_CREDENTIALS_VOLUME_NAME)
+                .ephemeral(bind_credentials_secret_operator_volume)
+                .build(),
+        )
+        .context(AddVolumeSnafu)?;
+
+        cb_kafka
+            .add_volume_mount(
+                LDAP_BIND_CREDENTIALS_VOLUME_NAME,
+                STACKABLE_LDAP_BIND_CREDENTIALS_DIR,
+            )
+            .context(AddVolumeMountSnafu)?;
+    }
+
+    Ok(())
+}
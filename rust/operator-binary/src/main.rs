@@ -3,30 +3,49 @@ use std::sync::Arc;
 use clap::{crate_description, crate_version, Parser};
 use futures::StreamExt;
 use product_config::ProductConfigManager;
-use stackable_kafka_crd::{KafkaCluster, APP_NAME, OPERATOR_NAME};
+use stackable_kafka_crd::{
+    acl::KafkaAcl, delegation_token::KafkaDelegationToken, topic::KafkaTopic, user::KafkaUser,
+    KafkaCluster, KafkaRole, APP_NAME, OPERATOR_NAME,
+};
 use stackable_operator::{
     cli::{Command, ProductOperatorRun},
     client::{self, Client},
     commons::listener::Listener,
     k8s_openapi::api::{
         apps::v1::StatefulSet,
-        core::v1::{ConfigMap, Service, ServiceAccount},
+        batch::v1::Job,
+        core::v1::{ConfigMap, Pod, Secret, Service, ServiceAccount},
         rbac::v1::RoleBinding,
     },
     kube::core::DeserializeGuard,
-    kube::runtime::{watcher, Controller},
+    kube::runtime::{reflector::ObjectRef, watcher, Controller},
+    kube::ResourceExt,
     logging::controller::report_controller_reconciled,
     namespace::WatchNamespace,
     CustomResourceExt,
 };
 
-use crate::kafka_controller::KAFKA_CONTROLLER_NAME;
+use crate::{
+    acl_controller::ACL_CONTROLLER_NAME,
+    delegation_token_controller::DELEGATION_TOKEN_CONTROLLER_NAME,
+    kafka_controller::KAFKA_CONTROLLER_NAME, rack_awareness::needs_rack_annotation,
+    topic_controller::TOPIC_CONTROLLER_NAME, user_controller::USER_CONTROLLER_NAME,
+};
 
+mod acl_controller;
+mod admin;
+mod delegation_token;
+mod delegation_token_controller;
 mod discovery;
+mod generated_secret;
 mod kafka_controller;
 mod kerberos;
+mod ldap;
 mod operations;
 mod product_logging;
+mod rack_awareness;
+mod topic_controller;
+mod user_controller;
 mod utils;
 
 mod built_info {
@@ -53,7 +72,13 @@ struct KafkaRun {
 async fn main() -> anyhow::Result<()> {
     let opts = Opts::parse();
     match opts.cmd {
-        Command::Crd => KafkaCluster::print_yaml_schema(built_info::PKG_VERSION)?,
+        Command::Crd => {
+            KafkaCluster::print_yaml_schema(built_info::PKG_VERSION)?;
+            KafkaTopic::print_yaml_schema(built_info::PKG_VERSION)?;
+            KafkaUser::print_yaml_schema(built_info::PKG_VERSION)?;
+            KafkaAcl::print_yaml_schema(built_info::PKG_VERSION)?;
+            KafkaDelegationToken::print_yaml_schema(built_info::PKG_VERSION)?;
+        }
         Command::Run(KafkaRun {
             common:
                 ProductOperatorRun {
@@ -103,47 +128,217 @@ pub async fn create_controller(
     let kafka_controller = Controller::new(
         namespace.get_api::<DeserializeGuard<KafkaCluster>>(&client),
         watcher::Config::default(),
+    );
+    let kafka_store = kafka_controller.store();
+    let rack_kafka_store = kafka_store.clone();
+    let kafka_controller = kafka_controller
+        .owns(
+            namespace.get_api::<StatefulSet>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<Service>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<Listener>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<ConfigMap>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<ServiceAccount>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<RoleBinding>(&client),
+            watcher::Config::default(),
+        )
+        .owns(
+            namespace.get_api::<Job>(&client),
+            watcher::Config::default(),
+        )
+        // The users of a Static AuthenticationClass are bootstrapped again when its Secret changes
+        .watches(
+            namespace.get_api::<Secret>(&client),
+            watcher::Config::default(),
+            move |secret| {
+                kafka_store
+                    .state()
+                    .into_iter()
+                    .filter(move |kafka| references_scram_credentials(kafka, &secret))
+                    .map(|kafka| ObjectRef::from_obj(&*kafka))
+            },
+        )
+        // The broker Pods are annotated with their rack once they are scheduled
+        .watches(
+            namespace.get_api::<Pod>(&client),
+            watcher::Config::default().labels(&format!(
+                "app.kubernetes.io/name={APP_NAME},app.kubernetes.io/component={}",
+                KafkaRole::Broker
+            )),
+            move |pod| {
+                let instance = pod.labels().get("app.kubernetes.io/instance")?;
+                let kafka_ref = ObjectRef::new(instance).within(&pod.namespace()?);
+                rack_kafka_store
+                    .get(&kafka_ref)
+                    .is_some_and(|kafka| awaits_rack_annotation(&kafka, &pod))
+                    .then_some(kafka_ref)
+            },
+        )
+        .shutdown_on_signal()
+        .run(
+            kafka_controller::reconcile_kafka,
+            kafka_controller::error_policy,
+            Arc::new(kafka_controller::Ctx {
+                client: client.clone(),
+                product_config,
+            }),
+        )
+        .map(|res| {
+            report_controller_reconciled(
+                &client,
+                &format!("{KAFKA_CONTROLLER_NAME}.{OPERATOR_NAME}"),
+                &res,
+            );
+        });
+
+    let topic_controller = Controller::new(
+        namespace.get_api::<DeserializeGuard<KafkaTopic>>(&client),
+        watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<StatefulSet>(&client),
+        namespace.get_api::<Job>(&client),
+        watcher::Config::default(),
+    )
+    .shutdown_on_signal()
+    .run(
+        topic_controller::reconcile_topic,
+        topic_controller::error_policy,
+        Arc::new(topic_controller::Ctx {
+            client: client.clone(),
+        }),
+    )
+    .map(|res| {
+        report_controller_reconciled(
+            &client,
+            &format!("{TOPIC_CONTROLLER_NAME}.{OPERATOR_NAME}"),
+            &res,
+        );
+    });
+
+    let user_controller = Controller::new(
+        namespace.get_api::<DeserializeGuard<KafkaUser>>(&client),
         watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<Service>(&client),
+        namespace.get_api::<Job>(&client),
         watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<Listener>(&client),
+        namespace.get_api::<Secret>(&client),
+        watcher::Config::default(),
+    )
+    .shutdown_on_signal()
+    .run(
+        user_controller::reconcile_user,
+        user_controller::error_policy,
+        Arc::new(user_controller::Ctx {
+            client: client.clone(),
+        }),
+    )
+    .map(|res| {
+        report_controller_reconciled(
+            &client,
+            &format!("{USER_CONTROLLER_NAME}.{OPERATOR_NAME}"),
+            &res,
+        );
+    });
+
+    let acl_controller = Controller::new(
+        namespace.get_api::<DeserializeGuard<KafkaAcl>>(&client),
         watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<ConfigMap>(&client),
+        namespace.get_api::<Job>(&client),
+        watcher::Config::default(),
+    )
+    .shutdown_on_signal()
+    .run(
+        acl_controller::reconcile_acl,
+        acl_controller::error_policy,
+        Arc::new(acl_controller::Ctx {
+            client: client.clone(),
+        }),
+    )
+    .map(|res| {
+        report_controller_reconciled(
+            &client,
+            &format!("{ACL_CONTROLLER_NAME}.{OPERATOR_NAME}"),
+            &res,
+        );
+    });
+
+    let delegation_token_controller = Controller::new(
+        namespace.get_api::<DeserializeGuard<KafkaDelegationToken>>(&client),
         watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<ServiceAccount>(&client),
+        namespace.get_api::<Job>(&client),
         watcher::Config::default(),
     )
     .owns(
-        namespace.get_api::<RoleBinding>(&client),
+        namespace.get_api::<Secret>(&client),
         watcher::Config::default(),
     )
     .shutdown_on_signal()
     .run(
-        kafka_controller::reconcile_kafka,
-        kafka_controller::error_policy,
-        Arc::new(kafka_controller::Ctx {
+        delegation_token_controller::reconcile_delegation_token,
+        delegation_token_controller::error_policy,
+        Arc::new(delegation_token_controller::Ctx {
             client: client.clone(),
-            product_config,
         }),
     )
     .map(|res| {
         report_controller_reconciled(
             &client,
-            &format!("{KAFKA_CONTROLLER_NAME}.{OPERATOR_NAME}"),
+            &format!("{DELEGATION_TOKEN_CONTROLLER_NAME}.{OPERATOR_NAME}"),
             &res,
         );
     });
 
-    kafka_controller.collect::<()>().await;
+    futures::stream::select(
+        futures::stream::select(kafka_controller, topic_controller),
+        futures::stream::select(
+            futures::stream::select(user_controller, acl_controller),
+            delegation_token_controller,
+        ),
+    )
+    .collect::<()>()
+    .await;
+}
+
+/// Whether the Pod is a broker of a rack aware cluster, which the operator has to annotate with its
+/// rack. Other changes of the Pods, e.g. of their status, do not need a reconciliation.
+fn awaits_rack_annotation(kafka: &DeserializeGuard<KafkaCluster>, pod: &Pod) -> bool {
+    let Ok(kafka) = &kafka.0 else {
+        return false;
+    };
+    kafka.spec.cluster_config.rack_awareness.is_some() && needs_rack_annotation(pod)
+}
+
+/// Whether the cluster bootstraps the users of the given Secret as SCRAM credentials. The name of
+/// the Secret is taken from the status, as it is referenced by a cluster-scoped AuthenticationClass.
+fn references_scram_credentials(kafka: &DeserializeGuard<KafkaCluster>, secret: &Secret) -> bool {
+    let Ok(kafka) = &kafka.0 else {
+        return false;
+    };
+    kafka.namespace() == secret.namespace()
+        && kafka
+            .status
+            .as_ref()
+            .and_then(|status| status.scram_credentials_secret.as_deref())
+            == Some(secret.name_any().as_str())
 }
@@ -33,6 +33,7 @@ pub async fn add_pdbs(
     }
     let max_unavailable = pdb.max_unavailable.unwrap_or(match role {
         KafkaRole::Broker => max_unavailable_brokers(),
+        KafkaRole::Controller => max_unavailable_controllers(),
     });
     let pdb = PodDisruptionBudgetBuilder::new_with_role(
         kafka,
@@ -59,3 +60,9 @@ fn max_unavailable_brokers() -> u16 {
     // We can not make any assumptions about topic replication factors.
     1
 }
+
+fn max_unavailable_controllers() -> u16 {
+    // The controller quorum tolerates the loss of a minority of its voters, which is at least one
+    // for the recommended (odd, >= 3) number of controllers.
+    1
+}
@@ -0,0 +1,162 @@
+//! Annotates the broker Pods with the rack of their node, as node labels are not available with
+//! the downward API. The annotation of a Pod is never changed, a Pod which is moved to another
+//! node is recreated anyway.
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{
+    rack_awareness::{KafkaRackAwareness, RACK_ANNOTATION},
+    KafkaCluster, KafkaRole, APP_NAME,
+};
+use stackable_operator::{
+    client::Client,
+    k8s_openapi::{
+        api::core::v1::{Node, Pod},
+        apimachinery::pkg::apis::meta::v1::LabelSelector,
+    },
+    kube::{runtime::reflector::ObjectRef, ResourceExt},
+    kvp::Labels,
+};
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("failed to build the label selector of the broker Pods"))]
+    LabelBuild {
+        source: stackable_operator::kvp::LabelError,
+    },
+
+    #[snafu(display("failed to list the broker Pods"))]
+    ListPods {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("failed to retrieve {node} of {pod}"))]
+    RetrieveNode {
+        source: stackable_operator::client::Error,
+        node: ObjectRef<Node>,
+        pod: ObjectRef<Pod>,
+    },
+
+    #[snafu(display(
+        "{node} of {pod} has no label [{label}], which is required for rack awareness"
+    ))]
+    MissingNodeLabel {
+        node: ObjectRef<Node>,
+        pod: ObjectRef<Pod>,
+        label: String,
+    },
+
+    #[snafu(display("failed to annotate {pod} with its rack"))]
+    AnnotatePod {
+        source: stackable_operator::client::Error,
+        pod: ObjectRef<Pod>,
+    },
+}
+
+/// Whether the Pod is a scheduled broker Pod, which is not annotated with its rack yet.
+pub fn needs_rack_annotation(pod: &Pod) -> bool {
+    pod.labels().get("app.kubernetes.io/component") == Some(&KafkaRole::Broker.to_string())
+        && !pod.annotations().contains_key(RACK_ANNOTATION)
+        && pod
+            .spec
+            .as_ref()
+            .is_some_and(|spec| spec.node_name.is_some())
+}
+
+/// Annotates all scheduled broker Pods, which are not annotated yet, with the value of the node
+/// label of the rack awareness settings. The brokers wait for the annotation before they start.
+///
+/// A Pod whose rack cannot be determined, e.g. because its node lacks the label, does not prevent
+/// the other Pods from being annotated. The errors of these Pods are returned instead.
+pub async fn annotate_broker_pods(
+    client: &Client,
+    kafka: &KafkaCluster,
+    rack_awareness: &KafkaRackAwareness,
+) -> Result<Vec<Error>, Error> {
+    let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let selector = LabelSelector {
+        match_labels: Some(
+            Labels::role_selector(kafka, APP_NAME, &KafkaRole::Broker.to_string())
+                .context(LabelBuildSnafu)?
+                .into(),
+        ),
+        ..LabelSelector::default()
+    };
+    let pods = client
+        .list_with_label_selector::<Pod>(&namespace, &selector)
+        .await
+        .context(ListPodsSnafu)?;
+
+    // The brokers of a node share its rack
+    let mut racks = BTreeMap::new();
+    let mut errors = Vec::new();
+    // Pods which are not scheduled yet are annotated on a later reconciliation
+    for pod in pods.into_iter().filter(needs_rack_annotation) {
+        let pod_ref = ObjectRef::from_obj(&pod);
+        let node_name = pod
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.node_name.clone())
+            .unwrap_or_default();
+
+        if !racks.contains_key(&node_name) {
+            match node_rack(client, &node_name, &pod_ref, &rack_awareness.node_label).await {
+                Ok(rack) => racks.insert(node_name.clone(), rack),
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+        }
+        let rack = &racks[&node_name];
+
+        tracing::info!(
+            pod.name = %pod.name_any(),
+            node.name = %node_name,
+            rack = %rack,
+            "Annotating broker Pod with its rack"
+        );
+        if let Err(error) = client
+            .merge_patch(
+                &pod,
+                json!({ "metadata": { "annotations": { RACK_ANNOTATION: rack } } }),
+            )
+            .await
+            .context(AnnotatePodSnafu { pod: pod_ref })
+        {
+            errors.push(error);
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Returns the value of the given label of the node the Pod is scheduled on.
+async fn node_rack(
+    client: &Client,
+    node_name: &str,
+    pod: &ObjectRef<Pod>,
+    label: &str,
+) -> Result<String, Error> {
+    let node_ref = ObjectRef::<Node>::new(node_name);
+    let node = client
+        .get::<Node>(node_name, &())
+        .await
+        .with_context(|_| RetrieveNodeSnafu {
+            node: node_ref.clone(),
+            pod: pod.clone(),
+        })?;
+
+    node.labels()
+        .get(label)
+        .cloned()
+        .with_context(|| MissingNodeLabelSnafu {
+            node: node_ref,
+            pod: pod.clone(),
+            label,
+        })
+}
@@ -0,0 +1,278 @@
+//! Ensures that the topic declared by a [`KafkaTopic`] exists in the referenced [`KafkaCluster`]
+use std::sync::Arc;
+
+use snafu::{OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{
+    admin::{DeletionPolicy, SyncState, SyncStatus},
+    security::KafkaTlsSecurity,
+    topic::{self, KafkaTopic},
+    KafkaCluster, OPERATOR_NAME,
+};
+use stackable_operator::{
+    k8s_openapi::api::batch::v1::Job,
+    kube::{
+        api::DynamicObject,
+        core::{error_boundary, DeserializeGuard},
+        runtime::{
+            controller::Action,
+            finalizer::{self, finalizer},
+            reflector::ObjectRef,
+        },
+        ResourceExt,
+    },
+    logging::controller::ReconcilerError,
+    time::Duration,
+};
+use strum::{EnumDiscriminants, IntoStaticStr};
+
+use crate::admin::{self, admin_job_name, admin_job_state, build_admin_job, AdminJobState};
+
+pub const TOPIC_CONTROLLER_NAME: &str = "kafkatopic";
+const TOPIC_FINALIZER: &str = "kafka.stackable.tech/topic";
+
+pub struct Ctx {
+    pub client: stackable_operator::client::Client,
+}
+
+#[derive(Snafu, Debug, EnumDiscriminants)]
+#[strum_discriminants(derive(IntoStaticStr))]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("KafkaTopic object is invalid"))]
+    InvalidKafkaTopic {
+        source: error_boundary::InvalidObject,
+    },
+
+    #[snafu(display("invalid topic"))]
+    InvalidTopic { source: topic::Error },
+
+    #[snafu(display("failed to retrieve {kafka}"))]
+    RetrieveKafkaCluster {
+        source: stackable_operator::client::Error,
+        kafka: ObjectRef<KafkaCluster>,
+    },
+
+    #[snafu(display("{kafka} not found"))]
+    KafkaClusterNotFound { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display("failed to initialize security context"))]
+    FailedToInitializeSecurityContext {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to build admin Job"))]
+    BuildAdminJob { source: admin::Error },
+
+    #[snafu(display("failed to apply admin Job"))]
+    ApplyAdminJob {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("the topic is not deleted yet, waiting for Job [{job_name}]"))]
+    DeletionPending { job_name: String },
+
+    #[snafu(display("failed to update status"))]
+    ApplyStatus {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("error managing finalizer"))]
+    Finalizer {
+        source: Box<finalizer::Error<Error>>,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl ReconcilerError for Error {
+    fn category(&self) -> &'static str {
+        ErrorDiscriminants::from(self).into()
+    }
+
+    fn secondary_object(&self) -> Option<ObjectRef<DynamicObject>> {
+        match self {
+            Error::ObjectHasNoNamespace => None,
+            Error::InvalidKafkaTopic { .. } => None,
+            Error::InvalidTopic { .. } => None,
+            Error::RetrieveKafkaCluster { kafka, .. } => Some(kafka.clone().erase()),
+            Error::KafkaClusterNotFound { kafka } => Some(kafka.clone().erase()),
+            Error::FailedToInitializeSecurityContext { .. } => None,
+            Error::BuildAdminJob { .. } => None,
+            Error::ApplyAdminJob { .. } => None,
+            Error::DeletionPending { .. } => None,
+            Error::ApplyStatus { .. } => None,
+            Error::Finalizer { .. } => None,
+        }
+    }
+}
+
+pub async fn reconcile_topic(
+    topic: Arc<DeserializeGuard<KafkaTopic>>,
+    ctx: Arc<Ctx>,
+) -> Result<Action> {
+    tracing::info!("Starting reconcile");
+
+    let topic = topic
+        .0
+        .as_ref()
+        .map_err(error_boundary::InvalidObject::clone)
+        .context(InvalidKafkaTopicSnafu)?;
+    let namespace = topic.namespace().context(ObjectHasNoNamespaceSnafu)?;
+
+    finalizer(
+        &ctx.client.get_api::<KafkaTopic>(&namespace),
+        TOPIC_FINALIZER,
+        Arc::new(topic.clone()),
+        |event| async move {
+            match event {
+                finalizer::Event::Apply(topic) => apply_topic(&topic, &ctx).await,
+                finalizer::Event::Cleanup(topic) => cleanup_topic(&topic, &ctx).await,
+            }
+        },
+    )
+    .await
+    .map_err(|source| Error::Finalizer {
+        source: Box::new(source),
+    })
+}
+
+/// Runs an admin Job for the current generation of the topic, unless it is already synced, and
+/// reports the outcome in the status.
+async fn apply_topic(topic: &KafkaTopic, ctx: &Ctx) -> Result<Action> {
+    if let Some(status) = &topic.status {
+        if status.sync_state == SyncState::Synced
+            && status.observed_generation == topic.metadata.generation
+        {
+            return Ok(Action::await_change());
+        }
+    }
+
+    let job_name = admin_job_name(
+        &topic.name_any(),
+        "topic",
+        &[&topic.metadata.generation.unwrap_or_default().to_string()],
+    );
+    let result = match topic.apply_commands().context(InvalidTopicSnafu) {
+        Ok(commands) => apply_admin_job(topic, ctx, &job_name, &commands).await,
+        Err(err) => Err(err),
+    };
+
+    let (sync_state, message) = match &result {
+        Ok(AdminJobState::Running) => (SyncState::Pending, None),
+        Ok(AdminJobState::Succeeded) => (SyncState::Synced, None),
+        Ok(AdminJobState::Failed) => (
+            SyncState::Failed,
+            Some(format!(
+                "the admin Job [{job_name}] failed, check its logs for details"
+            )),
+        ),
+        Err(err) => (SyncState::Failed, Some(err.to_string())),
+    };
+    update_status(topic, ctx, sync_state, message).await?;
+
+    match result {
+        // Retrying does not help, the spec has to be changed
+        Err(Error::InvalidTopic { .. }) => Ok(Action::await_change()),
+        result => result.map(|_| Action::await_change()),
+    }
+}
+
+/// Deletes the topic in Kafka if requested by the deletion policy. The finalizer is only removed
+/// once the admin Job succeeded or if there is nothing to delete.
+async fn cleanup_topic(topic: &KafkaTopic, ctx: &Ctx) -> Result<Action> {
+    if topic.spec.deletion_policy == DeletionPolicy::Retain {
+        return Ok(Action::await_change());
+    }
+    // Topics with an invalid name cannot have been created
+    let Ok(commands) = topic.delete_commands() else {
+        return Ok(Action::await_change());
+    };
+
+    let job_name = admin_job_name(&topic.name_any(), "topic", &["delete"]);
+    match apply_admin_job(topic, ctx, &job_name, &commands).await {
+        Ok(AdminJobState::Succeeded) => Ok(Action::await_change()),
+        Ok(state) => {
+            let message = (state == AdminJobState::Failed)
+                .then(|| format!("the admin Job [{job_name}] failed, check its logs for details"));
+            update_status(topic, ctx, SyncState::Deleting, message).await?;
+            DeletionPendingSnafu { job_name }.fail()
+        }
+        // There is nothing to delete if the cluster does not exist anymore
+        Err(Error::KafkaClusterNotFound { .. }) => Ok(Action::await_change()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Applies an admin Job, which runs the given commands against the cluster of the topic.
+async fn apply_admin_job(
+    topic: &KafkaTopic,
+    ctx: &Ctx,
+    job_name: &str,
+    commands: &str,
+) -> Result<AdminJobState> {
+    let client = &ctx.client;
+    let namespace = topic.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let kafka_ref = ObjectRef::<KafkaCluster>::new(&topic.spec.cluster_ref.name).within(&namespace);
+
+    let kafka = client
+        .get_opt::<KafkaCluster>(&topic.spec.cluster_ref.name, &namespace)
+        .await
+        .with_context(|_| RetrieveKafkaClusterSnafu {
+            kafka: kafka_ref.clone(),
+        })?
+        .with_context(|| KafkaClusterNotFoundSnafu { kafka: kafka_ref })?;
+
+    let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(client, &kafka)
+        .await
+        .context(FailedToInitializeSecurityContextSnafu)?;
+
+    let job = build_admin_job(
+        &kafka,
+        topic,
+        TOPIC_CONTROLLER_NAME,
+        job_name,
+        commands,
+        vec![],
+        &kafka_security,
+        &client.kubernetes_cluster_info,
+    )
+    .context(BuildAdminJobSnafu)?;
+    let job: Job = client
+        .apply_patch(OPERATOR_NAME, &job, &job)
+        .await
+        .context(ApplyAdminJobSnafu)?;
+
+    Ok(admin_job_state(&job))
+}
+
+async fn update_status(
+    topic: &KafkaTopic,
+    ctx: &Ctx,
+    sync_state: SyncState,
+    message: Option<String>,
+) -> Result<()> {
+    let status = SyncStatus::new(
+        sync_state,
+        topic.metadata.generation,
+        message,
+        topic.status.as_ref(),
+    );
+    ctx.client
+        .apply_patch_status(OPERATOR_NAME, topic, &status)
+        .await
+        .context(ApplyStatusSnafu)?;
+
+    Ok(())
+}
+
+pub fn error_policy(
+    _obj: Arc<DeserializeGuard<KafkaTopic>>,
+    error: &Error,
+    _ctx: Arc<Ctx>,
+) -> Action {
+    match error {
+        Error::InvalidKafkaTopic { .. } => Action::await_change(),
+        _ => Action::requeue(*Duration::from_secs(5)),
+    }
+}
@@ -0,0 +1,528 @@
+//! Provisions the SCRAM credentials declared by a [`KafkaUser`] in the referenced [`KafkaCluster`]
+//! and writes them to a [`Secret`]
+use std::{collections::BTreeMap, sync::Arc};
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use stackable_kafka_crd::{
+    admin::{SyncState, SyncStatus},
+    listener::KafkaClientAuthentication,
+    security::KafkaTlsSecurity,
+    user::{
+        self, jaas_config, KafkaUser, KafkaUserStatus, BOOTSTRAP_SERVERS_SECRET_KEY, PASSWORD_ENV,
+        PASSWORD_SECRET_KEY, SASL_JAAS_CONFIG_SECRET_KEY, SASL_MECHANISM_SECRET_KEY,
+        SCRAM_MECHANISM, USERNAME_SECRET_KEY,
+    },
+    KafkaCluster, APP_NAME, DOCKER_IMAGE_BASE_NAME, OPERATOR_NAME,
+};
+use stackable_operator::{
+    builder::meta::ObjectMetaBuilder,
+    k8s_openapi::{
+        api::{
+            batch::v1::Job,
+            core::v1::{ConfigMap, EnvVar, EnvVarSource, Secret, SecretKeySelector},
+        },
+        ByteString,
+    },
+    kube::{
+        api::DynamicObject,
+        core::{error_boundary, DeserializeGuard},
+        runtime::{
+            controller::Action,
+            finalizer::{self, finalizer},
+            reflector::ObjectRef,
+        },
+        ResourceExt,
+    },
+    kvp::ObjectLabels,
+    logging::controller::ReconcilerError,
+    time::Duration,
+};
+use strum::{EnumDiscriminants, IntoStaticStr};
+
+use crate::{
+    admin::{self, admin_job_name, admin_job_state, build_admin_job, stable_hash, AdminJobState},
+    utils::{generate_password, is_owned_by},
+};
+
+pub const USER_CONTROLLER_NAME: &str = "kafkauser";
+const USER_FINALIZER: &str = "kafka.stackable.tech/user";
+
+pub struct Ctx {
+    pub client: stackable_operator::client::Client,
+}
+
+#[derive(Snafu, Debug, EnumDiscriminants)]
+#[strum_discriminants(derive(IntoStaticStr))]
+pub enum Error {
+    #[snafu(display("object has no namespace"))]
+    ObjectHasNoNamespace,
+
+    #[snafu(display("KafkaUser object is invalid"))]
+    InvalidKafkaUser {
+        source: error_boundary::InvalidObject,
+    },
+
+    #[snafu(display("invalid user"))]
+    InvalidUser { source: user::Error },
+
+    #[snafu(display("failed to retrieve {kafka}"))]
+    RetrieveKafkaCluster {
+        source: stackable_operator::client::Error,
+        kafka: ObjectRef<KafkaCluster>,
+    },
+
+    #[snafu(display("{kafka} not found"))]
+    KafkaClusterNotFound { kafka: ObjectRef<KafkaCluster> },
+
+    #[snafu(display("failed to retrieve discovery {config_map}"))]
+    RetrieveDiscoveryConfigMap {
+        source: stackable_operator::client::Error,
+        config_map: ObjectRef<ConfigMap>,
+    },
+
+    #[snafu(display("failed to retrieve {secret}"))]
+    RetrieveSecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display(
+        "{secret} already exists and is not owned by the KafkaUser, choose another secretName"
+    ))]
+    SecretNotOwned { secret: ObjectRef<Secret> },
+
+    #[snafu(display("failed to generate password"))]
+    GeneratePassword { source: std::io::Error },
+
+    #[snafu(display("object is missing metadata to build owner reference"))]
+    ObjectMissingMetadataForOwnerRef {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to build Metadata"))]
+    MetadataBuild {
+        source: stackable_operator::builder::meta::Error,
+    },
+
+    #[snafu(display("failed to apply {secret}"))]
+    ApplySecret {
+        source: stackable_operator::client::Error,
+        secret: ObjectRef<Secret>,
+    },
+
+    #[snafu(display("failed to initialize security context"))]
+    FailedToInitializeSecurityContext {
+        source: stackable_kafka_crd::security::Error,
+    },
+
+    #[snafu(display("failed to build admin Job"))]
+    BuildAdminJob { source: admin::Error },
+
+    #[snafu(display("failed to apply admin Job"))]
+    ApplyAdminJob {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("the credentials are not deleted yet, waiting for Job [{job_name}]"))]
+    DeletionPending { job_name: String },
+
+    #[snafu(display("failed to update status"))]
+    ApplyStatus {
+        source: stackable_operator::client::Error,
+    },
+
+    #[snafu(display("error managing finalizer"))]
+    Finalizer {
+        source: Box<finalizer::Error<Error>>,
+    },
+}
+type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl ReconcilerError for Error {
+    fn category(&self) -> &'static str {
+        ErrorDiscriminants::from(self).into()
+    }
+
+    fn secondary_object(&self) -> Option<ObjectRef<DynamicObject>> {
+        match self {
+            Error::ObjectHasNoNamespace => None,
+            Error::InvalidKafkaUser { .. } => None,
+            Error::InvalidUser { .. } => None,
+            Error::RetrieveKafkaCluster { kafka, .. } => Some(kafka.clone().erase()),
+            Error::KafkaClusterNotFound { kafka } => Some(kafka.clone().erase()),
+            Error::RetrieveDiscoveryConfigMap { config_map, .. } => {
+                Some(config_map.clone().erase())
+            }
+            Error::RetrieveSecret { secret, .. } => Some(secret.clone().erase()),
+            Error::SecretNotOwned { secret } => Some(secret.clone().erase()),
+            Error::GeneratePassword { .. } => None,
+            Error::ObjectMissingMetadataForOwnerRef { .. } => None,
+            Error::MetadataBuild { .. } => None,
+            Error::ApplySecret { secret, .. } => Some(secret.clone().erase()),
+            Error::FailedToInitializeSecurityContext { .. } => None,
+            Error::BuildAdminJob { .. } => None,
+            Error::ApplyAdminJob { .. } => None,
+            Error::DeletionPending { .. } => None,
+            Error::ApplyStatus { .. } => None,
+            Error::Finalizer { .. } => None,
+        }
+    }
+}
+
+pub async fn reconcile_user(
+    user: Arc<DeserializeGuard<KafkaUser>>,
+    ctx: Arc<Ctx>,
+) -> Result<Action> {
+    tracing::info!("Starting reconcile");
+
+    let user = user
+        .0
+        .as_ref()
+        .map_err(error_boundary::InvalidObject::clone)
+        .context(InvalidKafkaUserSnafu)?;
+    let namespace = user.namespace().context(ObjectHasNoNamespaceSnafu)?;
+
+    finalizer(
+        &ctx.client.get_api::<KafkaUser>(&namespace),
+        USER_FINALIZER,
+        Arc::new(user.clone()),
+        |event| async move {
+            match event {
+                finalizer::Event::Apply(user) => apply_user(&user, &ctx).await,
+                finalizer::Event::Cleanup(user) => cleanup_user(&user, &ctx).await,
+            }
+        },
+    )
+    .await
+    .map_err(|source| Error::Finalizer {
+        source: Box::new(source),
+    })
+}
+
+/// Writes the credentials to the Secret and runs an admin Job to provision them, unless they are
+/// already synced. The outcome is reported in the status.
+async fn apply_user(user: &KafkaUser, ctx: &Ctx) -> Result<Action> {
+    let result = sync_user(user, ctx).await;
+
+    let (sync_state, message) = match &result {
+        Ok(UserSync {
+            state: AdminJobState::Running,
+            ..
+        }) => (SyncState::Pending, None),
+        Ok(UserSync {
+            state: AdminJobState::Succeeded,
+            ..
+        }) => (SyncState::Synced, None),
+        Ok(UserSync {
+            state: AdminJobState::Failed,
+            job_name,
+            ..
+        }) => (
+            SyncState::Failed,
+            Some(format!(
+                "the admin Job [{job_name}] failed, check its logs for details"
+            )),
+        ),
+        Err(err) => (SyncState::Failed, Some(err.to_string())),
+    };
+    // Only a provisioned password is recorded, otherwise it would not be provisioned again
+    let password_hash = match &result {
+        Ok(UserSync {
+            state: AdminJobState::Succeeded,
+            password_hash,
+            ..
+        }) => Some(password_hash.clone()),
+        _ => user
+            .status
+            .as_ref()
+            .and_then(|status| status.password_hash.clone()),
+    };
+    update_status(user, ctx, sync_state, message, password_hash).await?;
+
+    match result {
+        // Retrying does not help, the spec has to be changed
+        Err(Error::InvalidUser { .. }) => Ok(Action::await_change()),
+        result => result.map(|_| Action::await_change()),
+    }
+}
+
+/// The outcome of [`sync_user`]
+struct UserSync {
+    state: AdminJobState,
+    job_name: String,
+    password_hash: String,
+}
+
+async fn sync_user(user: &KafkaUser, ctx: &Ctx) -> Result<UserSync> {
+    let client = &ctx.client;
+    let namespace = user.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let user_name = user.user_name().context(InvalidUserSnafu)?;
+    let kafka = get_kafka_cluster(user, ctx).await?;
+
+    let secret_name = user.secret_name();
+    let secret_ref = ObjectRef::<Secret>::new(&secret_name).within(&namespace);
+    let existing_secret = client
+        .get_opt::<Secret>(&secret_name, &namespace)
+        .await
+        .with_context(|_| RetrieveSecretSnafu {
+            secret: secret_ref.clone(),
+        })?;
+    // Otherwise the credentials of another application could be read or overwritten by choosing
+    // its Secret as secretName
+    if let Some(existing_secret) = &existing_secret {
+        ensure!(
+            is_owned_by(existing_secret, user),
+            SecretNotOwnedSnafu {
+                secret: secret_ref.clone(),
+            }
+        );
+    }
+    let existing_password = existing_secret
+        .and_then(|secret| secret.data)
+        .and_then(|mut data| data.remove(PASSWORD_SECRET_KEY))
+        .and_then(|password| String::from_utf8(password.0).ok());
+    let password = match existing_password {
+        Some(password) => password,
+        None => generate_password().context(GeneratePasswordSnafu)?,
+    };
+
+    let mut data = BTreeMap::from([
+        (USERNAME_SECRET_KEY.to_string(), user_name.clone()),
+        (PASSWORD_SECRET_KEY.to_string(), password.clone()),
+        (
+            SASL_MECHANISM_SECRET_KEY.to_string(),
+            SCRAM_MECHANISM.to_string(),
+        ),
+        (
+            SASL_JAAS_CONFIG_SECRET_KEY.to_string(),
+            jaas_config(&user_name, &password),
+        ),
+    ]);
+    if let Some(bootstrap_servers) = bootstrap_servers(&kafka, ctx).await? {
+        data.insert(BOOTSTRAP_SERVERS_SECRET_KEY.to_string(), bootstrap_servers);
+    }
+
+    let resolved_product_image = kafka
+        .spec
+        .image
+        .resolve(DOCKER_IMAGE_BASE_NAME, crate::built_info::PKG_VERSION);
+    let secret = Secret {
+        metadata: ObjectMetaBuilder::new()
+            .name_and_namespace(user)
+            .name(&secret_name)
+            .ownerreference_from_resource(user, None, Some(true))
+            .context(ObjectMissingMetadataForOwnerRefSnafu)?
+            .with_recommended_labels(ObjectLabels {
+                owner: user,
+                app_name: APP_NAME,
+                app_version: &resolved_product_image.app_version_label,
+                operator_name: OPERATOR_NAME,
+                controller_name: USER_CONTROLLER_NAME,
+                role: "user",
+                role_group: "default",
+            })
+            .context(MetadataBuildSnafu)?
+            .build(),
+        data: Some(
+            data.into_iter()
+                .map(|(key, value)| (key, ByteString(value.into_bytes())))
+                .collect(),
+        ),
+        ..Secret::default()
+    };
+    client
+        .apply_patch(OPERATOR_NAME, &secret, &secret)
+        .await
+        .with_context(|_| ApplySecretSnafu {
+            secret: secret_ref.clone(),
+        })?;
+
+    // A new Job is needed for every generation of the KafkaUser and for every password, as the
+    // password in the Secret could have been changed. The uid salts the hash, so that it does not
+    // reveal whether two users share a password.
+    let password_hash = stable_hash(&format!(
+        "{}/{password}",
+        user.metadata.uid.as_deref().unwrap_or_default()
+    ));
+    let job_name = admin_job_name(
+        &user.name_any(),
+        "user",
+        &[
+            &user.metadata.generation.unwrap_or_default().to_string(),
+            &password_hash,
+        ],
+    );
+    if let Some(status) = &user.status {
+        if status.sync.sync_state == SyncState::Synced
+            && status.sync.observed_generation == user.metadata.generation
+            && status.password_hash.as_ref() == Some(&password_hash)
+        {
+            return Ok(UserSync {
+                state: AdminJobState::Succeeded,
+                job_name,
+                password_hash,
+            });
+        }
+    }
+
+    let password_env = EnvVar {
+        name: PASSWORD_ENV.to_string(),
+        value_from: Some(EnvVarSource {
+            secret_key_ref: Some(SecretKeySelector {
+                name: secret_name,
+                key: PASSWORD_SECRET_KEY.to_string(),
+                ..SecretKeySelector::default()
+            }),
+            ..EnvVarSource::default()
+        }),
+        ..EnvVar::default()
+    };
+    let commands = user.apply_commands().context(InvalidUserSnafu)?;
+    let state =
+        apply_admin_job(user, &kafka, ctx, &job_name, &commands, vec![password_env]).await?;
+
+    Ok(UserSync {
+        state,
+        job_name,
+        password_hash,
+    })
+}
+
+/// Deletes the SCRAM credentials in Kafka. The finalizer is only removed once the admin Job
+/// succeeded or if there is nothing to delete.
+async fn cleanup_user(user: &KafkaUser, ctx: &Ctx) -> Result<Action> {
+    // Users with an invalid name cannot have been created
+    let Ok(commands) = user.delete_commands() else {
+        return Ok(Action::await_change());
+    };
+    let kafka = match get_kafka_cluster(user, ctx).await {
+        Ok(kafka) => kafka,
+        // There is nothing to delete if the cluster does not exist anymore
+        Err(Error::KafkaClusterNotFound { .. }) => return Ok(Action::await_change()),
+        Err(err) => return Err(err),
+    };
+
+    let job_name = admin_job_name(&user.name_any(), "user", &["delete"]);
+    match apply_admin_job(user, &kafka, ctx, &job_name, &commands, vec![]).await? {
+        AdminJobState::Succeeded => Ok(Action::await_change()),
+        state => {
+            let message = (state == AdminJobState::Failed)
+                .then(|| format!("the admin Job [{job_name}] failed, check its logs for details"));
+            let password_hash = user
+                .status
+                .as_ref()
+                .and_then(|status| status.password_hash.clone());
+            update_status(user, ctx, SyncState::Deleting, message, password_hash).await?;
+            DeletionPendingSnafu { job_name }.fail()
+        }
+    }
+}
+
+async fn get_kafka_cluster(user: &KafkaUser, ctx: &Ctx) -> Result<KafkaCluster> {
+    let namespace = user.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let kafka_ref = ObjectRef::<KafkaCluster>::new(&user.spec.cluster_ref.name).within(&namespace);
+
+    ctx.client
+        .get_opt::<KafkaCluster>(&user.spec.cluster_ref.name, &namespace)
+        .await
+        .with_context(|_| RetrieveKafkaClusterSnafu {
+            kafka: kafka_ref.clone(),
+        })?
+        .with_context(|| KafkaClusterNotFoundSnafu { kafka: kafka_ref })
+}
+
+/// The bootstrap servers from the discovery ConfigMap of the listener which serves SCRAM, which
+/// is only available once the bootstrap Listeners are ready.
+async fn bootstrap_servers(kafka: &KafkaCluster, ctx: &Ctx) -> Result<Option<String>> {
+    let namespace = kafka.namespace().context(ObjectHasNoNamespaceSnafu)?;
+    let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(&ctx.client, kafka)
+        .await
+        .context(FailedToInitializeSecurityContextSnafu)?;
+    let config_map_name = kafka_security
+        .client_listeners()
+        .into_iter()
+        .find(|client_listener| {
+            client_listener.authentication == Some(KafkaClientAuthentication::Scram)
+        })
+        .map(|client_listener| client_listener.discovery_config_map_name(&kafka.name_any()))
+        .unwrap_or_else(|| kafka.name_any());
+    let config_map_ref = ObjectRef::<ConfigMap>::new(&config_map_name).within(&namespace);
+
+    Ok(ctx
+        .client
+        .get_opt::<ConfigMap>(&config_map_name, &namespace)
+        .await
+        .context(RetrieveDiscoveryConfigMapSnafu {
+            config_map: config_map_ref,
+        })?
+        .and_then(|config_map| config_map.data)
+        .and_then(|mut data| data.remove("KAFKA")))
+}
+
+/// Applies an admin Job, which runs the given commands against the cluster of the user.
+async fn apply_admin_job(
+    user: &KafkaUser,
+    kafka: &KafkaCluster,
+    ctx: &Ctx,
+    job_name: &str,
+    commands: &str,
+    env: Vec<EnvVar>,
+) -> Result<AdminJobState> {
+    let client = &ctx.client;
+    let kafka_security = KafkaTlsSecurity::new_from_kafka_cluster(client, kafka)
+        .await
+        .context(FailedToInitializeSecurityContextSnafu)?;
+
+    let job = build_admin_job(
+        kafka,
+        user,
+        USER_CONTROLLER_NAME,
+        job_name,
+        commands,
+        env,
+        &kafka_security,
+        &client.kubernetes_cluster_info,
+    )
+    .context(BuildAdminJobSnafu)?;
+    let job: Job = client
+        .apply_patch(OPERATOR_NAME, &job, &job)
+        .await
+        .context(ApplyAdminJobSnafu)?;
+
+    Ok(admin_job_state(&job))
+}
+
+async fn update_status(
+    user: &KafkaUser,
+    ctx: &Ctx,
+    sync_state: SyncState,
+    message: Option<String>,
+    password_hash: Option<String>,
+) -> Result<()> {
+    let status = KafkaUserStatus {
+        sync: SyncStatus::new(
+            sync_state,
+            user.metadata.generation,
+            message,
+            user.status.as_ref().map(|status| &status.sync),
+        ),
+        password_hash,
+    };
+    ctx.client
+        .apply_patch_status(OPERATOR_NAME, user, &status)
+        .await
+        .context(ApplyStatusSnafu)?;
+
+    Ok(())
+}
+
+pub fn error_policy(
+    _obj: Arc<DeserializeGuard<KafkaUser>>,
+    error: &Error,
+    _ctx: Arc<Ctx>,
+) -> Action {
+    match error {
+        Error::InvalidKafkaUser { .. } => Action::await_change(),
+        _ => Action::requeue(*Duration::from_secs(5)),
+    }
+}
@@ -1,5 +1,14 @@
+use std::{fs::File, io::Read};
+
 use stackable_kafka_crd::{KafkaCluster, APP_NAME, OPERATOR_NAME};
-use stackable_operator::kvp::ObjectLabels;
+use stackable_operator::{
+    kube::{Resource, ResourceExt},
+    kvp::ObjectLabels,
+};
+
+const PASSWORD_LENGTH: usize = 32;
+const PASSWORD_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
 /// Build recommended values for labels
 pub fn build_recommended_labels<'a>(
@@ -19,3 +28,25 @@ pub fn build_recommended_labels<'a>(
         role_group,
     }
 }
+
+/// Generates a random password from the kernel's random number generator.
+pub fn generate_password() -> std::io::Result<String> {
+    let mut bytes = [0u8; PASSWORD_LENGTH];
+    File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+
+    Ok(bytes
+        .iter()
+        .map(|byte| char::from(PASSWORD_ALPHABET[usize::from(byte & 0x3f)]))
+        .collect())
+}
+
+/// Whether `object` has an owner reference to `owner`. Objects with the expected name, which were
+/// created by someone else, must not be taken over.
+pub fn is_owned_by(object: &impl Resource, owner: &impl Resource) -> bool {
+    owner.meta().uid.as_ref().is_some_and(|uid| {
+        object
+            .owner_references()
+            .iter()
+            .any(|owner_reference| &owner_reference.uid == uid)
+    })
+}